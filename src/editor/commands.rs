@@ -0,0 +1,348 @@
+pub mod commands {
+    use super::super::cursor::cursor::Cursor;
+    use super::super::kill_ring::kill_ring::KillRing;
+    use std::fmt;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Command {
+        CursorLeft,
+        CursorRight,
+        CursorUp,
+        CursorDown,
+        CursorHome,
+        CursorEnd,
+        SelectLeft,
+        SelectRight,
+        SelectUp,
+        SelectDown,
+        SelectHome,
+        SelectEnd,
+        SelectAll,
+        EditDelete,
+        EditBackspace,
+        EditNewLine,
+        EditToggleOverwrite,
+        MarkSet,
+        MarkCancel,
+        KillLine,
+        KillRegion,
+        CopyRegion,
+        Yank,
+        YankPop,
+        FileSave,
+        FileExportSvg,
+        ThemeNext,
+        ViewZoomIn,
+        ViewZoomOut,
+        ViewZoomReset,
+        ViewToggleFrameTime,
+        FontEditGlyph,
+        AppQuit,
+    }
+
+    static COMMAND_NAMES: [(Command, &str); 33] = [
+        (Command::CursorLeft, "cursor.left"),
+        (Command::CursorRight, "cursor.right"),
+        (Command::CursorUp, "cursor.up"),
+        (Command::CursorDown, "cursor.down"),
+        (Command::CursorHome, "cursor.home"),
+        (Command::CursorEnd, "cursor.end"),
+        (Command::SelectLeft, "select.left"),
+        (Command::SelectRight, "select.right"),
+        (Command::SelectUp, "select.up"),
+        (Command::SelectDown, "select.down"),
+        (Command::SelectHome, "select.home"),
+        (Command::SelectEnd, "select.end"),
+        (Command::SelectAll, "select.all"),
+        (Command::EditDelete, "edit.delete"),
+        (Command::EditBackspace, "edit.backspace"),
+        (Command::EditNewLine, "edit.new_line"),
+        (Command::EditToggleOverwrite, "edit.toggle_overwrite"),
+        (Command::MarkSet, "mark.set"),
+        (Command::MarkCancel, "mark.cancel"),
+        (Command::KillLine, "kill.line"),
+        (Command::KillRegion, "kill.region"),
+        (Command::CopyRegion, "copy.region"),
+        (Command::Yank, "yank"),
+        (Command::YankPop, "yank.pop"),
+        (Command::FileSave, "file.save"),
+        (Command::FileExportSvg, "file.export_svg"),
+        (Command::ThemeNext, "theme.next"),
+        (Command::ViewZoomIn, "view.zoom_in"),
+        (Command::ViewZoomOut, "view.zoom_out"),
+        (Command::ViewZoomReset, "view.zoom_reset"),
+        (Command::ViewToggleFrameTime, "view.toggle_frame_time"),
+        (Command::FontEditGlyph, "font.edit_glyph"),
+        (Command::AppQuit, "app.quit"),
+    ];
+
+    impl Command {
+        pub fn from_name(name: &str) -> Option<Command> {
+            COMMAND_NAMES
+                .iter()
+                .find(|(_, command_name)| *command_name == name)
+                .map(|(command, _)| *command)
+        }
+        pub fn name(self) -> &'static str {
+            COMMAND_NAMES
+                .iter()
+                .find(|(command, _)| *command == self)
+                .map(|(_, name)| *name)
+                .unwrap()
+        }
+        /// Whether holding the key down should run the command again.
+        pub fn is_repeatable(self) -> bool {
+            !matches!(
+                self,
+                Command::SelectAll
+                    | Command::EditToggleOverwrite
+                    | Command::MarkSet
+                    | Command::Yank
+                    | Command::YankPop
+                    | Command::FileSave
+                    | Command::FileExportSvg
+                    | Command::ThemeNext
+                    | Command::ViewZoomReset
+                    | Command::ViewToggleFrameTime
+                    | Command::FontEditGlyph
+                    | Command::AppQuit
+            )
+        }
+    }
+
+    impl fmt::Display for Command {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "{}", self.name())
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum Outcome {
+        Continue,
+        /// The frontend should switch to the next color theme.
+        NextTheme,
+        Zoom(Zoom),
+        /// The frontend should show or hide how long frames take to draw.
+        ToggleFrameTime,
+        /// The frontend should open the glyph editor for the character.
+        EditGlyph(char),
+        /// The frontend should write the selection, or the whole buffer
+        /// without one, as an SVG file.
+        ExportSvg,
+        /// The buffer was written to its file, or why it could not be, for
+        /// the frontend to show.
+        Saved(Result<(), String>),
+        Quit,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum Zoom {
+        In,
+        Out,
+        Reset,
+    }
+
+    type Position = (usize, usize);
+
+    /// The document state commands operate on, independent of any frontend.
+    #[derive(Debug)]
+    pub struct Editor {
+        pub cursor: Cursor,
+        pub file_path: String,
+        pub kill_ring: KillRing,
+        /// While the mark is active, `current` stays at the mark and
+        /// movement extends the selection from there.
+        pub is_mark_active: bool,
+        /// Typing replaces the character under the caret.
+        pub is_overwrite: bool,
+        last_command: Option<Command>,
+        yanked: Option<(Position, Position)>,
+    }
+
+    impl Editor {
+        pub fn new(cursor: Cursor, file_path: &str) -> Editor {
+            Editor {
+                cursor,
+                file_path: file_path.to_string(),
+                kill_ring: KillRing::new(),
+                is_mark_active: false,
+                is_overwrite: false,
+                last_command: None,
+                yanked: None,
+            }
+        }
+        pub fn from_file(file_path: &str) -> Editor {
+            Editor::new(Cursor::from_file(file_path), file_path)
+        }
+        pub fn execute(&mut self, command: Command) -> Outcome {
+            let outcome = self.run(command);
+            self.last_command = Some(command);
+            outcome
+        }
+        fn run(&mut self, command: Command) -> Outcome {
+            let select = self.is_mark_active;
+            let cursor = &mut self.cursor;
+            match command {
+                Command::CursorLeft => cursor.left(select),
+                Command::CursorRight => cursor.right(select),
+                Command::CursorUp => cursor.up(select),
+                Command::CursorDown => cursor.down(select),
+                Command::CursorHome => cursor.home(select),
+                Command::CursorEnd => cursor.end(select),
+                Command::SelectLeft => cursor.left(true),
+                Command::SelectRight => cursor.right(true),
+                Command::SelectUp => cursor.up(true),
+                Command::SelectDown => cursor.down(true),
+                Command::SelectHome => cursor.home(true),
+                Command::SelectEnd => cursor.end(true),
+                Command::SelectAll => cursor.select_all(),
+                Command::EditDelete => self.edit(Cursor::delete),
+                Command::EditBackspace => self.edit(Cursor::backspace),
+                Command::EditNewLine => self.edit(Cursor::new_line),
+                Command::EditToggleOverwrite => self.is_overwrite = !self.is_overwrite,
+                Command::MarkSet => {
+                    let (line, column) = get_position(cursor);
+                    cursor.move_to(line, column, false);
+                    self.is_mark_active = true;
+                }
+                Command::MarkCancel => self.deactivate_mark(),
+                Command::KillLine => self.kill_line(),
+                Command::KillRegion => {
+                    if cursor.current != cursor.extender {
+                        let text = cursor.selected_text();
+                        cursor.delete();
+                        let (line, column) = get_position(cursor);
+                        cursor.move_to(line, column, false);
+                        self.kill_ring.push(text);
+                    }
+                    self.is_mark_active = false;
+                }
+                Command::CopyRegion => {
+                    let text = cursor.selected_text();
+                    self.kill_ring.push(text);
+                    self.deactivate_mark();
+                }
+                Command::Yank => self.yank(),
+                Command::YankPop => self.yank_pop(),
+                Command::FileSave => return Outcome::Saved(self.save()),
+                Command::FileExportSvg => return Outcome::ExportSvg,
+                Command::ThemeNext => return Outcome::NextTheme,
+                Command::ViewZoomIn => return Outcome::Zoom(Zoom::In),
+                Command::ViewZoomOut => return Outcome::Zoom(Zoom::Out),
+                Command::ViewZoomReset => return Outcome::Zoom(Zoom::Reset),
+                Command::ViewToggleFrameTime => return Outcome::ToggleFrameTime,
+                Command::FontEditGlyph => {
+                    if let Some(character) = self.character_at_cursor() {
+                        return Outcome::EditGlyph(character);
+                    }
+                }
+                Command::AppQuit => return Outcome::Quit,
+            }
+            Outcome::Continue
+        }
+        /// Types a character, replacing the selection or, when overwriting,
+        /// the character under the caret. An active mark is dropped first
+        /// so the marked text stays.
+        pub fn add(&mut self, character: char) {
+            if self.is_mark_active {
+                self.deactivate_mark();
+            }
+            let cursor = &mut self.cursor;
+            let line_length = cursor.lines[cursor.current.line].chars().count();
+            if self.is_overwrite
+                && cursor.current == cursor.extender
+                && cursor.current.column < line_length
+            {
+                cursor.delete();
+            }
+            self.cursor.add(character);
+            self.is_mark_active = false;
+            self.last_command = None;
+        }
+        /// Types everything an input method committed at once, starting a
+        /// new line at line breaks.
+        pub fn add_text(&mut self, text: &str) {
+            for character in text.chars() {
+                match character {
+                    '\n' => self.edit(Cursor::new_line),
+                    '\r' => {}
+                    _ => self.add(character),
+                }
+            }
+        }
+        /// The character under the caret, or before it at the end of a line.
+        fn character_at_cursor(&self) -> Option<char> {
+            let position = &self.cursor.current;
+            let line = &self.cursor.lines[position.line];
+            line.chars()
+                .nth(position.column)
+                .or_else(|| line.chars().last())
+        }
+        fn edit(&mut self, edit: fn(&mut Cursor)) {
+            edit(&mut self.cursor);
+            self.is_mark_active = false;
+        }
+        fn deactivate_mark(&mut self) {
+            let (line, column) = get_position(&self.cursor);
+            self.cursor.move_to(line, column, false);
+            self.is_mark_active = false;
+        }
+        /// Kills to the end of the line, or the line break when already
+        /// there. Consecutive kills are collected into one kill ring entry.
+        fn kill_line(&mut self) {
+            let cursor = &mut self.cursor;
+            let (line, column) = get_position(cursor);
+            let length = cursor.lines[line].chars().count();
+            let (to_line, to_column) = if column < length {
+                (line, length)
+            } else if line + 1 < cursor.lines.len() {
+                (line + 1, 0)
+            } else {
+                return;
+            };
+            cursor.move_to(line, column, false);
+            cursor.move_to(to_line, to_column, true);
+            let text = cursor.selected_text();
+            cursor.delete();
+            cursor.move_to(line, column, false);
+            self.is_mark_active = false;
+            if self.last_command == Some(Command::KillLine) {
+                self.kill_ring.append(&text);
+            } else {
+                self.kill_ring.push(text);
+            }
+        }
+        fn yank(&mut self) {
+            if let Some(text) = self.kill_ring.current() {
+                let start = get_position(&self.cursor);
+                self.cursor.insert(text);
+                self.yanked = Some((start, get_position(&self.cursor)));
+                self.is_mark_active = false;
+            }
+        }
+        /// Replaces the text just yanked with the next older kill.
+        fn yank_pop(&mut self) {
+            let is_after_yank = matches!(
+                self.last_command,
+                Some(Command::Yank) | Some(Command::YankPop)
+            );
+            if let (true, Some((start, end))) = (is_after_yank, self.yanked) {
+                if let Some(text) = self.kill_ring.rotate() {
+                    self.cursor.move_to(start.0, start.1, false);
+                    self.cursor.move_to(end.0, end.1, true);
+                    self.cursor.insert(text);
+                    self.yanked = Some((start, get_position(&self.cursor)));
+                }
+            }
+        }
+        pub fn save(&mut self) -> Result<(), String> {
+            self.cursor
+                .to_file(&self.file_path)
+                .map_err(|error| error.to_string())
+        }
+    }
+
+    fn get_position(cursor: &Cursor) -> Position {
+        (cursor.extender.line, cursor.extender.column)
+    }
+}
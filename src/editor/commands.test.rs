@@ -0,0 +1,316 @@
+#[path = "commands.rs"]
+mod commands;
+
+#[path = "cursor.rs"]
+mod cursor;
+
+#[path = "kill_ring.rs"]
+mod kill_ring;
+
+#[cfg(test)]
+mod tests {
+    use super::commands::commands::{Command, Editor, Outcome, Zoom};
+    use super::cursor::cursor::Cursor;
+    use std::env;
+    use std::fs;
+    fn editor(lines: Vec<&str>) -> Editor {
+        Editor::new(
+            Cursor::new(lines.into_iter().map(String::from).collect()),
+            "",
+        )
+    }
+    #[test]
+    fn names_roundtrip() {
+        for name in &["cursor.left", "select.all", "file.save", "edit.new_line"] {
+            assert_eq!(Command::from_name(name).unwrap().name(), *name);
+        }
+        assert_eq!(Command::from_name("cursor.sideways"), None);
+    }
+    #[test]
+    fn repeatable() {
+        assert!(Command::CursorLeft.is_repeatable());
+        assert!(!Command::FileSave.is_repeatable());
+    }
+    #[test]
+    fn cursor_movement() {
+        let mut editor = editor(vec!["ab", "c"]);
+        editor.execute(Command::CursorRight);
+        editor.execute(Command::CursorDown);
+        assert_eq!(editor.cursor.current.line, 1);
+        assert_eq!(editor.cursor.current.column, 1);
+        editor.execute(Command::CursorHome);
+        assert_eq!(editor.cursor.current.column, 0);
+    }
+    #[test]
+    fn selection() {
+        let mut editor = editor(vec!["abc"]);
+        editor.execute(Command::SelectRight);
+        editor.execute(Command::SelectRight);
+        assert_eq!(editor.cursor.current.column, 0);
+        assert_eq!(editor.cursor.extender.column, 2);
+        editor.execute(Command::EditBackspace);
+        assert_eq!(editor.cursor.lines, vec![String::from("c")]);
+    }
+    #[test]
+    fn overwrite_replaces_the_character_under_the_caret() {
+        let mut editor = editor(vec!["abc"]);
+        editor.execute(Command::EditToggleOverwrite);
+        assert!(editor.is_overwrite);
+        editor.add('x');
+        editor.execute(Command::CursorEnd);
+        editor.add('y');
+        assert_eq!(editor.cursor.lines, vec![String::from("xbcy")]);
+        editor.execute(Command::SelectHome);
+        editor.add('z');
+        assert_eq!(editor.cursor.lines, vec![String::from("z")]);
+        editor.execute(Command::EditToggleOverwrite);
+        editor.execute(Command::CursorHome);
+        editor.add('w');
+        assert_eq!(editor.cursor.lines, vec![String::from("wz")]);
+    }
+    #[test]
+    fn committed_text_is_typed_whole() {
+        let mut editor = editor(vec!["ab"]);
+        editor.execute(Command::CursorRight);
+        editor.add_text("日本語");
+        assert_eq!(editor.cursor.lines, vec![String::from("a日本語b")]);
+        assert_eq!(editor.cursor.current.column, 4);
+        editor.add_text("x\r\ny");
+        assert_eq!(
+            editor.cursor.lines,
+            vec![String::from("a日本語x"), String::from("yb")]
+        );
+    }
+    #[test]
+    fn select_all_and_delete() {
+        let mut editor = editor(vec!["ab", "cd"]);
+        editor.execute(Command::SelectAll);
+        editor.execute(Command::EditDelete);
+        assert_eq!(editor.cursor.lines, vec![String::from("")]);
+    }
+    #[test]
+    fn new_line() {
+        let mut editor = editor(vec!["ab"]);
+        editor.execute(Command::CursorRight);
+        editor.execute(Command::EditNewLine);
+        assert_eq!(
+            editor.cursor.lines,
+            vec![String::from("a"), String::from("b")]
+        );
+    }
+    #[test]
+    fn quit() {
+        let mut editor = editor(vec![""]);
+        assert_eq!(editor.execute(Command::CursorLeft), Outcome::Continue);
+        assert_eq!(editor.execute(Command::AppQuit), Outcome::Quit);
+    }
+    #[test]
+    fn zoom_is_left_to_the_frontend() {
+        let mut editor = editor(vec!["a"]);
+        assert_eq!(editor.execute(Command::ViewZoomIn), Outcome::Zoom(Zoom::In));
+        assert_eq!(
+            editor.execute(Command::ViewZoomOut),
+            Outcome::Zoom(Zoom::Out)
+        );
+        assert_eq!(
+            editor.execute(Command::ViewZoomReset),
+            Outcome::Zoom(Zoom::Reset)
+        );
+        assert_eq!(
+            editor.execute(Command::ViewToggleFrameTime),
+            Outcome::ToggleFrameTime
+        );
+    }
+    #[test]
+    fn theme_next_is_left_to_the_frontend() {
+        let mut editor = editor(vec!["a"]);
+        assert_eq!(editor.execute(Command::ThemeNext), Outcome::NextTheme);
+        assert_eq!(editor.cursor.lines, vec!["a"]);
+    }
+    #[test]
+    fn edit_glyph_opens_the_character_at_the_caret() {
+        let mut editor = editor(vec!["ab", ""]);
+        editor.execute(Command::CursorRight);
+        assert_eq!(
+            editor.execute(Command::FontEditGlyph),
+            Outcome::EditGlyph('b')
+        );
+        editor.execute(Command::CursorEnd);
+        assert_eq!(
+            editor.execute(Command::FontEditGlyph),
+            Outcome::EditGlyph('b')
+        );
+        editor.execute(Command::CursorDown);
+        assert_eq!(editor.execute(Command::FontEditGlyph), Outcome::Continue);
+    }
+    #[test]
+    fn export_svg_is_left_to_the_frontend() {
+        let mut editor = editor(vec!["a"]);
+        assert_eq!(editor.execute(Command::FileExportSvg), Outcome::ExportSvg);
+        assert_eq!(
+            Command::from_name("file.export_svg"),
+            Some(Command::FileExportSvg)
+        );
+    }
+    #[test]
+    fn save() {
+        let path = env::temp_dir().join("editor_commands_test_save");
+        let mut editor = editor(vec!["a", "b"]);
+        editor.file_path = path.to_str().unwrap().to_string();
+        assert_eq!(editor.execute(Command::FileSave), Outcome::Saved(Ok(())));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a\nb");
+        fs::remove_file(&path).unwrap();
+        editor.file_path = env::temp_dir().to_str().unwrap().to_string();
+        assert!(editor.save().is_err(), "a directory is not a file");
+    }
+    fn position(editor: &Editor) -> (usize, usize) {
+        (editor.cursor.extender.line, editor.cursor.extender.column)
+    }
+    #[test]
+    fn mark_extends_selection_with_movement() {
+        let mut editor = editor(vec!["abc", "def"]);
+        editor.execute(Command::CursorRight);
+        editor.execute(Command::MarkSet);
+        editor.execute(Command::CursorRight);
+        editor.execute(Command::CursorDown);
+        assert_eq!(editor.cursor.selected_text(), "bc\nde");
+        editor.execute(Command::MarkCancel);
+        assert_eq!(editor.cursor.current, editor.cursor.extender);
+        assert_eq!(position(&editor), (1, 2));
+    }
+    #[test]
+    fn typing_deactivates_mark() {
+        let mut editor = editor(vec!["abc"]);
+        editor.execute(Command::MarkSet);
+        editor.execute(Command::CursorRight);
+        editor.add('x');
+        assert!(!editor.is_mark_active);
+        editor.execute(Command::CursorRight);
+        assert_eq!(editor.cursor.current, editor.cursor.extender);
+    }
+    #[test]
+    fn typing_with_an_active_mark_keeps_the_marked_text() {
+        let mut editor = editor(vec!["abc"]);
+        editor.execute(Command::MarkSet);
+        editor.execute(Command::CursorRight);
+        editor.execute(Command::CursorRight);
+        editor.add('x');
+        assert_eq!(editor.cursor.lines, vec![String::from("abxc")]);
+        assert_eq!(position(&editor), (0, 3));
+    }
+    #[test]
+    fn kill_line() {
+        let mut editor = editor(vec!["abc", "def"]);
+        editor.execute(Command::CursorRight);
+        editor.execute(Command::KillLine);
+        assert_eq!(
+            editor.cursor.lines,
+            vec![String::from("a"), String::from("def")]
+        );
+        assert_eq!(editor.kill_ring.current(), Some("bc"));
+    }
+    #[test]
+    fn kill_line_at_end_joins_lines() {
+        let mut editor = editor(vec!["abc", "def"]);
+        editor.execute(Command::CursorEnd);
+        editor.execute(Command::KillLine);
+        assert_eq!(editor.cursor.lines, vec![String::from("abcdef")]);
+        assert_eq!(editor.kill_ring.current(), Some("\n"));
+    }
+    #[test]
+    fn consecutive_kills_are_collected() {
+        let mut editor = editor(vec!["abc", "def", "ghi"]);
+        editor.execute(Command::KillLine);
+        editor.execute(Command::KillLine);
+        editor.execute(Command::KillLine);
+        assert_eq!(
+            editor.cursor.lines,
+            vec![String::from(""), String::from("ghi")]
+        );
+        assert_eq!(editor.kill_ring.current(), Some("abc\ndef"));
+    }
+    #[test]
+    fn kill_line_on_last_line_end() {
+        let mut editor = editor(vec!["abc"]);
+        editor.execute(Command::CursorEnd);
+        editor.execute(Command::KillLine);
+        assert_eq!(editor.cursor.lines, vec![String::from("abc")]);
+        assert_eq!(editor.kill_ring.current(), None);
+    }
+    #[test]
+    fn kill_region_and_yank() {
+        let mut editor = editor(vec!["abcd"]);
+        editor.execute(Command::CursorRight);
+        editor.execute(Command::MarkSet);
+        editor.execute(Command::CursorRight);
+        editor.execute(Command::CursorRight);
+        editor.execute(Command::KillRegion);
+        assert_eq!(editor.cursor.lines, vec![String::from("ad")]);
+        assert!(!editor.is_mark_active);
+        editor.execute(Command::CursorEnd);
+        editor.execute(Command::Yank);
+        assert_eq!(editor.cursor.lines, vec![String::from("adbc")]);
+    }
+    #[test]
+    fn kill_region_without_selection() {
+        let mut editor = editor(vec!["abc"]);
+        editor.execute(Command::KillRegion);
+        assert_eq!(editor.cursor.lines, vec![String::from("abc")]);
+    }
+    #[test]
+    fn copy_region() {
+        let mut editor = editor(vec!["abc"]);
+        editor.execute(Command::MarkSet);
+        editor.execute(Command::CursorEnd);
+        editor.execute(Command::CopyRegion);
+        assert_eq!(editor.cursor.lines, vec![String::from("abc")]);
+        assert_eq!(editor.cursor.current, editor.cursor.extender);
+        editor.execute(Command::Yank);
+        assert_eq!(editor.cursor.lines, vec![String::from("abcabc")]);
+    }
+    #[test]
+    fn yank_pop_cycles_kill_ring() {
+        let mut editor = editor(vec!["a", "b", "c", ""]);
+        editor.execute(Command::KillLine);
+        editor.execute(Command::CursorDown);
+        editor.execute(Command::KillLine);
+        editor.execute(Command::CursorDown);
+        editor.execute(Command::KillLine);
+        editor.execute(Command::CursorDown);
+        editor.execute(Command::Yank);
+        assert_eq!(editor.cursor.lines[3], "c");
+        editor.execute(Command::YankPop);
+        assert_eq!(editor.cursor.lines[3], "b");
+        editor.execute(Command::YankPop);
+        assert_eq!(editor.cursor.lines[3], "a");
+        editor.execute(Command::YankPop);
+        assert_eq!(editor.cursor.lines[3], "c");
+    }
+    #[test]
+    fn yank_pop_only_after_yank() {
+        let mut editor = editor(vec!["a", "b"]);
+        editor.execute(Command::KillLine);
+        editor.execute(Command::CursorDown);
+        editor.execute(Command::KillLine);
+        editor.execute(Command::Yank);
+        editor.execute(Command::CursorHome);
+        editor.execute(Command::YankPop);
+        assert_eq!(
+            editor.cursor.lines,
+            vec![String::from(""), String::from("b")]
+        );
+    }
+    #[test]
+    fn yank_pop_multiple_lines() {
+        let mut editor = editor(vec!["x"]);
+        editor.kill_ring.push(String::from("a"));
+        editor.kill_ring.push(String::from("b\nc"));
+        editor.execute(Command::Yank);
+        assert_eq!(
+            editor.cursor.lines,
+            vec![String::from("b"), String::from("cx")]
+        );
+        editor.execute(Command::YankPop);
+        assert_eq!(editor.cursor.lines, vec![String::from("ax")]);
+    }
+}
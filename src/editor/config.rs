@@ -0,0 +1,938 @@
+pub mod config {
+    use super::super::commands::commands::Command;
+    use super::super::font::font::Font;
+    use super::super::keymap::keymap::Keymap;
+    use super::super::theme::theme::{find_theme, Theme};
+    use sdl2::pixels::Color;
+    use std::convert::TryFrom;
+    use std::env;
+    use std::fmt;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Value {
+        Integer(i64),
+        Float(f64),
+        Boolean(bool),
+        String(String),
+        Array(Vec<Value>),
+    }
+
+    impl Value {
+        fn type_name(&self) -> &'static str {
+            match self {
+                Value::Integer(_) => "an integer",
+                Value::Float(_) => "a float",
+                Value::Boolean(_) => "a boolean",
+                Value::String(_) => "a string",
+                Value::Array(_) => "an array",
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Entry {
+        pub line: usize,
+        pub section: String,
+        pub key: String,
+        pub value: Value,
+    }
+
+    impl Entry {
+        pub fn full_key(&self) -> String {
+            if self.section.is_empty() {
+                self.key.to_string()
+            } else {
+                format!("{}.{}", self.section, self.key)
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct ConfigError {
+        pub line: usize,
+        pub key: String,
+        pub message: String,
+    }
+
+    impl ConfigError {
+        pub fn new(line: usize, key: &str, message: &str) -> ConfigError {
+            ConfigError {
+                line,
+                key: key.to_string(),
+                message: message.to_string(),
+            }
+        }
+        pub fn for_entry(entry: &Entry, message: &str) -> ConfigError {
+            ConfigError::new(entry.line, &entry.full_key(), message)
+        }
+    }
+
+    impl fmt::Display for ConfigError {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            if self.key.is_empty() {
+                write!(formatter, "line {}: {}", self.line, self.message)
+            } else {
+                write!(
+                    formatter,
+                    "line {}: \"{}\": {}",
+                    self.line, self.key, self.message
+                )
+            }
+        }
+    }
+
+    /// Parses the subset of TOML the editor needs: `[section]` headers,
+    /// `key = value` pairs with strings, integers, floats, booleans and
+    /// single-line arrays, and `#` comments.
+    pub fn parse(content: &str) -> Result<Vec<Entry>, ConfigError> {
+        let mut entries: Vec<Entry> = vec![];
+        let mut section = String::new();
+        for (line_index, raw_line) in content.lines().enumerate() {
+            let line_number = line_index + 1;
+            let line = strip_comment(raw_line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with('[') {
+                if !line.ends_with(']') {
+                    return Err(ConfigError::new(
+                        line_number,
+                        "",
+                        "section header is missing its closing \"]\"",
+                    ));
+                }
+                section = line[1..line.len() - 1].trim().to_string();
+                if section.is_empty() {
+                    return Err(ConfigError::new(line_number, "", "empty section name"));
+                }
+                continue;
+            }
+            let (key, rest) = parse_key(line)
+                .ok_or_else(|| ConfigError::new(line_number, "", "expected a key"))?;
+            let full_key = if section.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", section, key)
+            };
+            let rest = rest.trim_start();
+            if !rest.starts_with('=') {
+                return Err(ConfigError::new(
+                    line_number,
+                    &full_key,
+                    "expected \"=\" after the key",
+                ));
+            }
+            let (value, remaining) = parse_value(rest[1..].trim_start())
+                .map_err(|message| ConfigError::new(line_number, &full_key, &message))?;
+            if !remaining.trim().is_empty() {
+                return Err(ConfigError::new(
+                    line_number,
+                    &full_key,
+                    &format!("unexpected \"{}\" after the value", remaining.trim()),
+                ));
+            }
+            if let Some(previous) = entries
+                .iter()
+                .find(|entry| entry.section == section && entry.key == key)
+            {
+                return Err(ConfigError::new(
+                    line_number,
+                    &full_key,
+                    &format!("already defined on line {}", previous.line),
+                ));
+            }
+            entries.push(Entry {
+                line: line_number,
+                section: section.to_string(),
+                key,
+                value,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Replaces the line of `key` in `[section]`, or adds it to the
+    /// section, keeping everything else as it is.
+    pub fn set_value(
+        content: &str,
+        section: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<String, ConfigError> {
+        let entry_line = format!("{} = {}", key, value);
+        let mut lines = content.lines().map(String::from).collect::<Vec<String>>();
+        let existing = parse(content)?
+            .into_iter()
+            .find(|entry| entry.section == section && entry.key == key);
+        let is_header = |line: &String| {
+            let line = strip_comment(line).trim();
+            line.starts_with('[')
+                && line.ends_with(']')
+                && line[1..line.len() - 1].trim() == section
+        };
+        if let Some(entry) = existing {
+            lines[entry.line - 1] = entry_line;
+        } else if let Some(index) = lines.iter().position(is_header) {
+            lines.insert(index + 1, entry_line);
+        } else {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push(format!("[{}]", section));
+            lines.push(entry_line);
+        }
+        Ok(lines.join("\n") + "\n")
+    }
+
+    /// `set_value` for each `(section, key, value)` in turn.
+    pub fn set_values(content: &str, values: &[(&str, &str, &str)]) -> Result<String, ConfigError> {
+        let mut content = content.to_string();
+        for (section, key, value) in values {
+            content = set_value(&content, section, key, value)?;
+        }
+        Ok(content)
+    }
+
+    fn strip_comment(line: &str) -> &str {
+        let mut in_string = false;
+        let mut escaped = false;
+        for (index, character) in line.char_indices() {
+            match character {
+                '\\' if in_string => {
+                    escaped = !escaped;
+                    continue;
+                }
+                '"' if !escaped => in_string = !in_string,
+                '#' if !in_string => return &line[..index],
+                _ => {}
+            }
+            escaped = false;
+        }
+        line
+    }
+
+    fn parse_key(line: &str) -> Option<(String, &str)> {
+        if line.starts_with('"') {
+            let (value, rest) = parse_string(line).ok()?;
+            if let Value::String(key) = value {
+                return Some((key, rest));
+            }
+            return None;
+        }
+        let end = line
+            .find(|character: char| {
+                !(character.is_alphanumeric() || character == '_' || character == '-')
+            })
+            .unwrap_or(line.len());
+        if end == 0 {
+            None
+        } else {
+            Some((line[..end].to_string(), &line[end..]))
+        }
+    }
+
+    fn parse_value(input: &str) -> Result<(Value, &str), String> {
+        if input.starts_with('"') {
+            parse_string(input)
+        } else if input.starts_with('[') {
+            parse_array(input)
+        } else {
+            let end = input.find([',', ']', ' ']).unwrap_or(input.len());
+            let word = &input[..end];
+            let rest = &input[end..];
+            match word {
+                "" => Err("expected a value".to_string()),
+                "true" => Ok((Value::Boolean(true), rest)),
+                "false" => Ok((Value::Boolean(false), rest)),
+                _ => {
+                    let number = word.replace('_', "");
+                    if let Ok(integer) = number.parse::<i64>() {
+                        Ok((Value::Integer(integer), rest))
+                    } else if let Ok(float) = number.parse::<f64>() {
+                        Ok((Value::Float(float), rest))
+                    } else {
+                        Err(format!("\"{}\" is not a valid value", word))
+                    }
+                }
+            }
+        }
+    }
+
+    fn parse_string(input: &str) -> Result<(Value, &str), String> {
+        let mut result = String::new();
+        let mut characters = input.char_indices().skip(1);
+        while let Some((index, character)) = characters.next() {
+            match character {
+                '"' => return Ok((Value::String(result), &input[index + 1..])),
+                '\\' => match characters.next() {
+                    Some((_, 'n')) => result.push('\n'),
+                    Some((_, 't')) => result.push('\t'),
+                    Some((_, '"')) => result.push('"'),
+                    Some((_, '\\')) => result.push('\\'),
+                    Some((_, other)) => return Err(format!("unknown escape \"\\{}\"", other)),
+                    None => break,
+                },
+                _ => result.push(character),
+            }
+        }
+        Err("string is missing its closing quote".to_string())
+    }
+
+    fn parse_array(input: &str) -> Result<(Value, &str), String> {
+        let mut values = vec![];
+        let mut rest = input[1..].trim_start();
+        loop {
+            if let Some(after) = rest.strip_prefix(']') {
+                return Ok((Value::Array(values), after));
+            }
+            let (value, remaining) = parse_value(rest)?;
+            values.push(value);
+            rest = remaining.trim_start();
+            if let Some(after) = rest.strip_prefix(',') {
+                rest = after.trim_start();
+            } else if !rest.starts_with(']') {
+                return Err("array is missing its closing \"]\"".to_string());
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Layout {
+        pub character_width: i32,
+        pub character_height: i32,
+        pub line_gap: i32,
+        pub character_gap: i32,
+        pub x_offset: i32,
+        pub y_offset: i32,
+    }
+
+    impl Default for Layout {
+        fn default() -> Layout {
+            Layout::from_character_size(10, 16)
+        }
+    }
+
+    impl Layout {
+        pub fn from_character_size(character_width: i32, character_height: i32) -> Layout {
+            Layout {
+                character_width,
+                character_height,
+                line_gap: character_height / 3,
+                character_gap: character_width / 3,
+                x_offset: 10,
+                y_offset: 5,
+            }
+        }
+
+        /// Every size scaled to `zoom` percent, keeping glyphs at least a pixel.
+        pub fn zoomed(&self, zoom: u32) -> Layout {
+            let scale = |size: i32| size * zoom as i32 / 100;
+            Layout {
+                character_width: scale(self.character_width).max(1),
+                character_height: scale(self.character_height).max(1),
+                line_gap: scale(self.line_gap),
+                character_gap: scale(self.character_gap),
+                x_offset: scale(self.x_offset),
+                y_offset: scale(self.y_offset),
+            }
+        }
+    }
+
+    /// The left edge of the cell at `column_index`.
+    pub fn get_character_x(layout: &Layout, column_index: i32) -> i32 {
+        column_index * (layout.character_gap + layout.character_width) + layout.x_offset
+    }
+
+    /// The top edge of the cell at `line_index`, counted from the camera.
+    pub fn get_character_y(layout: &Layout, line_index: i32) -> i32 {
+        line_index * (layout.line_gap + layout.character_height) + layout.y_offset
+    }
+
+    /// Drawable pixels per window pixel, 1 on ordinary displays.
+    pub fn get_display_scale(drawable_width: u32, window_width: u32) -> f32 {
+        if window_width == 0 || drawable_width == 0 {
+            1.0
+        } else {
+            drawable_width as f32 / window_width as f32
+        }
+    }
+
+    /// The zoom in percent that also makes up for the display scale.
+    pub fn get_scaled_zoom(zoom: u32, scale: f32) -> u32 {
+        ((zoom as f32 * scale).round() as u32).max(1)
+    }
+
+    /// Strokes `width` window pixels wide at 100% in drawable pixels at
+    /// `zoom` percent, which includes the display scale.
+    pub fn get_stroke_width(width: u32, zoom: u32) -> u32 {
+        ((width as f32 * zoom as f32 / 100.0).round() as u32).max(1)
+    }
+
+    /// How many columns a window `width` pixels wide shows, at least one.
+    pub fn get_columns_in_width(layout: &Layout, width: u32) -> u32 {
+        let column_width = (layout.character_width + layout.character_gap).max(1) as u32;
+        (width / column_width).max(1)
+    }
+
+    static ZOOM_LEVELS: [u32; 13] = [50, 67, 75, 80, 90, 100, 110, 125, 150, 175, 200, 250, 300];
+
+    /// The next larger zoom level, in percent.
+    pub fn zoom_in(zoom: u32) -> u32 {
+        ZOOM_LEVELS
+            .iter()
+            .find(|level| **level > zoom)
+            .cloned()
+            .unwrap_or(zoom)
+    }
+
+    /// The next smaller zoom level, in percent.
+    pub fn zoom_out(zoom: u32) -> u32 {
+        ZOOM_LEVELS
+            .iter()
+            .rev()
+            .find(|level| **level < zoom)
+            .cloned()
+            .unwrap_or(zoom)
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Window {
+        pub columns: u32,
+        pub lines: u32,
+        /// The most frames drawn per second, nothing is drawn while idle.
+        pub frames_per_second: u32,
+        /// In percent of the layout sizes.
+        pub zoom: u32,
+        /// The size in pixels the window was left at, used instead of
+        /// `columns` and `lines`.
+        pub width: Option<u32>,
+        pub height: Option<u32>,
+        /// Where the window was left, centered without both.
+        pub x: Option<i32>,
+        pub y: Option<i32>,
+        /// Whether the average time to draw a frame is shown in the top
+        /// right corner.
+        pub show_frame_time: bool,
+        /// Whether a scrollbar is shown at the right edge.
+        pub scrollbar: bool,
+        /// Whether the buffer is shown in miniature left of the scrollbar.
+        pub minimap: bool,
+    }
+
+    impl Default for Window {
+        fn default() -> Window {
+            Window {
+                columns: 45,
+                lines: 20,
+                frames_per_second: 60,
+                zoom: 100,
+                width: None,
+                height: None,
+                x: None,
+                y: None,
+                show_frame_time: false,
+                scrollbar: true,
+                minimap: false,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Keybindings {
+        Default,
+        Vim,
+        Emacs,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Editing {
+        pub keybindings: Keybindings,
+        /// Seconds after a change until it is saved, 0 to only save on
+        /// `file.save`.
+        pub autosave_seconds: u32,
+    }
+
+    impl Default for Editing {
+        fn default() -> Editing {
+            Editing {
+                keybindings: Keybindings::Default,
+                autosave_seconds: 0,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum CaretStyle {
+        /// A line before the character.
+        Bar,
+        /// The character's cell filled, with the character in the
+        /// background color.
+        Block,
+        /// A line below the character.
+        Underline,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Caret {
+        pub style: CaretStyle,
+        /// How long the caret is shown and hidden in turn, 0 to not blink.
+        pub blink_milliseconds: u32,
+        /// Whether to show a block caret while typing overwrites.
+        pub block_when_overwriting: bool,
+    }
+
+    impl Default for Caret {
+        fn default() -> Caret {
+            Caret {
+                style: CaretStyle::Bar,
+                blink_milliseconds: 500,
+                block_when_overwriting: true,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum StrokeStyle {
+        /// Aliased lines, whole pixels only.
+        Crisp,
+        /// Anti-aliased lines with round caps and joins.
+        Smooth,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Strokes {
+        /// In window pixels at 100% zoom, growing with the zoom.
+        pub width: u32,
+        pub style: StrokeStyle,
+    }
+
+    impl Default for Strokes {
+        fn default() -> Strokes {
+            Strokes {
+                width: 1,
+                style: StrokeStyle::Crisp,
+            }
+        }
+    }
+
+    /// What the window keeps in textures between frames.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Cache {
+        /// Each glyph rasterized once per size and color.
+        pub glyphs: bool,
+        /// Whole lines rendered once until they change.
+        pub lines: bool,
+    }
+
+    impl Default for Cache {
+        fn default() -> Cache {
+            Cache {
+                glyphs: true,
+                lines: false,
+            }
+        }
+    }
+
+    /// What `file.export_svg` draws besides the glyphs.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Export {
+        pub syntax_colors: bool,
+        pub line_numbers: bool,
+    }
+
+    impl Default for Export {
+        fn default() -> Export {
+            Export {
+                syntax_colors: true,
+                line_numbers: false,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Config {
+        pub layout: Layout,
+        pub theme: Theme,
+        /// The available themes to switch between.
+        pub themes: Vec<Theme>,
+        /// `[colors]` and `[syntax]` entries applied on top of every theme.
+        pub color_overrides: Vec<Entry>,
+        pub window: Window,
+        pub editing: Editing,
+        pub keymap: Keymap,
+        /// The built-in font with the glyphs of `[font] path`.
+        pub font: Font,
+        /// `[font] path`, resolved against the configuration directory.
+        pub font_path: Option<PathBuf>,
+        pub export: Export,
+        pub caret: Caret,
+        pub strokes: Strokes,
+        pub cache: Cache,
+    }
+
+    impl Default for Config {
+        fn default() -> Config {
+            Config {
+                layout: Layout::default(),
+                theme: Theme::default(),
+                themes: Theme::presets(),
+                color_overrides: vec![],
+                window: Window::default(),
+                editing: Editing::default(),
+                keymap: Keymap::default(),
+                font: Font::default(),
+                font_path: None,
+                export: Export::default(),
+                caret: Caret::default(),
+                strokes: Strokes::default(),
+                cache: Cache::default(),
+            }
+        }
+    }
+
+    impl Config {
+        /// `$XDG_CONFIG_HOME/editor/config.toml`, falling back to
+        /// `$HOME/.config/editor/config.toml`.
+        pub fn path() -> Option<PathBuf> {
+            config_directory().map(|directory| directory.join("config.toml"))
+        }
+
+        /// Loads the user configuration, or the defaults if there is none.
+        /// Themes from the `themes` directory can be chosen as well as the presets.
+        pub fn load() -> Result<Config, ConfigError> {
+            let themes = Theme::load_all().map_err(|message| ConfigError::new(0, "", &message))?;
+            match Config::path() {
+                Some(path) if path.exists() => match fs::read_to_string(&path) {
+                    Ok(content) => Config::from_str(&content, themes),
+                    Err(error) => Err(ConfigError::new(
+                        0,
+                        "",
+                        &format!("could not read {}: {}", path.display(), error),
+                    )),
+                },
+                _ => Config::from_str("", themes),
+            }
+        }
+
+        /// `themes` are the ones `[theme] name` can choose from.
+        pub fn from_str(content: &str, themes: Vec<Theme>) -> Result<Config, ConfigError> {
+            let mut config = Config {
+                themes,
+                ..Config::default()
+            };
+            let mut line_gap = None;
+            let mut character_gap = None;
+            let mut theme_entry = None;
+            let mut keymap_entries = vec![];
+            for entry in parse(content)? {
+                match (entry.section.as_str(), entry.key.as_str()) {
+                    ("layout", "character_width") => {
+                        config.layout.character_width = get_positive_integer(&entry)?
+                    }
+                    ("layout", "character_height") => {
+                        config.layout.character_height = get_positive_integer(&entry)?
+                    }
+                    ("layout", "line_gap") => line_gap = Some(get_integer(&entry)?),
+                    ("layout", "character_gap") => character_gap = Some(get_integer(&entry)?),
+                    ("layout", "x_offset") => config.layout.x_offset = get_integer(&entry)?,
+                    ("layout", "y_offset") => config.layout.y_offset = get_integer(&entry)?,
+                    ("theme", "name") => theme_entry = Some(entry),
+                    ("colors", _) | ("syntax", _) => config.color_overrides.push(entry),
+                    ("window", "columns") => {
+                        config.window.columns = get_positive_integer(&entry)? as u32
+                    }
+                    ("window", "lines") => {
+                        config.window.lines = get_positive_integer(&entry)? as u32
+                    }
+                    ("window", "frames_per_second") => {
+                        config.window.frames_per_second = get_positive_integer(&entry)? as u32
+                    }
+                    ("window", "show_frame_time") => {
+                        config.window.show_frame_time = get_boolean(&entry)?
+                    }
+                    ("window", "scrollbar") => config.window.scrollbar = get_boolean(&entry)?,
+                    ("window", "minimap") => config.window.minimap = get_boolean(&entry)?,
+                    ("window", "zoom") => {
+                        let zoom = get_positive_integer(&entry)? as u32;
+                        let (minimum, maximum) =
+                            (ZOOM_LEVELS[0], ZOOM_LEVELS[ZOOM_LEVELS.len() - 1]);
+                        if zoom < minimum || zoom > maximum {
+                            return Err(ConfigError::for_entry(
+                                &entry,
+                                &format!("expected between {} and {}", minimum, maximum),
+                            ));
+                        }
+                        config.window.zoom = zoom;
+                    }
+                    ("window", "width") => {
+                        config.window.width = Some(get_positive_integer(&entry)? as u32)
+                    }
+                    ("window", "height") => {
+                        config.window.height = Some(get_positive_integer(&entry)? as u32)
+                    }
+                    ("window", "x") => config.window.x = Some(get_integer(&entry)?),
+                    ("window", "y") => config.window.y = Some(get_integer(&entry)?),
+                    ("editing", "keybindings") => {
+                        config.editing.keybindings = match get_string(&entry)?.as_str() {
+                            "default" => Keybindings::Default,
+                            "vim" => Keybindings::Vim,
+                            "emacs" => Keybindings::Emacs,
+                            _ => {
+                                return Err(ConfigError::for_entry(
+                                    &entry,
+                                    "expected \"default\", \"vim\" or \"emacs\"",
+                                ))
+                            }
+                        }
+                    }
+                    ("editing", "autosave_seconds") => {
+                        let seconds = get_integer(&entry)?;
+                        if seconds < 0 {
+                            return Err(ConfigError::for_entry(
+                                &entry,
+                                "expected an integer of 0 or more",
+                            ));
+                        }
+                        config.editing.autosave_seconds = seconds as u32;
+                    }
+                    ("keymap", _) => keymap_entries.push(entry),
+                    ("font", "path") => {
+                        let path = PathBuf::from(get_string(&entry)?);
+                        let path = match config_directory() {
+                            Some(directory) => directory.join(path),
+                            None => path,
+                        };
+                        config.font = Font::load(&path)
+                            .map_err(|message| ConfigError::for_entry(&entry, &message))?;
+                        config.font_path = Some(path);
+                    }
+                    ("export", "syntax_colors") => {
+                        config.export.syntax_colors = get_boolean(&entry)?
+                    }
+                    ("export", "line_numbers") => config.export.line_numbers = get_boolean(&entry)?,
+                    ("caret", "style") => {
+                        config.caret.style = match get_string(&entry)?.as_str() {
+                            "bar" => CaretStyle::Bar,
+                            "block" => CaretStyle::Block,
+                            "underline" => CaretStyle::Underline,
+                            _ => {
+                                return Err(ConfigError::for_entry(
+                                    &entry,
+                                    "expected \"bar\", \"block\" or \"underline\"",
+                                ))
+                            }
+                        }
+                    }
+                    ("caret", "blink_milliseconds") => {
+                        let milliseconds = get_integer(&entry)?;
+                        if milliseconds < 0 {
+                            return Err(ConfigError::for_entry(
+                                &entry,
+                                "expected an integer of 0 or more",
+                            ));
+                        }
+                        config.caret.blink_milliseconds = milliseconds as u32;
+                    }
+                    ("caret", "block_when_overwriting") => {
+                        config.caret.block_when_overwriting = get_boolean(&entry)?
+                    }
+                    ("cache", "glyphs") => config.cache.glyphs = get_boolean(&entry)?,
+                    ("cache", "lines") => config.cache.lines = get_boolean(&entry)?,
+                    ("strokes", "width") => {
+                        let width = get_integer(&entry)?;
+                        if !(1..=16).contains(&width) {
+                            return Err(ConfigError::for_entry(
+                                &entry,
+                                "expected an integer from 1 to 16",
+                            ));
+                        }
+                        config.strokes.width = width as u32;
+                    }
+                    ("strokes", "style") => {
+                        config.strokes.style = match get_string(&entry)?.as_str() {
+                            "crisp" => StrokeStyle::Crisp,
+                            "smooth" => StrokeStyle::Smooth,
+                            _ => {
+                                return Err(ConfigError::for_entry(
+                                    &entry,
+                                    "expected \"crisp\" or \"smooth\"",
+                                ))
+                            }
+                        }
+                    }
+                    _ => return Err(ConfigError::for_entry(&entry, "unknown key")),
+                }
+            }
+            if config.editing.keybindings == Keybindings::Emacs {
+                config.keymap = Keymap::emacs();
+            }
+            for entry in keymap_entries {
+                let command_name = get_string(&entry)?;
+                let result = if command_name == "none" {
+                    config.keymap.unbind(&entry.key)
+                } else {
+                    match Command::from_name(&command_name) {
+                        Some(command) => config.keymap.bind(&entry.key, command),
+                        None => Err(format!("unknown command \"{}\"", command_name)),
+                    }
+                };
+                result.map_err(|message| ConfigError::for_entry(&entry, &message))?;
+            }
+            if let Some(entry) = theme_entry {
+                config.theme = find_theme(&config.themes, &entry)?.clone();
+            } else if let Some(theme) = config.themes.iter().find(|theme| theme.name == "dark") {
+                config.theme = theme.clone();
+            }
+            for entry in config.color_overrides.iter() {
+                config.theme.apply(entry)?;
+            }
+            config.layout.line_gap = line_gap.unwrap_or(config.layout.character_height / 3);
+            config.layout.character_gap =
+                character_gap.unwrap_or(config.layout.character_width / 3);
+            Ok(config)
+        }
+
+        /// Sets `key` in `[section]` of the configuration file to `value`,
+        /// written as TOML, creating the file if needed.
+        pub fn store(section: &str, key: &str, value: &str) -> Result<(), String> {
+            Config::store_all(&[(section, key, value)])
+        }
+
+        /// `store` for several `(section, key, value)`, writing the file once.
+        pub fn store_all(values: &[(&str, &str, &str)]) -> Result<(), String> {
+            let path = Config::path().ok_or("no configuration directory")?;
+            let content = if path.exists() {
+                fs::read_to_string(&path)
+                    .map_err(|error| format!("could not read {}: {}", path.display(), error))?
+            } else {
+                String::new()
+            };
+            let content = set_values(&content, values).map_err(|error| error.to_string())?;
+            if let Some(directory) = path.parent() {
+                fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+            }
+            fs::write(&path, content)
+                .map_err(|error| format!("could not write {}: {}", path.display(), error))
+        }
+
+        /// The theme after `theme`, with the overrides applied.
+        pub fn next_theme(&self, theme: &Theme) -> Theme {
+            let index = self
+                .themes
+                .iter()
+                .position(|candidate| candidate.name == theme.name)
+                .map_or(0, |index| (index + 1) % self.themes.len());
+            let mut next = self.themes[index].clone();
+            for entry in self.color_overrides.iter() {
+                // Already validated while loading.
+                next.apply(entry).unwrap();
+            }
+            next
+        }
+    }
+
+    pub fn config_directory() -> Option<PathBuf> {
+        match env::var_os("XDG_CONFIG_HOME") {
+            Some(directory) if !directory.is_empty() => Some(PathBuf::from(directory)),
+            _ => env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")),
+        }
+        .map(|directory| directory.join("editor"))
+    }
+
+    pub fn get_integer(entry: &Entry) -> Result<i32, ConfigError> {
+        match entry.value {
+            Value::Integer(integer) => i32::try_from(integer)
+                .map_err(|_| ConfigError::for_entry(entry, "integer is out of range")),
+            _ => Err(ConfigError::for_entry(
+                entry,
+                &format!("expected an integer, found {}", entry.value.type_name()),
+            )),
+        }
+    }
+
+    pub fn get_positive_integer(entry: &Entry) -> Result<i32, ConfigError> {
+        let integer = get_integer(entry)?;
+        if integer > 0 {
+            Ok(integer)
+        } else {
+            Err(ConfigError::for_entry(
+                entry,
+                "expected an integer greater than 0",
+            ))
+        }
+    }
+
+    pub fn get_boolean(entry: &Entry) -> Result<bool, ConfigError> {
+        match entry.value {
+            Value::Boolean(boolean) => Ok(boolean),
+            _ => Err(ConfigError::for_entry(
+                entry,
+                &format!("expected true or false, found {}", entry.value.type_name()),
+            )),
+        }
+    }
+
+    pub fn get_string(entry: &Entry) -> Result<String, ConfigError> {
+        match &entry.value {
+            Value::String(string) => Ok(string.to_string()),
+            value => Err(ConfigError::for_entry(
+                entry,
+                &format!("expected a string, found {}", value.type_name()),
+            )),
+        }
+    }
+
+    /// Accepts `"#rrggbb"`, `"#rrggbbaa"` or `[r, g, b]`/`[r, g, b, a]`.
+    pub fn get_color(entry: &Entry) -> Result<Color, ConfigError> {
+        let invalid = || {
+            ConfigError::for_entry(
+                entry,
+                "expected a color like \"#rrggbb\" or [r, g, b] with components from 0 to 255",
+            )
+        };
+        match &entry.value {
+            Value::String(string) => {
+                let hex = string.trim_start_matches('#');
+                if !string.starts_with('#') || (hex.len() != 6 && hex.len() != 8) {
+                    return Err(invalid());
+                }
+                let mut components = vec![];
+                for index in (0..hex.len()).step_by(2) {
+                    let component = hex
+                        .get(index..index + 2)
+                        .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                        .ok_or_else(invalid)?;
+                    components.push(component);
+                }
+                components.push(0xff);
+                Ok(Color::RGBA(
+                    components[0],
+                    components[1],
+                    components[2],
+                    components[3],
+                ))
+            }
+            Value::Array(values) if values.len() == 3 || values.len() == 4 => {
+                let mut components = vec![];
+                for value in values {
+                    match value {
+                        Value::Integer(integer) if *integer >= 0 && *integer <= 255 => {
+                            components.push(*integer as u8)
+                        }
+                        _ => return Err(invalid()),
+                    }
+                }
+                components.push(0xff);
+                Ok(Color::RGBA(
+                    components[0],
+                    components[1],
+                    components[2],
+                    components[3],
+                ))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
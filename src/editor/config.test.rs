@@ -0,0 +1,495 @@
+#[path = "config.rs"]
+mod config;
+
+#[path = "commands.rs"]
+mod commands;
+
+#[path = "cursor.rs"]
+mod cursor;
+
+#[path = "kill_ring.rs"]
+mod kill_ring;
+
+#[path = "keymap.rs"]
+mod keymap;
+
+#[path = "font.rs"]
+mod font;
+
+#[path = "highlighting.rs"]
+mod highlighting;
+
+#[path = "theme.rs"]
+mod theme;
+
+#[cfg(test)]
+mod tests {
+    use super::commands::commands::Command;
+    use super::config::config::{
+        get_columns_in_width, get_display_scale, get_scaled_zoom, get_stroke_width, parse,
+        set_value, set_values, zoom_in, zoom_out, CaretStyle, Config, ConfigError, Keybindings,
+        Layout, StrokeStyle, Value,
+    };
+    use super::font::font::Font;
+    use super::keymap::keymap::{Chord, KeyResult};
+    use super::theme::theme::Theme;
+    use sdl2::pixels::Color;
+    fn from_str(content: &str) -> Result<Config, ConfigError> {
+        Config::from_str(content, Theme::presets())
+    }
+    #[test]
+    fn empty_is_default() {
+        assert_eq!(from_str("").unwrap(), Config::default());
+    }
+    #[test]
+    fn defaults_match_previous_statics() {
+        let config = Config::default();
+        assert_eq!(config.layout.character_width, 10);
+        assert_eq!(config.layout.character_height, 16);
+        assert_eq!(config.layout.line_gap, 5);
+        assert_eq!(config.layout.character_gap, 3);
+        assert_eq!(config.layout.x_offset, 10);
+        assert_eq!(config.layout.y_offset, 5);
+        assert_eq!(Theme::classic().colors.text, Color::RGB(80, 80, 80));
+        assert_eq!(config.window.columns, 45);
+        assert_eq!(config.window.lines, 20);
+        assert_eq!(config.window.frames_per_second, 60);
+    }
+    #[test]
+    fn parse_values() {
+        let entries =
+            parse("a = 1\nb = -2.5\nc = true\nd = \"x # y\"\ne = [1, \"two\", [3]]").unwrap();
+        assert_eq!(entries.len(), 5);
+        assert_eq!(entries[0].value, Value::Integer(1));
+        assert_eq!(entries[1].value, Value::Float(-2.5));
+        assert_eq!(entries[2].value, Value::Boolean(true));
+        assert_eq!(entries[3].value, Value::String(String::from("x # y")));
+        assert_eq!(
+            entries[4].value,
+            Value::Array(vec![
+                Value::Integer(1),
+                Value::String(String::from("two")),
+                Value::Array(vec![Value::Integer(3)])
+            ])
+        );
+    }
+    #[test]
+    fn parse_sections_and_comments() {
+        let entries = parse("# comment\n[layout]\nx_offset = 3 # trailing\n\n[colors]\n").unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].line, 3);
+        assert_eq!(entries[0].section, "layout");
+        assert_eq!(entries[0].full_key(), "layout.x_offset");
+    }
+    #[test]
+    fn parse_quoted_key() {
+        let entries = parse("\"ctrl+s\" = \"save\"").unwrap();
+        assert_eq!(entries[0].key, "ctrl+s");
+    }
+    #[test]
+    fn parse_string_escapes() {
+        let entries = parse("a = \"quote \\\" backslash \\\\ tab \\t\"").unwrap();
+        assert_eq!(
+            entries[0].value,
+            Value::String(String::from("quote \" backslash \\ tab \t"))
+        );
+    }
+    #[test]
+    fn error_missing_equals() {
+        let error = parse("[layout]\n\ncharacter_width 10").unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.key, "layout.character_width");
+    }
+    #[test]
+    fn error_unterminated_string() {
+        let error = parse("a = \"abc").unwrap_err();
+        assert_eq!(error.line, 1);
+        assert_eq!(error.key, "a");
+    }
+    #[test]
+    fn error_duplicate_key() {
+        let error = parse("a = 1\na = 2").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(error.message, "already defined on line 1");
+    }
+    #[test]
+    fn error_unclosed_section() {
+        let error = parse("[layout").unwrap_err();
+        assert_eq!(error.line, 1);
+    }
+    #[test]
+    fn layout_values() {
+        let config = from_str(
+            "[layout]\ncharacter_width = 20\ncharacter_height = 30\nx_offset = 0\ny_offset = 1",
+        )
+        .unwrap();
+        assert_eq!(
+            config.layout,
+            Layout {
+                character_width: 20,
+                character_height: 30,
+                line_gap: 10,
+                character_gap: 6,
+                x_offset: 0,
+                y_offset: 1,
+            }
+        );
+    }
+    #[test]
+    fn layout_explicit_gaps() {
+        let config = from_str("[layout]\nline_gap = 0\ncharacter_gap = 1").unwrap();
+        assert_eq!(config.layout.line_gap, 0);
+        assert_eq!(config.layout.character_gap, 1);
+    }
+    #[test]
+    fn colors_hex_and_array() {
+        let config = from_str("[colors]\ntext = \"#a0b0c0\"\nbackground = [1, 2, 3, 4]").unwrap();
+        assert_eq!(config.theme.colors.text, Color::RGB(0xa0, 0xb0, 0xc0));
+        assert_eq!(config.theme.colors.background, Color::RGBA(1, 2, 3, 4));
+    }
+    #[test]
+    fn syntax_colors() {
+        let config = from_str("[syntax]\nkeyword = \"#010203\"\ntype = [4, 5, 6]").unwrap();
+        assert_eq!(config.theme.syntax.keyword, Color::RGB(1, 2, 3));
+        assert_eq!(config.theme.syntax.type_name, Color::RGB(4, 5, 6));
+        assert_eq!(
+            config.theme.syntax.string,
+            Config::default().theme.syntax.string
+        );
+    }
+    #[test]
+    fn window_values() {
+        let config =
+            from_str("[window]\ncolumns = 80\nlines = 40\nframes_per_second = 60").unwrap();
+        assert_eq!(config.window.columns, 80);
+        assert_eq!(config.window.lines, 40);
+        assert_eq!(config.window.frames_per_second, 60);
+    }
+    #[test]
+    fn autosave_seconds() {
+        assert_eq!(Config::default().editing.autosave_seconds, 0);
+        let config = from_str("[editing]\nautosave_seconds = 30").unwrap();
+        assert_eq!(config.editing.autosave_seconds, 30);
+        let error = from_str("[editing]\nautosave_seconds = -1").unwrap_err();
+        assert_eq!(error.key, "editing.autosave_seconds");
+        assert_eq!(error.message, "expected an integer of 0 or more");
+    }
+    #[test]
+    fn caret_values() {
+        assert_eq!(Config::default().caret.style, CaretStyle::Bar);
+        let config = from_str(
+            "[caret]\nstyle = \"underline\"\nblink_milliseconds = 0\nblock_when_overwriting = false",
+        )
+        .unwrap();
+        assert_eq!(config.caret.style, CaretStyle::Underline);
+        assert_eq!(config.caret.blink_milliseconds, 0);
+        assert!(!config.caret.block_when_overwriting);
+        let error = from_str("[caret]\nstyle = \"beam\"").unwrap_err();
+        assert_eq!(error.key, "caret.style");
+    }
+    #[test]
+    fn stroke_values() {
+        assert_eq!(Config::default().strokes.width, 1);
+        assert_eq!(Config::default().strokes.style, StrokeStyle::Crisp);
+        let config = from_str("[strokes]\nwidth = 2\nstyle = \"smooth\"").unwrap();
+        assert_eq!(config.strokes.width, 2);
+        assert_eq!(config.strokes.style, StrokeStyle::Smooth);
+        let error = from_str("[strokes]\nwidth = 0").unwrap_err();
+        assert_eq!(error.key, "strokes.width");
+        let error = from_str("[strokes]\nstyle = \"blurry\"").unwrap_err();
+        assert_eq!(error.key, "strokes.style");
+    }
+    #[test]
+    fn cache_values() {
+        let config = Config::default();
+        assert!(config.cache.glyphs);
+        assert!(!config.cache.lines);
+        assert!(!config.window.show_frame_time);
+        let config =
+            from_str("[cache]\nglyphs = false\nlines = true\n[window]\nshow_frame_time = true")
+                .unwrap();
+        assert!(!config.cache.glyphs);
+        assert!(config.cache.lines);
+        assert!(config.window.show_frame_time);
+        let error = from_str("[cache]\nlines = 1").unwrap_err();
+        assert_eq!(error.key, "cache.lines");
+    }
+    #[test]
+    fn scrollbar_and_minimap() {
+        let config = Config::default();
+        assert!(config.window.scrollbar);
+        assert!(!config.window.minimap);
+        let config = from_str("[window]\nscrollbar = false\nminimap = true").unwrap();
+        assert!(!config.window.scrollbar);
+        assert!(config.window.minimap);
+    }
+    #[test]
+    fn window_geometry() {
+        let config = Config::default();
+        assert_eq!((config.window.width, config.window.x), (None, None));
+        let config = from_str("[window]\nwidth = 800\nheight = 600\nx = -20\ny = 40").unwrap();
+        assert_eq!(config.window.width, Some(800));
+        assert_eq!(config.window.height, Some(600));
+        assert_eq!(config.window.x, Some(-20));
+        assert_eq!(config.window.y, Some(40));
+        let error = from_str("[window]\nwidth = 0").unwrap_err();
+        assert_eq!(error.key, "window.width");
+    }
+    #[test]
+    fn display_scale() {
+        assert_eq!(get_display_scale(1170, 585), 2.0);
+        assert_eq!(get_display_scale(585, 585), 1.0);
+        assert_eq!(get_display_scale(0, 0), 1.0);
+        assert_eq!(get_scaled_zoom(100, 2.0), 200);
+        assert_eq!(get_scaled_zoom(125, 1.5), 188);
+        assert_eq!(get_stroke_width(1, 100), 1);
+        assert_eq!(get_stroke_width(1, 150), 2);
+        assert_eq!(get_stroke_width(1, 200), 2);
+        assert_eq!(get_stroke_width(1, 50), 1);
+        assert_eq!(get_stroke_width(3, 200), 6);
+        let scaled = Layout::default().zoomed(get_scaled_zoom(100, 2.0));
+        assert_eq!(scaled.character_width, 20);
+        assert_eq!(scaled.character_height, 32);
+    }
+    #[test]
+    fn columns_in_width() {
+        let layout = Layout::default();
+        assert_eq!(get_columns_in_width(&layout, 585), 45);
+        assert_eq!(get_columns_in_width(&layout, 597), 45);
+        assert_eq!(get_columns_in_width(&layout, 5), 1);
+    }
+    #[test]
+    fn error_wrong_type() {
+        let error = from_str("[layout]\ncharacter_width = \"wide\"").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(error.key, "layout.character_width");
+        assert_eq!(error.message, "expected an integer, found a string");
+    }
+    #[test]
+    fn error_not_positive() {
+        let error = from_str("[window]\nframes_per_second = 0").unwrap_err();
+        assert_eq!(error.key, "window.frames_per_second");
+    }
+    #[test]
+    fn error_integer_out_of_range() {
+        for integer in &["2147483648", "-2147483649", "-9223372036854775808"] {
+            let content = format!("[layout]\ncharacter_width = {}", integer);
+            let error = from_str(&content).unwrap_err();
+            assert_eq!(error.message, "integer is out of range");
+        }
+    }
+    #[test]
+    fn error_invalid_color() {
+        let error = from_str("[colors]\n\ntext = [300, 0, 0]").unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.key, "colors.text");
+    }
+    #[test]
+    fn error_unknown_key() {
+        let error = from_str("[layout]\ncharacter_widht = 3").unwrap_err();
+        assert_eq!(error.key, "layout.character_widht");
+        assert_eq!(error.message, "unknown key");
+    }
+    #[test]
+    fn error_display() {
+        let error = from_str("[layout]\ncharacter_width = true").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2: \"layout.character_width\": expected an integer, found a boolean"
+        );
+    }
+    #[test]
+    fn keymap_bindings() {
+        let mut config =
+            from_str("[keymap]\n\"ctrl+k ctrl+u\" = \"cursor.up\"\n\"ctrl+s\" = \"none\"").unwrap();
+        assert_eq!(
+            config.keymap.press(Chord::parse("ctrl+s").unwrap()),
+            KeyResult::Unbound
+        );
+        assert_eq!(
+            config.keymap.press(Chord::parse("ctrl+k").unwrap()),
+            KeyResult::Pending
+        );
+        assert_eq!(
+            config.keymap.press(Chord::parse("ctrl+u").unwrap()),
+            KeyResult::Command(Command::CursorUp)
+        );
+    }
+    #[test]
+    fn error_keymap_unknown_command() {
+        let error = from_str("[keymap]\n\"ctrl+u\" = \"cursor.upp\"").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(error.key, "keymap.ctrl+u");
+        assert_eq!(error.message, "unknown command \"cursor.upp\"");
+    }
+    #[test]
+    fn error_keymap_invalid_chord() {
+        let error = from_str("[keymap]\n\"hyper+u\" = \"cursor.up\"").unwrap_err();
+        assert_eq!(error.message, "unknown modifier \"hyper\"");
+    }
+    #[test]
+    fn editing_keybindings() {
+        let config = from_str("[editing]\nkeybindings = \"vim\"").unwrap();
+        assert_eq!(config.editing.keybindings, Keybindings::Vim);
+        assert_eq!(Config::default().editing.keybindings, Keybindings::Default);
+    }
+    #[test]
+    fn error_editing_keybindings() {
+        let error = from_str("[editing]\nkeybindings = \"nano\"").unwrap_err();
+        assert_eq!(error.key, "editing.keybindings");
+    }
+    #[test]
+    fn export() {
+        let config = from_str("[export]\nsyntax_colors = false\nline_numbers = true").unwrap();
+        assert!(!config.export.syntax_colors);
+        assert!(config.export.line_numbers);
+        assert!(Config::default().export.syntax_colors);
+        let error = from_str("[export]\nline_numbers = 1").unwrap_err();
+        assert_eq!(error.key, "export.line_numbers");
+        assert_eq!(error.message, "expected true or false, found an integer");
+    }
+    #[test]
+    fn emacs_keybindings_with_overrides() {
+        let mut config =
+            from_str("[keymap]\n\"ctrl+t\" = \"yank\"\n\n[editing]\nkeybindings = \"emacs\"")
+                .unwrap();
+        assert_eq!(config.editing.keybindings, Keybindings::Emacs);
+        assert_eq!(
+            config.keymap.press(Chord::parse("ctrl+k").unwrap()),
+            KeyResult::Command(Command::KillLine)
+        );
+        assert_eq!(
+            config.keymap.press(Chord::parse("ctrl+t").unwrap()),
+            KeyResult::Command(Command::Yank)
+        );
+    }
+    #[test]
+    fn theme_by_name() {
+        assert_eq!(from_str("").unwrap().theme, Theme::dark());
+        let config = from_str("[theme]\nname = \"light\"").unwrap();
+        assert_eq!(config.theme, Theme::light());
+    }
+    #[test]
+    fn error_unknown_theme() {
+        let error = from_str("[theme]\nname = \"solarized\"").unwrap_err();
+        assert_eq!(error.key, "theme.name");
+        assert_eq!(
+            error.message,
+            "unknown theme \"solarized\", expected one of \"dark\", \"light\", \"high-contrast\", \"classic\""
+        );
+    }
+    #[test]
+    fn font_path() {
+        let path = std::env::temp_dir().join(format!("editor-config-{}.font", std::process::id()));
+        std::fs::write(&path, "a 0,0 1,1").unwrap();
+        let config = from_str(&format!("[font]\npath = {:?}", path.to_str().unwrap())).unwrap();
+        assert_eq!(
+            config.font.get_strokes(&'a', 4, 4),
+            vec![vec![(0, 0), (4, 4)]]
+        );
+        assert_eq!(from_str("").unwrap().font, Font::builtin());
+
+        std::fs::write(&path, "a 0,0").unwrap();
+        let error = from_str(&format!("[font]\npath = {:?}", path.to_str().unwrap())).unwrap_err();
+        assert_eq!(error.key, "font.path");
+        assert!(error
+            .message
+            .ends_with("line 1: a stroke needs at least two points"));
+        std::fs::remove_file(&path).unwrap();
+    }
+    #[test]
+    fn error_unknown_color() {
+        let error = from_str("[colors]\ncaret = \"#ffffff\"").unwrap_err();
+        assert_eq!(error.key, "colors.caret");
+        assert_eq!(error.message, "unknown key");
+    }
+    #[test]
+    fn next_theme_keeps_overrides() {
+        let config =
+            from_str("[theme]\nname = \"high-contrast\"\n[colors]\ngutter = \"#010203\"").unwrap();
+        assert_eq!(config.theme.colors.gutter, Color::RGB(1, 2, 3));
+        let next = config.next_theme(&config.theme);
+        assert_eq!(next.name, "classic");
+        assert_eq!(next.colors.gutter, Color::RGB(1, 2, 3));
+        assert_eq!(next.colors.text, Theme::classic().colors.text);
+        assert_eq!(config.next_theme(&next).name, "dark");
+    }
+    #[test]
+    fn zoom_value() {
+        assert_eq!(from_str("").unwrap().window.zoom, 100);
+        assert_eq!(from_str("[window]\nzoom = 150").unwrap().window.zoom, 150);
+        let error = from_str("[window]\nzoom = 20").unwrap_err();
+        assert_eq!(error.key, "window.zoom");
+        assert_eq!(error.message, "expected between 50 and 300");
+    }
+    #[test]
+    fn zoom_levels() {
+        assert_eq!(zoom_in(100), 110);
+        assert_eq!(zoom_in(105), 110);
+        assert_eq!(zoom_in(300), 300);
+        assert_eq!(zoom_out(100), 90);
+        assert_eq!(zoom_out(105), 100);
+        assert_eq!(zoom_out(50), 50);
+    }
+    #[test]
+    fn layout_zoomed() {
+        let layout = Layout::default();
+        assert_eq!(layout.zoomed(100), layout);
+        assert_eq!(
+            layout.zoomed(200),
+            Layout {
+                character_width: 20,
+                character_height: 32,
+                line_gap: 10,
+                character_gap: 6,
+                x_offset: 20,
+                y_offset: 10,
+            }
+        );
+        let tiny = Layout::from_character_size(1, 1).zoomed(50);
+        assert_eq!(tiny.character_width, 1);
+        assert_eq!(tiny.character_height, 1);
+    }
+    #[test]
+    fn set_value_replaces_existing_entry() {
+        let content = "# settings\n[window]\nzoom = 100 # old\nlines = 30\n";
+        assert_eq!(
+            set_value(content, "window", "zoom", "150").unwrap(),
+            "# settings\n[window]\nzoom = 150\nlines = 30\n"
+        );
+    }
+    #[test]
+    fn set_value_adds_to_section() {
+        let content = "[ window ] # size\nlines = 30\n\n[layout]\nx_offset = 0";
+        assert_eq!(
+            set_value(content, "window", "zoom", "90").unwrap(),
+            "[ window ] # size\nzoom = 90\nlines = 30\n\n[layout]\nx_offset = 0\n"
+        );
+    }
+    #[test]
+    fn set_value_adds_section() {
+        assert_eq!(
+            set_value("", "window", "zoom", "90").unwrap(),
+            "[window]\nzoom = 90\n"
+        );
+        assert_eq!(
+            set_value("[layout]\nx_offset = 0\n", "window", "zoom", "90").unwrap(),
+            "[layout]\nx_offset = 0\n\n[window]\nzoom = 90\n"
+        );
+        assert!(set_value("[layout", "window", "zoom", "90").is_err());
+    }
+    #[test]
+    fn set_values_sets_every_entry() {
+        let content = "[window]\nwidth = 400\n";
+        let values = [
+            ("window", "width", "640"),
+            ("window", "height", "480"),
+            ("font", "path", "\"user.font\""),
+        ];
+        assert_eq!(
+            set_values(content, &values).unwrap(),
+            "[window]\nheight = 480\nwidth = 640\n\n[font]\npath = \"user.font\"\n"
+        );
+        assert!(set_values("[window", &values).is_err());
+    }
+}
@@ -38,7 +38,6 @@ pub mod cursor {
         pub extender: CursorPosition,
         pub lines: Vec<String>,
     }
-    use std::error::Error;
     use std::fs;
     use std::fs::File;
     use std::io;
@@ -77,14 +76,7 @@ pub mod cursor {
             )
         }
         pub fn to_file(&mut self, file_name: &str) -> Result<(), io::Error> {
-            let path = Path::new(file_name);
-            let display = path.display();
-
-            let mut file = match File::create(&path) {
-                Err(why) => panic!("couldn't create {}: {}", display, why.description()),
-                Ok(file) => file,
-            };
-
+            let mut file = File::create(Path::new(file_name))?;
             file.write_all(self.to_multi_line_string(&"\n".to_string()).as_bytes())
         }
         pub fn to_multi_line_string(&mut self, separator: &String) -> String {
@@ -299,6 +291,67 @@ pub mod cursor {
                 self.current.column_offset = new_end_column;
             }
         }
+        pub fn move_to(&mut self, line: usize, column: usize, select: bool) {
+            let line = line.min(self.lines.len() - 1);
+            let column = column.min(self.lines[line].chars().count());
+            self.extender.line = line;
+            self.extender.column = column;
+            self.extender.column_offset = column;
+            if !select {
+                self.current.line = line;
+                self.current.column = column;
+                self.current.column_offset = column;
+            }
+        }
+        pub fn selected_text(&self) -> String {
+            let (start, end) = if self.current < self.extender {
+                (&self.current, &self.extender)
+            } else {
+                (&self.extender, &self.current)
+            };
+            (start.line..=end.line)
+                .map(|line_index| {
+                    let line = &self.lines[line_index];
+                    let from = if line_index == start.line {
+                        start.column
+                    } else {
+                        0
+                    };
+                    let to = if line_index == end.line {
+                        end.column
+                    } else {
+                        line.chars().count()
+                    };
+                    line.chars()
+                        .skip(from)
+                        .take(to.saturating_sub(from))
+                        .collect::<String>()
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        }
+        pub fn insert(&mut self, text: &str) {
+            if self.current != self.extender {
+                self.delete();
+            }
+            for character in text.chars() {
+                if character == '\n' {
+                    self.new_line();
+                } else {
+                    self.add(character);
+                }
+            }
+        }
+        pub fn select_all(&mut self) {
+            let last_line = self.lines.len() - 1;
+            let last_column = self.lines[last_line].chars().count();
+            self.current.line = 0;
+            self.current.column = 0;
+            self.current.column_offset = 0;
+            self.extender.line = last_line;
+            self.extender.column = last_column;
+            self.extender.column_offset = last_column;
+        }
         pub fn up(&mut self, select: bool) {
             self.vertical(-1, select);
         }
@@ -818,4 +818,60 @@ mod tests {
             "Hello\n\rWorld".to_string()
         );
     }
+    #[test]
+    fn select_all() {
+        let mut cursor = Cursor::new(vec![String::from("ab"), String::from("cde")]);
+        cursor.right(false);
+        cursor.select_all();
+        assert_eq!(cursor.current.line, 0);
+        assert_eq!(cursor.current.column, 0);
+        assert_eq!(cursor.extender.line, 1);
+        assert_eq!(cursor.extender.column, 3);
+        cursor.delete();
+        assert_eq!(cursor.lines, vec![String::from("")]);
+    }
+    #[test]
+    fn move_to() {
+        let mut cursor = Cursor::new(vec![String::from("abc"), String::from("d")]);
+        cursor.move_to(1, 5, false);
+        assert_eq!(cursor.current.line, 1);
+        assert_eq!(cursor.current.column, 1);
+        cursor.move_to(0, 2, true);
+        assert_eq!(cursor.current.line, 1);
+        assert_eq!(cursor.extender.line, 0);
+        assert_eq!(cursor.extender.column, 2);
+    }
+    #[test]
+    fn selected_text_multiple_lines() {
+        let mut cursor = Cursor::new(vec![
+            String::from("abc"),
+            String::from("def"),
+            String::from("ghi"),
+        ]);
+        cursor.move_to(2, 1, false);
+        cursor.move_to(0, 1, true);
+        assert_eq!(cursor.selected_text(), "bc\ndef\ng");
+    }
+    #[test]
+    fn selected_text_empty() {
+        let cursor = Cursor::new(vec![String::from("abc")]);
+        assert_eq!(cursor.selected_text(), "");
+    }
+    #[test]
+    fn insert_multiple_lines() {
+        let mut cursor = Cursor::new(vec![String::from("ad")]);
+        cursor.right(false);
+        cursor.insert("b\nc");
+        assert_eq!(cursor.lines, vec![String::from("ab"), String::from("cd")]);
+        assert_eq!(cursor.current.line, 1);
+        assert_eq!(cursor.current.column, 1);
+    }
+    #[test]
+    fn insert_replaces_selection() {
+        let mut cursor = Cursor::new(vec![String::from("abc")]);
+        cursor.right(true);
+        cursor.right(true);
+        cursor.insert("x");
+        assert_eq!(cursor.lines, vec![String::from("xc")]);
+    }
 }
@@ -0,0 +1,387 @@
+pub mod font {
+    use std::collections::HashMap;
+    use std::fmt;
+    use std::fs;
+    use std::path::Path;
+    use std::str::FromStr;
+
+    static DEFAULT_FONT: &str = include_str!("default.font");
+
+    /// How far points may reach outside of the cell, in cells.
+    static MARGIN: i32 = 1;
+
+    /// A coordinate as written in the font file, in cells.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Fraction {
+        pub numerator: i32,
+        pub denominator: i32,
+    }
+
+    impl fmt::Display for Fraction {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            match self.denominator {
+                1 => write!(formatter, "{}", self.numerator),
+                denominator => write!(formatter, "{}/{}", self.numerator, denominator),
+            }
+        }
+    }
+
+    impl Fraction {
+        fn scale(self, size: i32) -> i32 {
+            (size as i64 * self.numerator as i64 / self.denominator as i64) as i32
+        }
+
+        fn is_within(self, minimum: i32, maximum: i32) -> bool {
+            let (numerator, denominator) = (self.numerator as i64, self.denominator as i64);
+            numerator >= minimum as i64 * denominator && numerator <= maximum as i64 * denominator
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Point {
+        x: Fraction,
+        y: Fraction,
+        /// Pulls the stroke into a curve between its neighbours instead of
+        /// being passed through.
+        is_control: bool,
+    }
+
+    /// Vector glyphs on a grid normalized to the cell size.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Font {
+        glyphs: HashMap<char, Vec<Vec<Point>>>,
+        /// How far the glyphs reach past the cell at most, in cells across
+        /// and down.
+        reach: (f64, f64),
+    }
+
+    impl Default for Font {
+        fn default() -> Font {
+            Font::builtin()
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct FontError {
+        pub line: usize,
+        pub message: String,
+    }
+
+    impl FontError {
+        fn new(line: usize, message: &str) -> FontError {
+            FontError {
+                line,
+                message: message.to_string(),
+            }
+        }
+    }
+
+    impl fmt::Display for FontError {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "line {}: {}", self.line, self.message)
+        }
+    }
+
+    impl FromStr for Font {
+        type Err = FontError;
+
+        /// Parses the font format described at the top of `default.font`.
+        fn from_str(content: &str) -> Result<Font, FontError> {
+            let mut glyphs = HashMap::new();
+            for (line_index, raw_line) in content.lines().enumerate() {
+                let line_number = line_index + 1;
+                let line = raw_line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let mut parts = line.splitn(2, char::is_whitespace);
+                let key = parts.next().unwrap_or_default();
+                let character = parse_character(key).ok_or_else(|| {
+                    FontError::new(
+                        line_number,
+                        &format!(
+                            "expected a character or a code point like U+0041, found \"{}\"",
+                            key
+                        ),
+                    )
+                })?;
+                let strokes = parts
+                    .next()
+                    .unwrap_or_default()
+                    .split('|')
+                    .map(parse_stroke)
+                    .collect::<Result<Vec<Vec<Point>>, String>>()
+                    .map_err(|message| FontError::new(line_number, &message))?;
+                if glyphs.insert(character, strokes).is_some() {
+                    return Err(FontError::new(
+                        line_number,
+                        &format!("duplicate glyph \"{}\"", key),
+                    ));
+                }
+            }
+            let reach = get_reach(&glyphs);
+            Ok(Font { glyphs, reach })
+        }
+    }
+
+    impl Font {
+        /// The font shipped with the editor, `default.font`.
+        pub fn builtin() -> Font {
+            // Parsed by the tests.
+            Font::from_str(DEFAULT_FONT).unwrap()
+        }
+
+        /// The built-in font with the glyphs of the font file at `path`
+        /// added or replaced.
+        pub fn load(path: &Path) -> Result<Font, String> {
+            let content = fs::read_to_string(path)
+                .map_err(|error| format!("could not read {}: {}", path.display(), error))?;
+            let user_font = Font::from_str(&content)
+                .map_err(|error| format!("in {}: {}", path.display(), error))?;
+            let mut font = Font::builtin();
+            font.extend(user_font);
+            Ok(font)
+        }
+
+        /// Adds the glyphs of `font`, replacing those of the same characters.
+        pub fn extend(&mut self, font: Font) {
+            self.glyphs.extend(font.glyphs);
+            self.reach = get_reach(&self.glyphs);
+        }
+
+        /// How many pixels the glyphs reach past the edges of a cell of this
+        /// size at most.
+        pub fn get_overflow(&self, character_width: i32, character_height: i32) -> i32 {
+            let (across, down) = self.reach;
+            let across = (across * character_width as f64).ceil() as i32;
+            let down = (down * character_height as f64).ceil() as i32;
+            across.max(down)
+        }
+
+        /// The strokes of `character` scaled to the cell size, empty if the
+        /// font has no glyph for it.
+        pub fn get_strokes(
+            &self,
+            character: &char,
+            character_width: i32,
+            character_height: i32,
+        ) -> Vec<Vec<(i32, i32)>> {
+            match self.glyphs.get(character) {
+                Some(strokes) => strokes
+                    .iter()
+                    .map(|stroke| scale_stroke(stroke, character_width, character_height))
+                    .collect(),
+                None => vec![],
+            }
+        }
+
+        /// The points of `character` rounded to `grid` steps per cell, each
+        /// with whether it is a curve point.
+        pub fn get_points(&self, character: &char, grid: i32) -> Vec<Vec<(i32, i32, bool)>> {
+            let round = |fraction: Fraction| {
+                (fraction.numerator as f64 * grid as f64 / fraction.denominator as f64).round()
+                    as i32
+            };
+            self.get_fractions(character)
+                .into_iter()
+                .map(|stroke| {
+                    stroke
+                        .into_iter()
+                        .map(|(x, y, is_control)| (round(x), round(y), is_control))
+                        .collect()
+                })
+                .collect()
+        }
+
+        /// The points of `character` as written in the font file, each with
+        /// whether it is a curve point.
+        pub fn get_fractions(&self, character: &char) -> Vec<Vec<(Fraction, Fraction, bool)>> {
+            self.glyphs.get(character).map_or(vec![], |strokes| {
+                strokes
+                    .iter()
+                    .map(|stroke| {
+                        stroke
+                            .iter()
+                            .map(|point| (point.x, point.y, point.is_control))
+                            .collect()
+                    })
+                    .collect()
+            })
+        }
+    }
+
+    /// Replaces the line of the glyph `line` is for in `content`, or appends
+    /// it if there is none.
+    pub fn set_glyph_line(content: &str, line: &str) -> String {
+        let get_character = |line: &str| {
+            line.split_whitespace()
+                .next()
+                .filter(|_| !line.trim_start().starts_with('#'))
+                .and_then(parse_character)
+        };
+        let character = get_character(line);
+        let mut lines = content.lines().map(String::from).collect::<Vec<String>>();
+        match lines
+            .iter()
+            .position(|existing| character.is_some() && get_character(existing) == character)
+        {
+            Some(index) => lines[index] = line.to_string(),
+            None => lines.push(line.to_string()),
+        }
+        let mut content = lines.join("\n");
+        content.push('\n');
+        content
+    }
+
+    /// The character as written in font files, code points for `#` and
+    /// whitespace.
+    pub fn format_character(character: char) -> String {
+        if character == '#' || character.is_whitespace() || character.is_control() {
+            format!("U+{:04X}", character as u32)
+        } else {
+            character.to_string()
+        }
+    }
+
+    fn get_reach(glyphs: &HashMap<char, Vec<Vec<Point>>>) -> (f64, f64) {
+        let past_the_cell = |fraction: Fraction| {
+            let position = fraction.numerator as f64 / fraction.denominator as f64;
+            (-position).max(position - 1.0).max(0.0)
+        };
+        glyphs
+            .values()
+            .flatten()
+            .flatten()
+            .fold((0.0, 0.0), |(across, down), point| {
+                (
+                    past_the_cell(point.x).max(across),
+                    past_the_cell(point.y).max(down),
+                )
+            })
+    }
+
+    fn scale_stroke(
+        stroke: &[Point],
+        character_width: i32,
+        character_height: i32,
+    ) -> Vec<(i32, i32)> {
+        let position = |point: &Point| {
+            (
+                point.x.scale(character_width),
+                point.y.scale(character_height),
+            )
+        };
+        let mut coords = vec![];
+        for (index, point) in stroke.iter().enumerate() {
+            if point.is_control {
+                continue;
+            }
+            if index > 0 && stroke[index - 1].is_control {
+                let curve = get_curve(
+                    position(&stroke[index - 2]),
+                    position(&stroke[index - 1]),
+                    position(point),
+                );
+                coords.extend(curve.into_iter().skip(1));
+            } else {
+                coords.push(position(point));
+            }
+        }
+        coords
+    }
+
+    /// A quadratic Bézier curve from `start` to `end` pulled towards
+    /// `control`, flattened to a polyline.
+    pub fn get_curve(start: (i32, i32), control: (i32, i32), end: (i32, i32)) -> Vec<(i32, i32)> {
+        let segments = 8;
+        (0..=segments)
+            .map(|index| {
+                let (t, u) = (index, segments - index);
+                let coordinate = |start: i32, control: i32, end: i32| {
+                    (u * u * start + 2 * u * t * control + t * t * end) / (segments * segments)
+                };
+                (
+                    coordinate(start.0, control.0, end.0),
+                    coordinate(start.1, control.1, end.1),
+                )
+            })
+            .collect()
+    }
+
+    /// A single character, or `U+` followed by its hex code point.
+    fn parse_character(key: &str) -> Option<char> {
+        if key.starts_with("U+") && key.len() > 2 {
+            return u32::from_str_radix(&key[2..], 16)
+                .ok()
+                .and_then(std::char::from_u32);
+        }
+        let mut characters = key.chars();
+        match (characters.next(), characters.next()) {
+            (Some(character), None) => Some(character),
+            _ => None,
+        }
+    }
+
+    fn parse_stroke(stroke: &str) -> Result<Vec<Point>, String> {
+        let points = stroke
+            .split_whitespace()
+            .map(|point| {
+                parse_point(point)
+                    .ok_or_else(|| format!("expected a point like 1/2,1, found \"{}\"", point))
+            })
+            .collect::<Result<Vec<Point>, String>>()?;
+        if points.len() < 2 {
+            return Err(String::from("a stroke needs at least two points"));
+        }
+        for (index, point) in points.iter().enumerate() {
+            let is_between_points = index > 0
+                && index < points.len() - 1
+                && !points[index - 1].is_control
+                && !points[index + 1].is_control;
+            if point.is_control && !is_between_points {
+                return Err(String::from("a curve point needs a point on either side"));
+            }
+            for coordinate in [point.x, point.y].iter() {
+                if !coordinate.is_within(-MARGIN, 1 + MARGIN) {
+                    return Err(format!(
+                        "a point is too far outside of the cell, expected coordinates between {} and {}",
+                        -MARGIN,
+                        1 + MARGIN
+                    ));
+                }
+            }
+        }
+        Ok(points)
+    }
+
+    fn parse_point(point: &str) -> Option<Point> {
+        let is_control = point.starts_with('~');
+        let point = point.trim_start_matches('~');
+        let mut coordinates = point.split(',');
+        match (coordinates.next(), coordinates.next(), coordinates.next()) {
+            (Some(x), Some(y), None) => Some(Point {
+                x: parse_fraction(x)?,
+                y: parse_fraction(y)?,
+                is_control,
+            }),
+            _ => None,
+        }
+    }
+
+    /// An integer or a fraction like `-3/4`.
+    fn parse_fraction(fraction: &str) -> Option<Fraction> {
+        let mut parts = fraction.split('/');
+        let numerator = parts.next()?.parse::<i32>().ok()?;
+        let denominator = match parts.next() {
+            Some(denominator) => denominator.parse::<i32>().ok()?,
+            None => 1,
+        };
+        if denominator <= 0 || parts.next().is_some() {
+            return None;
+        }
+        Some(Fraction {
+            numerator,
+            denominator,
+        })
+    }
+}
@@ -0,0 +1,150 @@
+#[path = "font.rs"]
+mod font;
+
+#[cfg(test)]
+mod tests {
+    use super::font::font::{format_character, get_curve, set_glyph_line, Font};
+    use std::env;
+    use std::fs;
+    use std::str::FromStr;
+    #[test]
+    fn builtin_font_has_ascii() {
+        let font = Font::builtin();
+        for character in '!'..='~' {
+            assert!(
+                !font.get_strokes(&character, 10, 16).is_empty(),
+                "{}",
+                character
+            );
+        }
+        assert!(font.get_strokes(&' ', 10, 16).is_empty());
+    }
+    #[test]
+    fn fractions_scale_to_the_cell() {
+        let font = Font::from_str("x 0,0 1/3,5/4 | -1/4,1 1,-1/3").unwrap();
+        assert_eq!(
+            font.get_strokes(&'x', 10, 16),
+            vec![vec![(0, 0), (3, 20)], vec![(-2, 16), (10, -5)]]
+        );
+    }
+    #[test]
+    fn comments_and_code_points() {
+        let font = Font::from_str("# comment\n\nU+0023 0,0 1,1\n  | 1/2,0 1/2,1\n").unwrap();
+        assert_eq!(font.get_strokes(&'#', 8, 8), vec![vec![(0, 0), (8, 8)]]);
+        assert_eq!(font.get_strokes(&'|', 8, 8), vec![vec![(4, 0), (4, 8)]]);
+    }
+    #[test]
+    fn curve_points() {
+        let font = Font::from_str("( 1,0 ~0,1/2 1,1 1,2").unwrap();
+        let mut expected = get_curve((8, 0), (0, 8), (8, 16));
+        expected.push((8, 32));
+        assert_eq!(font.get_strokes(&'(', 8, 16), vec![expected]);
+    }
+    #[test]
+    fn get_points_rounds_to_the_grid() {
+        let font = Font::from_str("a 0,1/3 3/7,-1/4 | 1/2,0 ~1,1/2 1/2,1").unwrap();
+        assert_eq!(
+            font.get_points(&'a', 12),
+            vec![
+                vec![(0, 4, false), (5, -3, false)],
+                vec![(6, 0, false), (12, 6, true), (6, 12, false)]
+            ]
+        );
+        assert!(font.get_points(&'b', 12).is_empty());
+    }
+    #[test]
+    fn set_glyph_line_replaces_or_appends() {
+        let content = "# a comment\na 0,0 1,1\nU+0023 0,0 1,0";
+        assert_eq!(
+            set_glyph_line(content, "a 1,1 0,0"),
+            "# a comment\na 1,1 0,0\nU+0023 0,0 1,0\n"
+        );
+        assert_eq!(
+            set_glyph_line(content, "# 0,1 1,1"),
+            "# a comment\na 0,0 1,1\nU+0023 0,0 1,0\n# 0,1 1,1\n"
+        );
+        assert_eq!(
+            set_glyph_line(content, "U+0023 0,1 1,1"),
+            "# a comment\na 0,0 1,1\nU+0023 0,1 1,1\n"
+        );
+        assert_eq!(set_glyph_line("", "b 0,0 1,1"), "b 0,0 1,1\n");
+    }
+    #[test]
+    fn format_character_escapes_comments_and_whitespace() {
+        assert_eq!(format_character('a'), "a");
+        assert_eq!(format_character('#'), "U+0023");
+        assert_eq!(format_character(' '), "U+0020");
+        assert_eq!(format_character('\u{1b}'), "U+001B");
+    }
+    fn error(content: &str) -> String {
+        Font::from_str(content).unwrap_err().to_string()
+    }
+    #[test]
+    fn errors() {
+        assert_eq!(
+            error("a 0,0 1,1\nab 0,0 1,1"),
+            "line 2: expected a character or a code point like U+0041, found \"ab\""
+        );
+        assert_eq!(
+            error("a 0,0 1"),
+            "line 1: expected a point like 1/2,1, found \"1\""
+        );
+        assert_eq!(
+            error("a 0,0 1/0,1"),
+            "line 1: expected a point like 1/2,1, found \"1/0,1\""
+        );
+        assert_eq!(error("a 0,0"), "line 1: a stroke needs at least two points");
+        assert_eq!(error("a"), "line 1: a stroke needs at least two points");
+        assert_eq!(
+            error("a 0,0 1,1 |"),
+            "line 1: a stroke needs at least two points"
+        );
+        assert_eq!(
+            error("a 0,0 ~1,1"),
+            "line 1: a curve point needs a point on either side"
+        );
+        assert_eq!(
+            error("a 0,0 ~1,1 ~1,0 0,1"),
+            "line 1: a curve point needs a point on either side"
+        );
+        assert_eq!(
+            error("a 0,0 5/2,1"),
+            "line 1: a point is too far outside of the cell, expected coordinates between -1 and 2"
+        );
+        assert_eq!(
+            error("a 0,0 1,1\n\na 1,1 0,0"),
+            "line 3: duplicate glyph \"a\""
+        );
+    }
+    #[test]
+    fn load_adds_and_replaces_glyphs() {
+        let path = env::temp_dir().join(format!("editor-font-{}.font", std::process::id()));
+        fs::write(&path, "a 0,0 1,1\n€ 0,1 1,0").unwrap();
+        let font = Font::load(&path).unwrap();
+        let builtin = Font::builtin();
+        assert_eq!(font.get_strokes(&'a', 8, 8), vec![vec![(0, 0), (8, 8)]]);
+        assert_eq!(font.get_strokes(&'€', 8, 8), vec![vec![(0, 8), (8, 0)]]);
+        assert_eq!(
+            font.get_strokes(&'b', 8, 8),
+            builtin.get_strokes(&'b', 8, 8)
+        );
+
+        fs::write(&path, "a 0,0 1,1\nb 0,0").unwrap();
+        let error = Font::load(&path).unwrap_err();
+        assert!(error.contains(path.to_str().unwrap()));
+        assert!(error.contains("line 2"));
+        fs::remove_file(&path).unwrap();
+        assert!(Font::load(&path).unwrap_err().starts_with("could not read"));
+    }
+    #[test]
+    fn overflow_is_how_far_glyphs_reach_past_the_cell() {
+        let inside = Font::from_str("a 0,0 1,1").unwrap();
+        assert_eq!(inside.get_overflow(10, 16), 0);
+        let mut font = Font::from_str("g 0,1/2 1,5/4\n- -1/8,1/2 9/8,1/2").unwrap();
+        assert_eq!(font.get_overflow(10, 16), 4);
+        assert_eq!(font.get_overflow(48, 16), 6);
+        font.extend(Font::from_str("g 0,0 1,1").unwrap());
+        assert_eq!(font.get_overflow(10, 16), 2);
+        assert!(Font::builtin().get_overflow(10, 16) >= 4);
+    }
+}
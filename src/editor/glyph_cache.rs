@@ -0,0 +1,449 @@
+pub mod glyph_cache {
+    use super::super::config::config::{get_character_x, get_character_y, Layout, StrokeStyle};
+    use super::super::font::font::Font;
+    use super::super::image_rendering::image_rendering::ImageRenderer;
+    use super::super::rasterizer::rasterizer::{get_coverage_image, rasterize_stroke};
+    use super::super::rendering::rendering::{
+        draw_character, draw_wide_lines, PlacedGlyph, Renderer,
+    };
+    use image::RgbaImage;
+    use sdl2::pixels::{Color, PixelFormatEnum};
+    use sdl2::rect::Rect;
+    use sdl2::render::{BlendMode, Texture, TextureCreator, WindowCanvas};
+    use sdl2::video::WindowContext;
+    use std::collections::{HashMap, HashSet};
+    use std::hash::Hash;
+
+    /// Width and height of the glyph atlas texture.
+    const ATLAS_SIZE: u32 = 1024;
+
+    /// Pixels around a cell that glyphs and their strokes may reach past
+    /// its edges, like the descenders below it.
+    pub fn get_padding(font: &Font, layout: &Layout, stroke_width: u32) -> i32 {
+        font.get_overflow(layout.character_width, layout.character_height) + stroke_width as i32 + 1
+    }
+
+    /// What tells rasterized glyphs apart.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct GlyphKey {
+        pub character: char,
+        pub character_width: i32,
+        pub character_height: i32,
+        pub stroke_width: u32,
+        pub color: Color,
+    }
+
+    /// Where glyphs sit in an atlas of equal cells, filled row by row and
+    /// emptied when it is full or the cell size changes.
+    pub struct GlyphAtlas {
+        width: u32,
+        height: u32,
+        cell_width: u32,
+        cell_height: u32,
+        slots: HashMap<GlyphKey, Rect>,
+    }
+
+    impl GlyphAtlas {
+        pub fn new(width: u32, height: u32) -> GlyphAtlas {
+            GlyphAtlas {
+                width,
+                height,
+                cell_width: 0,
+                cell_height: 0,
+                slots: HashMap::new(),
+            }
+        }
+        /// The cell of `key` and whether the glyph still has to be drawn
+        /// into it, `None` if a cell this big does not fit the atlas.
+        pub fn place(
+            &mut self,
+            key: GlyphKey,
+            cell_width: u32,
+            cell_height: u32,
+        ) -> Option<(Rect, bool)> {
+            if cell_width == 0 || cell_height == 0 {
+                return None;
+            }
+            if cell_width > self.width || cell_height > self.height {
+                return None;
+            }
+            if (cell_width, cell_height) != (self.cell_width, self.cell_height) {
+                self.slots.clear();
+                self.cell_width = cell_width;
+                self.cell_height = cell_height;
+            }
+            if let Some(rect) = self.slots.get(&key) {
+                return Some((*rect, false));
+            }
+            let columns = self.width / cell_width;
+            if self.slots.len() as u32 == columns * (self.height / cell_height) {
+                self.slots.clear();
+            }
+            let index = self.slots.len() as u32;
+            let rect = Rect::new(
+                ((index % columns) * cell_width) as i32,
+                ((index / columns) * cell_height) as i32,
+                cell_width,
+                cell_height,
+            );
+            self.slots.insert(key, rect);
+            Some((rect, true))
+        }
+        pub fn clear(&mut self) {
+            self.slots.clear();
+        }
+    }
+
+    /// What tells rendered glyph runs apart, wherever they are.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct RunKey {
+        glyphs: Vec<(char, i32, Color)>,
+        character_width: i32,
+        character_height: i32,
+        character_gap: i32,
+        stroke_width: u32,
+    }
+
+    impl RunKey {
+        pub fn new(glyphs: &[PlacedGlyph], layout: &Layout, stroke_width: u32) -> RunKey {
+            RunKey {
+                glyphs: glyphs
+                    .iter()
+                    .map(|glyph| (glyph.character, glyph.column, glyph.color))
+                    .collect(),
+                character_width: layout.character_width,
+                character_height: layout.character_height,
+                character_gap: layout.character_gap,
+                stroke_width,
+            }
+        }
+    }
+
+    /// What tells smooth strokes apart, drawn where their points are.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct StrokeKey {
+        pub points: Vec<(i32, i32)>,
+        pub width: u32,
+        pub color: Color,
+    }
+
+    /// Rendered glyph runs or strokes by what they show. An edited line
+    /// shows other glyphs, so it is rendered again, and entries a frame did
+    /// not use are dropped when it ends.
+    pub struct FrameCache<K, T> {
+        entries: HashMap<K, T>,
+        used: HashSet<K>,
+    }
+
+    impl<K: Clone + Eq + Hash, T> FrameCache<K, T> {
+        pub fn new() -> FrameCache<K, T> {
+            FrameCache {
+                entries: HashMap::new(),
+                used: HashSet::new(),
+            }
+        }
+        pub fn get(&mut self, key: &K) -> Option<&T> {
+            let value = self.entries.get(key)?;
+            self.used.insert(key.clone());
+            Some(value)
+        }
+        pub fn insert(&mut self, key: K, value: T) {
+            self.used.insert(key.clone());
+            self.entries.insert(key, value);
+        }
+        pub fn end_frame(&mut self) {
+            let used = std::mem::take(&mut self.used);
+            self.entries.retain(|key, _| used.contains(key));
+        }
+        pub fn clear(&mut self) {
+            self.entries.clear();
+            self.used.clear();
+        }
+    }
+
+    /// `glyphs` of one line on a transparent image that starts `padding`
+    /// pixels above and left of the first cell. The colors are kept apart
+    /// from the alpha, so the image can be blended.
+    pub fn rasterize_glyph_run(
+        glyphs: &[PlacedGlyph],
+        layout: &Layout,
+        font: &Font,
+        stroke_width: u32,
+        stroke_style: StrokeStyle,
+    ) -> RgbaImage {
+        let padding = get_padding(font, layout, stroke_width);
+        let first = glyphs.iter().map(|glyph| glyph.column).min().unwrap_or(0);
+        let last = glyphs.iter().map(|glyph| glyph.column).max().unwrap_or(0);
+        let column_width = layout.character_width + layout.character_gap;
+        let mut local = layout.clone();
+        local.x_offset = padding - first * column_width;
+        local.y_offset = padding;
+        let width = (last - first) * column_width + layout.character_width + 2 * padding;
+        let height = layout.character_height + 2 * padding;
+        let mut image = RgbaImage::new(width.max(1) as u32, height.max(1) as u32);
+        let mut renderer = ImageRenderer::with_stroke_style(&mut image, stroke_style);
+        for glyph in glyphs {
+            let glyph = PlacedGlyph {
+                line: 0,
+                ..glyph.clone()
+            };
+            renderer.draw_glyph(&local, font, &glyph, stroke_width);
+        }
+        unpremultiply(&mut image);
+        image
+    }
+
+    /// Smooth strokes blend into the transparent black they are drawn on,
+    /// which darkens their colors along the edges.
+    fn unpremultiply(image: &mut RgbaImage) {
+        for pixel in image.pixels_mut() {
+            let alpha = pixel[3] as u32;
+            if alpha == 0 || alpha == 255 {
+                continue;
+            }
+            for channel in 0..3 {
+                pixel[channel] = (pixel[channel] as u32 * 255 / alpha).min(255) as u8;
+            }
+        }
+    }
+
+    fn create_texture<'a>(
+        texture_creator: &'a TextureCreator<WindowContext>,
+        image: &RgbaImage,
+    ) -> Texture<'a> {
+        // The bytes of ABGR8888 are in RGBA order on little-endian machines.
+        let mut texture = texture_creator
+            .create_texture_static(PixelFormatEnum::ABGR8888, image.width(), image.height())
+            .unwrap();
+        texture
+            .update(None, image, image.width() as usize * 4)
+            .unwrap();
+        texture.set_blend_mode(BlendMode::Blend);
+        texture
+    }
+
+    /// Glyphs, smooth strokes and, if asked for, whole glyph runs kept in
+    /// textures between frames.
+    pub struct TextureCache<'a> {
+        texture_creator: &'a TextureCreator<WindowContext>,
+        stroke_style: StrokeStyle,
+        atlas: Option<GlyphAtlas>,
+        atlas_texture: Option<Texture<'a>>,
+        lines: Option<FrameCache<RunKey, Texture<'a>>>,
+        /// Each texture with where it is drawn.
+        strokes: FrameCache<StrokeKey, (Texture<'a>, Rect)>,
+    }
+
+    impl<'a> TextureCache<'a> {
+        pub fn new(
+            texture_creator: &'a TextureCreator<WindowContext>,
+            stroke_style: StrokeStyle,
+            is_caching_glyphs: bool,
+            is_caching_lines: bool,
+        ) -> TextureCache<'a> {
+            TextureCache {
+                texture_creator,
+                stroke_style,
+                atlas: if is_caching_glyphs {
+                    Some(GlyphAtlas::new(ATLAS_SIZE, ATLAS_SIZE))
+                } else {
+                    None
+                },
+                atlas_texture: None,
+                lines: if is_caching_lines {
+                    Some(FrameCache::new())
+                } else {
+                    None
+                },
+                strokes: FrameCache::new(),
+            }
+        }
+        /// Forgets everything drawn so far, for when the font changes.
+        pub fn clear(&mut self) {
+            if let Some(atlas) = self.atlas.as_mut() {
+                atlas.clear();
+            }
+            if let Some(lines) = self.lines.as_mut() {
+                lines.clear();
+            }
+            self.strokes.clear();
+        }
+        pub fn end_frame(&mut self) {
+            if let Some(lines) = self.lines.as_mut() {
+                lines.end_frame();
+            }
+            self.strokes.end_frame();
+        }
+    }
+
+    /// Draws into the window like the canvas itself, but takes glyphs from
+    /// the texture cache.
+    pub struct CachedCanvas<'c, 'a> {
+        canvas: &'c mut WindowCanvas,
+        cache: &'c mut TextureCache<'a>,
+        color: Color,
+    }
+
+    impl<'c, 'a> CachedCanvas<'c, 'a> {
+        pub fn new(
+            canvas: &'c mut WindowCanvas,
+            cache: &'c mut TextureCache<'a>,
+        ) -> CachedCanvas<'c, 'a> {
+            CachedCanvas {
+                canvas,
+                cache,
+                color: Color::RGB(0, 0, 0),
+            }
+        }
+        fn draw_uncached_glyph(
+            &mut self,
+            layout: &Layout,
+            font: &Font,
+            glyph: &PlacedGlyph,
+            stroke_width: u32,
+        ) {
+            self.set_color(glyph.color);
+            draw_character(
+                self,
+                layout,
+                font,
+                glyph.character,
+                glyph.column,
+                glyph.line,
+                stroke_width,
+            );
+        }
+    }
+
+    impl<'c, 'a> Renderer for CachedCanvas<'c, 'a> {
+        fn set_color(&mut self, color: Color) {
+            self.color = color;
+            self.canvas.set_draw_color(color);
+        }
+        fn clear(&mut self) {
+            self.canvas.clear();
+        }
+        fn draw_line(&mut self, from: (i32, i32), to: (i32, i32)) {
+            Renderer::draw_line(&mut *self.canvas, from, to);
+        }
+        fn fill_rect(&mut self, left: i32, top: i32, width: u32, height: u32) {
+            Renderer::fill_rect(&mut *self.canvas, left, top, width, height);
+        }
+        fn draw_lines(&mut self, points: &[(i32, i32)]) {
+            Renderer::draw_lines(&mut *self.canvas, points);
+        }
+        fn draw_stroke(&mut self, points: &[(i32, i32)], width: u32) {
+            match self.cache.stroke_style {
+                StrokeStyle::Crisp => draw_wide_lines(self, points, width),
+                StrokeStyle::Smooth => {
+                    let key = StrokeKey {
+                        points: points.to_vec(),
+                        width,
+                        color: self.color,
+                    };
+                    let cache = &mut *self.cache;
+                    if cache.strokes.get(&key).is_none() {
+                        let coverage = rasterize_stroke(points, width as f32, true);
+                        let image = get_coverage_image(&coverage, self.color);
+                        let texture = create_texture(cache.texture_creator, &image);
+                        let target =
+                            Rect::new(coverage.left, coverage.top, coverage.width, coverage.height);
+                        cache.strokes.insert(key.clone(), (texture, target));
+                    }
+                    let (texture, target) = cache.strokes.get(&key).unwrap();
+                    if self.canvas.copy(texture, None, *target).is_err() {
+                        draw_wide_lines(self, points, width);
+                    }
+                }
+            }
+        }
+        fn draw_glyph(
+            &mut self,
+            layout: &Layout,
+            font: &Font,
+            glyph: &PlacedGlyph,
+            stroke_width: u32,
+        ) {
+            let padding = get_padding(font, layout, stroke_width);
+            let key = GlyphKey {
+                character: glyph.character,
+                character_width: layout.character_width,
+                character_height: layout.character_height,
+                stroke_width,
+                color: glyph.color,
+            };
+            let cell_width = (layout.character_width + 2 * padding) as u32;
+            let cell_height = (layout.character_height + 2 * padding) as u32;
+            let cache = &mut *self.cache;
+            let placed = cache
+                .atlas
+                .as_mut()
+                .and_then(|atlas| atlas.place(key, cell_width, cell_height));
+            let (source, is_new) = match placed {
+                Some(placed) => placed,
+                None => return self.draw_uncached_glyph(layout, font, glyph, stroke_width),
+            };
+            let texture_creator = cache.texture_creator;
+            let texture = cache.atlas_texture.get_or_insert_with(|| {
+                let mut texture = texture_creator
+                    .create_texture_static(PixelFormatEnum::ABGR8888, ATLAS_SIZE, ATLAS_SIZE)
+                    .unwrap();
+                texture.set_blend_mode(BlendMode::Blend);
+                texture
+            });
+            if is_new {
+                let cell = PlacedGlyph {
+                    column: 0,
+                    ..glyph.clone()
+                };
+                let image =
+                    rasterize_glyph_run(&[cell], layout, font, stroke_width, cache.stroke_style);
+                texture
+                    .update(source, &image, image.width() as usize * 4)
+                    .unwrap();
+            }
+            let target = Rect::new(
+                get_character_x(layout, glyph.column) - padding,
+                get_character_y(layout, glyph.line) - padding,
+                cell_width,
+                cell_height,
+            );
+            self.canvas.copy(texture, source, target).unwrap();
+        }
+        fn draw_glyph_run(
+            &mut self,
+            layout: &Layout,
+            font: &Font,
+            glyphs: &[PlacedGlyph],
+            stroke_width: u32,
+        ) {
+            let cache = &mut *self.cache;
+            let lines = match (cache.lines.as_mut(), glyphs.first()) {
+                (Some(lines), Some(_)) => lines,
+                _ => {
+                    for glyph in glyphs {
+                        self.draw_glyph(layout, font, glyph, stroke_width);
+                    }
+                    return;
+                }
+            };
+            let key = RunKey::new(glyphs, layout, stroke_width);
+            if lines.get(&key).is_none() {
+                let image =
+                    rasterize_glyph_run(glyphs, layout, font, stroke_width, cache.stroke_style);
+                lines.insert(key.clone(), create_texture(cache.texture_creator, &image));
+            }
+            let texture = lines.get(&key).unwrap();
+            let query = texture.query();
+            let padding = get_padding(font, layout, stroke_width);
+            let first_column = glyphs.iter().map(|glyph| glyph.column).min().unwrap();
+            let target = Rect::new(
+                get_character_x(layout, first_column) - padding,
+                get_character_y(layout, glyphs[0].line) - padding,
+                query.width,
+                query.height,
+            );
+            self.canvas.copy(texture, None, target).unwrap();
+        }
+    }
+}
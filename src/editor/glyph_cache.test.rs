@@ -0,0 +1,268 @@
+#[path = "glyph_cache.rs"]
+mod glyph_cache;
+
+#[path = "image_rendering.rs"]
+mod image_rendering;
+
+#[path = "rendering.rs"]
+mod rendering;
+
+#[path = "scrollbar.rs"]
+mod scrollbar;
+
+#[path = "rasterizer.rs"]
+mod rasterizer;
+
+#[path = "config.rs"]
+mod config;
+
+#[path = "commands.rs"]
+mod commands;
+
+#[path = "cursor.rs"]
+mod cursor;
+
+#[path = "kill_ring.rs"]
+mod kill_ring;
+
+#[path = "keymap.rs"]
+mod keymap;
+
+#[path = "font.rs"]
+mod font;
+
+#[path = "highlighting.rs"]
+mod highlighting;
+
+#[path = "theme.rs"]
+mod theme;
+
+#[path = "text_rendering.rs"]
+mod text_rendering;
+
+#[cfg(test)]
+mod tests {
+    use super::config::config::{Layout, StrokeStyle};
+    use super::font::font::Font;
+    use super::glyph_cache::glyph_cache::{
+        get_padding, rasterize_glyph_run, FrameCache, GlyphAtlas, GlyphKey, RunKey, StrokeKey,
+    };
+    use super::image_rendering::image_rendering::ImageRenderer;
+    use super::rendering::rendering::{PlacedGlyph, Renderer};
+    use image::{Rgba, RgbaImage};
+    use sdl2::pixels::Color;
+    use sdl2::rect::Rect;
+    fn key(character: char) -> GlyphKey {
+        GlyphKey {
+            character,
+            character_width: 10,
+            character_height: 16,
+            stroke_width: 1,
+            color: Color::RGB(255, 255, 255),
+        }
+    }
+    fn glyph(character: char, column: i32, color: Color) -> PlacedGlyph {
+        PlacedGlyph {
+            character,
+            column,
+            line: 0,
+            color,
+        }
+    }
+    #[test]
+    fn atlas_reuses_cells_of_known_glyphs() {
+        let mut atlas = GlyphAtlas::new(40, 40);
+        assert_eq!(
+            atlas.place(key('a'), 20, 20),
+            Some((Rect::new(0, 0, 20, 20), true))
+        );
+        assert_eq!(
+            atlas.place(key('b'), 20, 20),
+            Some((Rect::new(20, 0, 20, 20), true))
+        );
+        assert_eq!(
+            atlas.place(key('c'), 20, 20),
+            Some((Rect::new(0, 20, 20, 20), true))
+        );
+        assert_eq!(
+            atlas.place(key('a'), 20, 20),
+            Some((Rect::new(0, 0, 20, 20), false))
+        );
+        let red = GlyphKey {
+            color: Color::RGB(255, 0, 0),
+            ..key('a')
+        };
+        assert_eq!(
+            atlas.place(red, 20, 20),
+            Some((Rect::new(20, 20, 20, 20), true))
+        );
+    }
+    #[test]
+    fn atlas_starts_over_when_full_or_resized() {
+        let mut atlas = GlyphAtlas::new(20, 20);
+        assert_eq!(
+            atlas.place(key('a'), 20, 20),
+            Some((Rect::new(0, 0, 20, 20), true))
+        );
+        assert_eq!(
+            atlas.place(key('b'), 20, 20),
+            Some((Rect::new(0, 0, 20, 20), true))
+        );
+        assert_eq!(
+            atlas.place(key('a'), 20, 20),
+            Some((Rect::new(0, 0, 20, 20), true))
+        );
+        assert_eq!(
+            atlas.place(key('a'), 10, 10),
+            Some((Rect::new(0, 0, 10, 10), true))
+        );
+        assert_eq!(atlas.place(key('a'), 30, 10), None);
+        atlas.clear();
+        assert_eq!(
+            atlas.place(key('a'), 10, 10),
+            Some((Rect::new(0, 0, 10, 10), true))
+        );
+    }
+    #[test]
+    fn frame_cache_keeps_what_the_last_frame_used() {
+        let layout = Layout::default();
+        let white = Color::RGB(255, 255, 255);
+        let before = RunKey::new(&[glyph('a', 0, white)], &layout, 1);
+        let edited = RunKey::new(&[glyph('a', 0, white), glyph('b', 1, white)], &layout, 1);
+        let mut cache = FrameCache::new();
+        cache.insert(before.clone(), 1);
+        cache.end_frame();
+        assert_eq!(cache.get(&before), Some(&1));
+        assert_eq!(cache.get(&edited), None);
+        cache.insert(edited.clone(), 2);
+        cache.end_frame();
+        cache.get(&edited);
+        cache.end_frame();
+        assert_eq!(cache.get(&before), None, "unused lines are dropped");
+        assert_eq!(cache.get(&edited), Some(&2));
+        cache.clear();
+        assert_eq!(cache.get(&edited), None);
+    }
+    #[test]
+    fn run_keys_ignore_the_line_but_not_the_color() {
+        let layout = Layout::default();
+        let white = Color::RGB(255, 255, 255);
+        let moved = PlacedGlyph {
+            line: 5,
+            ..glyph('a', 0, white)
+        };
+        assert_eq!(
+            RunKey::new(&[glyph('a', 0, white)], &layout, 1),
+            RunKey::new(&[moved], &layout, 1)
+        );
+        assert_ne!(
+            RunKey::new(&[glyph('a', 0, white)], &layout, 1),
+            RunKey::new(&[glyph('a', 0, Color::RGB(255, 0, 0))], &layout, 1)
+        );
+        assert_ne!(
+            RunKey::new(&[glyph('a', 0, white)], &layout, 1),
+            RunKey::new(&[glyph('a', 0, white)], &layout, 2)
+        );
+    }
+    #[test]
+    fn stroke_keys_tell_position_width_and_color_apart() {
+        let white = Color::RGB(255, 255, 255);
+        let stroke = |points: &[(i32, i32)], width: u32, color: Color| StrokeKey {
+            points: points.to_vec(),
+            width,
+            color,
+        };
+        let mut cache = FrameCache::new();
+        cache.insert(stroke(&[(0, 0), (4, 4)], 1, white), 1);
+        assert_eq!(cache.get(&stroke(&[(0, 0), (4, 4)], 1, white)), Some(&1));
+        assert_eq!(cache.get(&stroke(&[(1, 0), (5, 4)], 1, white)), None);
+        assert_eq!(cache.get(&stroke(&[(0, 0), (4, 4)], 2, white)), None);
+        let red = Color::RGB(255, 0, 0);
+        assert_eq!(cache.get(&stroke(&[(0, 0), (4, 4)], 1, red)), None);
+    }
+    /// Draws the run image over a background the way the window blends it.
+    fn composite(background: Rgba<u8>, run: &RgbaImage, left: i32, top: i32, into: &mut RgbaImage) {
+        for pixel in into.pixels_mut() {
+            *pixel = background;
+        }
+        for (x, y, pixel) in run.enumerate_pixels() {
+            let (x, y) = (left + x as i32, top + y as i32);
+            if x < 0 || y < 0 || x >= into.width() as i32 || y >= into.height() as i32 {
+                continue;
+            }
+            let alpha = pixel[3] as f32 / 255.0;
+            let target = into.get_pixel_mut(x as u32, y as u32);
+            for channel in 0..3 {
+                target[channel] = (target[channel] as f32 * (1.0 - alpha)
+                    + pixel[channel] as f32 * alpha)
+                    .round() as u8;
+            }
+        }
+    }
+    #[test]
+    fn rasterized_runs_match_drawing_directly() {
+        let layout = Layout::default();
+        let font = Font::builtin();
+        let background = Rgba([30, 30, 30, 255]);
+        let glyphs = vec![
+            glyph('w', 2, Color::RGB(200, 100, 50)),
+            glyph('x', 3, Color::RGB(50, 200, 100)),
+        ];
+        for &stroke_style in &[StrokeStyle::Crisp, StrokeStyle::Smooth] {
+            let mut direct = RgbaImage::from_pixel(100, 40, background);
+            let mut renderer = ImageRenderer::with_stroke_style(&mut direct, stroke_style);
+            renderer.draw_glyph_run(&layout, &font, &glyphs, 1);
+            let run = rasterize_glyph_run(&glyphs, &layout, &font, 1, stroke_style);
+            let padding = get_padding(&font, &layout, 1);
+            let left = layout.x_offset + 2 * (layout.character_width + layout.character_gap);
+            let mut cached = RgbaImage::new(100, 40);
+            composite(
+                background,
+                &run,
+                left - padding,
+                layout.y_offset - padding,
+                &mut cached,
+            );
+            for (direct, cached) in direct.pixels().zip(cached.pixels()) {
+                for channel in 0..3 {
+                    let difference = (direct[channel] as i32 - cached[channel] as i32).abs();
+                    assert!(difference <= 2, "{:?} {:?}", direct, cached);
+                }
+            }
+        }
+    }
+    #[test]
+    fn cached_descenders_are_not_cut_off() {
+        let font = Font::builtin();
+        let background = Rgba([30, 30, 30, 255]);
+        let white = Color::RGB(255, 255, 255);
+        for &zoom in &[100, 200, 300] {
+            let mut layout = Layout::default().zoomed(zoom);
+            layout.x_offset = 40;
+            layout.y_offset = 40;
+            let glyphs = vec![glyph('g', 0, white)];
+            let size = (layout.y_offset + layout.character_height * 3) as u32;
+            let mut direct = RgbaImage::from_pixel(size, size, background);
+            ImageRenderer::new(&mut direct).draw_glyph_run(&layout, &font, &glyphs, 1);
+            let run = rasterize_glyph_run(&glyphs, &layout, &font, 1, StrokeStyle::Crisp);
+            let padding = get_padding(&font, &layout, 1);
+            let mut cached = RgbaImage::new(size, size);
+            composite(
+                background,
+                &run,
+                layout.x_offset - padding,
+                layout.y_offset - padding,
+                &mut cached,
+            );
+            let below_the_cell = (layout.y_offset + layout.character_height) as u32;
+            assert!(direct
+                .enumerate_pixels()
+                .any(|(_, y, pixel)| y > below_the_cell && pixel[0] == 255));
+            assert!(
+                direct.pixels().eq(cached.pixels()),
+                "the cached g differs at {}%",
+                zoom
+            );
+        }
+    }
+}
@@ -0,0 +1,287 @@
+pub mod glyph_editor {
+    use super::super::font::font::{format_character, set_glyph_line, Font, Fraction};
+    use super::super::text_rendering::text_rendering::{
+        get_character_strokes, get_placeholder_strokes,
+    };
+    use std::fs;
+    use std::path::Path;
+    use std::str::FromStr;
+
+    /// Grid steps per cell, fine enough for the halves, thirds, quarters,
+    /// sixths and eighths of the built-in font, so its glyphs are saved as
+    /// they were loaded.
+    pub static GRID: i32 = 24;
+    /// The corners of the grid, reaching around the cell for accents and
+    /// descenders.
+    pub static GRID_MINIMUM: (i32, i32) = (-4, -8);
+    pub static GRID_MAXIMUM: (i32, i32) = (28, 32);
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct GridPoint {
+        pub x: i32,
+        pub y: i32,
+        /// Pulls the stroke into a curve instead of being passed through.
+        pub is_control: bool,
+        /// Where a loaded point off the grid really is, kept until it is
+        /// moved so that saving does not round it.
+        pub exact: Option<(Fraction, Fraction)>,
+    }
+
+    /// Edits the strokes of one glyph on a grid of `GRID` steps per cell.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct GlyphEditor {
+        pub character: char,
+        pub strokes: Vec<Vec<GridPoint>>,
+        /// Stroke and point index of the point last placed or moved. New
+        /// points are inserted after it, without one they start a new stroke.
+        pub selected: Option<(usize, usize)>,
+        pub is_dragging: bool,
+    }
+
+    impl GlyphEditor {
+        pub fn new(font: &Font, character: char) -> GlyphEditor {
+            let is_on_grid =
+                |fraction: Fraction| fraction.numerator * GRID % fraction.denominator == 0;
+            let strokes = font
+                .get_points(&character, GRID)
+                .into_iter()
+                .zip(font.get_fractions(&character))
+                .map(|(stroke, fractions)| {
+                    stroke
+                        .into_iter()
+                        .zip(fractions)
+                        .map(|((x, y, is_control), (exact_x, exact_y, _))| GridPoint {
+                            x,
+                            y,
+                            is_control,
+                            exact: if is_on_grid(exact_x) && is_on_grid(exact_y) {
+                                None
+                            } else {
+                                Some((exact_x, exact_y))
+                            },
+                        })
+                        .collect()
+                })
+                .collect();
+            GlyphEditor {
+                character,
+                strokes,
+                selected: None,
+                is_dragging: false,
+            }
+        }
+
+        fn find(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+            self.strokes
+                .iter()
+                .enumerate()
+                .rev()
+                .find_map(|(stroke_index, stroke)| {
+                    stroke
+                        .iter()
+                        .position(|point| point.x == x && point.y == y)
+                        .map(|point_index| (stroke_index, point_index))
+                })
+        }
+
+        /// Selects and starts dragging the point at `x`, `y`, placing a new
+        /// one there if there is none.
+        pub fn press(&mut self, x: i32, y: i32) {
+            self.selected = match (self.find(x, y), self.selected) {
+                (Some(found), _) => Some(found),
+                (None, Some((stroke_index, point_index))) => {
+                    let point = GridPoint {
+                        x,
+                        y,
+                        is_control: false,
+                        exact: None,
+                    };
+                    self.strokes[stroke_index].insert(point_index + 1, point);
+                    Some((stroke_index, point_index + 1))
+                }
+                (None, None) => {
+                    self.strokes.push(vec![GridPoint {
+                        x,
+                        y,
+                        is_control: false,
+                        exact: None,
+                    }]);
+                    Some((self.strokes.len() - 1, 0))
+                }
+            };
+            self.is_dragging = true;
+        }
+
+        pub fn drag(&mut self, x: i32, y: i32) {
+            if let (true, Some((stroke_index, point_index))) = (self.is_dragging, self.selected) {
+                let point = &mut self.strokes[stroke_index][point_index];
+                point.x = x;
+                point.y = y;
+                point.exact = None;
+            }
+        }
+
+        pub fn release(&mut self) {
+            self.is_dragging = false;
+        }
+
+        /// Removes the point at `x`, `y` and its stroke once it is empty.
+        pub fn delete(&mut self, x: i32, y: i32) {
+            if let Some(found) = self.find(x, y) {
+                self.remove(found);
+            }
+        }
+
+        pub fn delete_selected(&mut self) {
+            if let Some(selected) = self.selected {
+                self.remove(selected);
+            }
+        }
+
+        fn remove(&mut self, (stroke_index, point_index): (usize, usize)) {
+            self.strokes[stroke_index].remove(point_index);
+            self.selected = if self.strokes[stroke_index].is_empty() {
+                self.strokes.remove(stroke_index);
+                None
+            } else {
+                Some((stroke_index, point_index.saturating_sub(1)))
+            };
+        }
+
+        /// Makes the next point start a new stroke.
+        pub fn lift_pen(&mut self) {
+            self.selected = None;
+        }
+
+        pub fn toggle_curve(&mut self) {
+            if let Some((stroke_index, point_index)) = self.selected {
+                let point = &mut self.strokes[stroke_index][point_index];
+                point.is_control = !point.is_control;
+            }
+        }
+
+        /// The glyph in the font file format.
+        pub fn to_line(&self) -> String {
+            let strokes = self
+                .strokes
+                .iter()
+                .map(|stroke| {
+                    stroke
+                        .iter()
+                        .map(|point| {
+                            let (x, y) = match point.exact {
+                                Some((x, y)) => (x.to_string(), y.to_string()),
+                                None => (format_fraction(point.x), format_fraction(point.y)),
+                            };
+                            format!("{}{},{}", if point.is_control { "~" } else { "" }, x, y)
+                        })
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                })
+                .collect::<Vec<String>>()
+                .join(" | ");
+            format!("{} {}", format_character(self.character), strokes)
+        }
+
+        /// `font` with the edited glyph, unchanged while the glyph is not
+        /// valid yet.
+        pub fn preview_font(&self, font: &Font) -> Font {
+            let mut preview = font.clone();
+            if let Ok(glyph) = Font::from_str(&self.to_line()) {
+                preview.extend(glyph);
+            }
+            preview
+        }
+
+        /// Adds the glyph to the font file at `path`, or replaces it there.
+        pub fn save(&self, path: &Path) -> Result<(), String> {
+            let line = self.to_line();
+            Font::from_str(&line).map_err(|error| error.message)?;
+            let content = if path.exists() {
+                fs::read_to_string(path)
+                    .map_err(|error| format!("could not read {}: {}", path.display(), error))?
+            } else {
+                String::new()
+            };
+            if let Some(directory) = path.parent() {
+                fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+            }
+            fs::write(path, set_glyph_line(&content, &line))
+                .map_err(|error| format!("could not write {}: {}", path.display(), error))
+        }
+    }
+
+    fn format_fraction(steps: i32) -> String {
+        let mut divisor = GRID;
+        while steps % divisor != 0 || GRID % divisor != 0 {
+            divisor -= 1;
+        }
+        match GRID / divisor {
+            1 => (steps / divisor).to_string(),
+            denominator => format!("{}/{}", steps / divisor, denominator),
+        }
+    }
+
+    /// The first character after `character` that is drawn as a placeholder.
+    pub fn next_missing(font: &Font, character: char) -> char {
+        let mut code = character as u32;
+        loop {
+            code = (code + 1) % (char::MAX as u32 + 1);
+            let candidate = match std::char::from_u32(code) {
+                Some(candidate) if !candidate.is_whitespace() && !candidate.is_control() => {
+                    candidate
+                }
+                _ => continue,
+            };
+            let strokes = get_character_strokes(font, &candidate, GRID, GRID);
+            if strokes == get_placeholder_strokes(font, &candidate, GRID, GRID) {
+                return candidate;
+            }
+        }
+    }
+
+    /// Where the grid is drawn on the screen.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct GridView {
+        /// The screen position of grid point 0, 0, the top left of the cell.
+        pub left: i32,
+        pub top: i32,
+        /// Pixels between neighbouring grid points.
+        pub spacing: i32,
+    }
+
+    impl GridView {
+        /// The largest grid centered in the given area.
+        pub fn fit(left: i32, top: i32, width: i32, height: i32) -> GridView {
+            let columns = GRID_MAXIMUM.0 - GRID_MINIMUM.0;
+            let rows = GRID_MAXIMUM.1 - GRID_MINIMUM.1;
+            let spacing = (width / columns).min(height / rows).max(1);
+            GridView {
+                left: left + (width - spacing * columns) / 2 - GRID_MINIMUM.0 * spacing,
+                top: top + (height - spacing * rows) / 2 - GRID_MINIMUM.1 * spacing,
+                spacing,
+            }
+        }
+
+        pub fn get_screen_position(&self, x: i32, y: i32) -> (i32, i32) {
+            (self.left + x * self.spacing, self.top + y * self.spacing)
+        }
+
+        /// The grid point nearest to a screen position, if it is on the grid.
+        pub fn get_grid_point(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+            let nearest = |position: i32, origin: i32| {
+                (position - origin + self.spacing / 2).div_euclid(self.spacing)
+            };
+            let (x, y) = (nearest(x, self.left), nearest(y, self.top));
+            let is_on_grid = x >= GRID_MINIMUM.0
+                && x <= GRID_MAXIMUM.0
+                && y >= GRID_MINIMUM.1
+                && y <= GRID_MAXIMUM.1;
+            if is_on_grid {
+                Some((x, y))
+            } else {
+                None
+            }
+        }
+    }
+}
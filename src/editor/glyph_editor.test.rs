@@ -0,0 +1,209 @@
+#[path = "glyph_editor.rs"]
+mod glyph_editor;
+
+#[path = "font.rs"]
+mod font;
+
+#[path = "text_rendering.rs"]
+mod text_rendering;
+
+#[cfg(test)]
+mod tests {
+    use super::font::font::Font;
+    use super::glyph_editor::glyph_editor::{next_missing, GlyphEditor, GridPoint, GridView};
+    use std::env;
+    use std::fs;
+    use std::str::FromStr;
+    fn point(x: i32, y: i32) -> GridPoint {
+        GridPoint {
+            x,
+            y,
+            is_control: false,
+            exact: None,
+        }
+    }
+    fn empty(character: char) -> GlyphEditor {
+        GlyphEditor::new(&Font::from_str("").unwrap(), character)
+    }
+    #[test]
+    fn new_loads_the_glyph_on_the_grid() {
+        let editor = GlyphEditor::new(&Font::builtin(), 'i');
+        assert_eq!(editor.strokes.len(), 3);
+        assert_eq!(editor.strokes[0], vec![point(6, 24), point(18, 24)]);
+        assert_eq!(editor.strokes[2], vec![point(12, 6), point(12, 4)]);
+        assert!(empty(' ').strokes.is_empty());
+    }
+    #[test]
+    fn placing_points() {
+        let mut editor = empty('☺');
+        editor.press(0, 0);
+        editor.release();
+        editor.press(24, 0);
+        editor.release();
+        editor.press(12, 12);
+        editor.release();
+        assert_eq!(
+            editor.strokes,
+            vec![vec![point(0, 0), point(24, 0), point(12, 12)]]
+        );
+        editor.lift_pen();
+        editor.press(0, 24);
+        editor.press(24, 24);
+        assert_eq!(editor.strokes.len(), 2);
+        assert_eq!(editor.selected, Some((1, 1)));
+    }
+    #[test]
+    fn points_are_inserted_after_the_selected_one() {
+        let mut editor = empty('☺');
+        editor.press(0, 0);
+        editor.press(24, 0);
+        editor.press(0, 0);
+        editor.press(12, -4);
+        assert_eq!(
+            editor.strokes,
+            vec![vec![point(0, 0), point(12, -4), point(24, 0)]]
+        );
+    }
+    #[test]
+    fn dragging_moves_the_pressed_point() {
+        let mut editor = empty('☺');
+        editor.press(0, 0);
+        editor.press(24, 0);
+        editor.release();
+        editor.drag(6, 6);
+        editor.press(0, 0);
+        editor.drag(2, 2);
+        editor.drag(4, 8);
+        editor.release();
+        editor.drag(10, 10);
+        assert_eq!(editor.strokes, vec![vec![point(4, 8), point(24, 0)]]);
+    }
+    #[test]
+    fn deleting_points_and_strokes() {
+        let mut editor = GlyphEditor::new(&Font::builtin(), 'i');
+        editor.delete(12, 6);
+        assert_eq!(editor.strokes[2], vec![point(12, 4)]);
+        editor.delete(12, 4);
+        assert_eq!(editor.strokes.len(), 2);
+        editor.delete(0, 0);
+        assert_eq!(editor.strokes.len(), 2);
+        editor.press(18, 24);
+        editor.delete_selected();
+        assert_eq!(editor.strokes[0], vec![point(6, 24)]);
+        assert_eq!(editor.selected, Some((0, 0)));
+    }
+    #[test]
+    fn to_line_uses_the_font_format() {
+        let mut editor = empty('#');
+        editor.press(0, 24);
+        editor.press(12, 0);
+        editor.toggle_curve();
+        editor.press(32, -8);
+        editor.lift_pen();
+        editor.press(6, 12);
+        editor.press(16, 12);
+        assert_eq!(
+            editor.to_line(),
+            "U+0023 0,1 ~1/2,0 4/3,-1/3 | 1/4,1/2 2/3,1/2"
+        );
+    }
+    #[test]
+    fn to_line_keeps_the_glyph() {
+        let font = Font::builtin();
+        for character in "akw0%$(".chars() {
+            let line = GlyphEditor::new(&font, character).to_line();
+            let glyph = Font::from_str(&line).unwrap();
+            for (width, height) in &[(10, 16), (7, 11)] {
+                assert_eq!(
+                    glyph.get_strokes(&character, *width, *height),
+                    font.get_strokes(&character, *width, *height),
+                    "{}",
+                    line
+                );
+            }
+        }
+    }
+    #[test]
+    fn every_builtin_glyph_is_saved_as_it_was_loaded() {
+        let lines = include_str!("default.font")
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        for line in lines {
+            let key = line.split_whitespace().next().unwrap();
+            let character = match key.strip_prefix("U+") {
+                Some(code) => std::char::from_u32(u32::from_str_radix(code, 16).unwrap()).unwrap(),
+                None => key.chars().next().unwrap(),
+            };
+            let font = Font::from_str(line).unwrap();
+            let saved = Font::from_str(&GlyphEditor::new(&font, character).to_line()).unwrap();
+            assert_eq!(
+                saved.get_points(&character, 240),
+                font.get_points(&character, 240),
+                "{}",
+                line
+            );
+        }
+    }
+    #[test]
+    fn moved_points_leave_their_exact_position() {
+        let font = Font::from_str("` 3/5,0 1/2,-1/3").unwrap();
+        let mut editor = GlyphEditor::new(&font, '`');
+        assert_eq!(editor.to_line(), "` 3/5,0 1/2,-1/3");
+        editor.press(14, 0);
+        editor.drag(15, 0);
+        assert_eq!(editor.to_line(), "` 5/8,0 1/2,-1/3");
+    }
+    #[test]
+    fn preview_font_has_the_edited_glyph() {
+        let font = Font::builtin();
+        let mut editor = GlyphEditor::new(&font, 'a');
+        editor.strokes = vec![vec![point(0, 0)]];
+        editor.selected = Some((0, 0));
+        assert_eq!(editor.preview_font(&font), font);
+        editor.press(24, 24);
+        assert_eq!(
+            editor.preview_font(&font).get_strokes(&'a', 12, 12),
+            vec![vec![(0, 0), (12, 12)]]
+        );
+    }
+    #[test]
+    fn save_adds_and_replaces_the_glyph() {
+        let directory = env::temp_dir().join(format!("editor-glyphs-{}", std::process::id()));
+        let path = directory.join("user.font");
+        let mut editor = empty('☺');
+        assert!(editor.save(&path).is_err());
+        assert!(!path.exists());
+        editor.press(0, 0);
+        editor.press(24, 24);
+        editor.save(&path).unwrap();
+        editor.press(0, 24);
+        editor.save(&path).unwrap();
+        GlyphEditor::new(&Font::builtin(), 'l').save(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "☺ 0,0 1,1 0,1\nl 1/4,0 1/4,1 3/4,1\n"
+        );
+        fs::remove_dir_all(&directory).unwrap();
+    }
+    #[test]
+    fn next_missing_skips_drawn_characters() {
+        let empty_font = Font::from_str("").unwrap();
+        assert_eq!(next_missing(&empty_font, '`'), 'a');
+        let font = Font::from_str("a 0,0 1,1\nb 0,0 1,1").unwrap();
+        assert_eq!(next_missing(&font, '`'), 'c');
+        assert_eq!(next_missing(&font, '~'), '¡');
+    }
+    #[test]
+    fn grid_view_maps_screen_positions() {
+        let view = GridView::fit(0, 20, 160, 220);
+        assert_eq!(view.spacing, 5);
+        assert_eq!(view.get_screen_position(0, 0), (20, 70));
+        assert_eq!(view.get_screen_position(24, 24), (140, 190));
+        assert_eq!(view.get_grid_point(22, 69), Some((0, 0)));
+        assert_eq!(view.get_grid_point(18, 76), Some((0, 1)));
+        assert_eq!(view.get_grid_point(0, 30), Some((-4, -8)));
+        assert_eq!(view.get_grid_point(0, 24), None);
+        assert_eq!(view.get_grid_point(160, 240), None);
+    }
+}
@@ -0,0 +1,661 @@
+pub mod highlighting {
+    use std::path::Path;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum TokenKind {
+        Plain,
+        Keyword,
+        Type,
+        Constant,
+        String,
+        Number,
+        Comment,
+        Punctuation,
+        Heading,
+        Key,
+        Variable,
+        Code,
+        Emphasis,
+    }
+
+    /// Characters `start..end` of a line, counted in `char`s like `Cursor` columns.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Span {
+        pub start: usize,
+        pub end: usize,
+        pub kind: TokenKind,
+    }
+
+    /// What an unfinished construct at the end of a line carries over into
+    /// the next one.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum LineState {
+        Normal,
+        BlockComment(u32),
+        String(char),
+        CodeFence,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Syntax {
+        Code,
+        Markdown,
+    }
+
+    #[derive(Debug)]
+    pub struct Grammar {
+        extensions: &'static [&'static str],
+        syntax: Syntax,
+        line_comments: &'static [&'static str],
+        /// Line comments only start at the beginning of a word, like `#` in shell.
+        line_comment_needs_word_start: bool,
+        block_comment: Option<(&'static str, &'static str)>,
+        nested_block_comments: bool,
+        string_delimiters: &'static [char],
+        /// Delimiters whose strings do not know backslash escapes.
+        raw_string_delimiters: &'static [char],
+        multiline_strings: bool,
+        keywords: &'static [&'static str],
+        types: &'static [&'static str],
+        constants: &'static [&'static str],
+        capitalized_types: bool,
+        lifetimes: bool,
+        variables: bool,
+        /// Strings and words directly followed by this are keys.
+        key_separator: Option<char>,
+        section_headers: bool,
+        identifier_dashes: bool,
+    }
+
+    static RUST: Grammar = Grammar {
+        extensions: &["rs"],
+        syntax: Syntax::Code,
+        line_comments: &["//"],
+        line_comment_needs_word_start: false,
+        block_comment: Some(("/*", "*/")),
+        nested_block_comments: true,
+        string_delimiters: &['"'],
+        raw_string_delimiters: &[],
+        multiline_strings: true,
+        keywords: &[
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+            "extern", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+            "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+            "type", "unsafe", "use", "where", "while",
+        ],
+        types: &[
+            "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32",
+            "i64", "i128", "isize", "f32", "f64",
+        ],
+        constants: &["true", "false", "None", "Some", "Ok", "Err"],
+        capitalized_types: true,
+        lifetimes: true,
+        variables: false,
+        key_separator: None,
+        section_headers: false,
+        identifier_dashes: false,
+    };
+
+    static SHELL: Grammar = Grammar {
+        extensions: &["sh", "bash", "zsh"],
+        syntax: Syntax::Code,
+        line_comments: &["#"],
+        line_comment_needs_word_start: true,
+        block_comment: None,
+        nested_block_comments: false,
+        string_delimiters: &['"', '\'', '`'],
+        raw_string_delimiters: &['\''],
+        multiline_strings: true,
+        keywords: &[
+            "if", "then", "else", "elif", "fi", "case", "esac", "for", "while", "until", "do",
+            "done", "in", "function", "return", "local", "export", "readonly", "declare", "select",
+            "break", "continue", "exit", "source",
+        ],
+        types: &[],
+        constants: &["true", "false"],
+        capitalized_types: false,
+        lifetimes: false,
+        variables: true,
+        key_separator: None,
+        section_headers: false,
+        identifier_dashes: true,
+    };
+
+    static MARKDOWN: Grammar = Grammar {
+        extensions: &["md", "markdown"],
+        syntax: Syntax::Markdown,
+        line_comments: &[],
+        line_comment_needs_word_start: false,
+        block_comment: None,
+        nested_block_comments: false,
+        string_delimiters: &[],
+        raw_string_delimiters: &[],
+        multiline_strings: false,
+        keywords: &[],
+        types: &[],
+        constants: &[],
+        capitalized_types: false,
+        lifetimes: false,
+        variables: false,
+        key_separator: None,
+        section_headers: false,
+        identifier_dashes: false,
+    };
+
+    static JSON: Grammar = Grammar {
+        extensions: &["json"],
+        syntax: Syntax::Code,
+        line_comments: &[],
+        line_comment_needs_word_start: false,
+        block_comment: None,
+        nested_block_comments: false,
+        string_delimiters: &['"'],
+        raw_string_delimiters: &[],
+        multiline_strings: false,
+        keywords: &[],
+        types: &[],
+        constants: &["true", "false", "null"],
+        capitalized_types: false,
+        lifetimes: false,
+        variables: false,
+        key_separator: Some(':'),
+        section_headers: false,
+        identifier_dashes: false,
+    };
+
+    static TOML: Grammar = Grammar {
+        extensions: &["toml"],
+        syntax: Syntax::Code,
+        line_comments: &["#"],
+        line_comment_needs_word_start: false,
+        block_comment: None,
+        nested_block_comments: false,
+        string_delimiters: &['"', '\''],
+        raw_string_delimiters: &['\''],
+        multiline_strings: false,
+        keywords: &[],
+        types: &[],
+        constants: &["true", "false", "inf", "nan"],
+        capitalized_types: false,
+        lifetimes: false,
+        variables: false,
+        key_separator: Some('='),
+        section_headers: true,
+        identifier_dashes: true,
+    };
+
+    static GRAMMARS: [&Grammar; 5] = [&RUST, &SHELL, &MARKDOWN, &JSON, &TOML];
+
+    pub fn get_grammar_for_path(path: &str) -> Option<&'static Grammar> {
+        let extension = Path::new(path).extension()?.to_str()?.to_lowercase();
+        GRAMMARS
+            .iter()
+            .find(|grammar| grammar.extensions.contains(&extension.as_str()))
+            .cloned()
+    }
+
+    /// Collects spans, merging neighbours of the same kind.
+    struct Spans(Vec<Span>);
+
+    impl Spans {
+        fn push(&mut self, start: usize, end: usize, kind: TokenKind) {
+            if start >= end {
+                return;
+            }
+            if let Some(last) = self.0.last_mut() {
+                if last.kind == kind && last.end == start {
+                    last.end = end;
+                    return;
+                }
+            }
+            self.0.push(Span { start, end, kind });
+        }
+    }
+
+    fn starts_with(characters: &[char], index: usize, pattern: &str) -> bool {
+        pattern
+            .chars()
+            .enumerate()
+            .all(|(offset, character)| characters.get(index + offset) == Some(&character))
+    }
+
+    fn is_identifier_character(grammar: &Grammar, character: char) -> bool {
+        character.is_alphanumeric()
+            || character == '_'
+            || (grammar.identifier_dashes && character == '-')
+    }
+
+    fn next_non_whitespace(characters: &[char], index: usize) -> Option<char> {
+        characters[index..]
+            .iter()
+            .find(|character| !character.is_whitespace())
+            .cloned()
+    }
+
+    /// Returns the index after the comment and whether it is still open.
+    fn scan_block_comment(
+        grammar: &Grammar,
+        characters: &[char],
+        mut index: usize,
+        mut depth: u32,
+    ) -> (usize, u32) {
+        let (open, close) = grammar.block_comment.unwrap();
+        while index < characters.len() {
+            if starts_with(characters, index, close) {
+                index += close.chars().count();
+                depth -= 1;
+                if depth == 0 {
+                    return (index, 0);
+                }
+            } else if grammar.nested_block_comments && starts_with(characters, index, open) {
+                index += open.chars().count();
+                depth += 1;
+            } else {
+                index += 1;
+            }
+        }
+        (index, depth)
+    }
+
+    /// Returns the index after the closing delimiter, or the line length and
+    /// `false` if the string continues.
+    fn scan_string(
+        grammar: &Grammar,
+        characters: &[char],
+        mut index: usize,
+        delimiter: char,
+    ) -> (usize, bool) {
+        let has_escapes = !grammar.raw_string_delimiters.contains(&delimiter);
+        while index < characters.len() {
+            if has_escapes && characters[index] == '\\' {
+                index += 2;
+            } else if characters[index] == delimiter {
+                return (index + 1, true);
+            } else {
+                index += 1;
+            }
+        }
+        (characters.len(), false)
+    }
+
+    fn tokenize_code(
+        grammar: &Grammar,
+        characters: &[char],
+        state: LineState,
+    ) -> (Vec<Span>, LineState) {
+        let mut spans = Spans(vec![]);
+        let length = characters.len();
+        let mut index = 0;
+        match state {
+            LineState::BlockComment(depth) if grammar.block_comment.is_some() => {
+                let (end, depth) = scan_block_comment(grammar, characters, 0, depth);
+                spans.push(0, end, TokenKind::Comment);
+                if depth > 0 {
+                    return (spans.0, LineState::BlockComment(depth));
+                }
+                index = end;
+            }
+            LineState::String(delimiter) => {
+                let (end, is_closed) = scan_string(grammar, characters, 0, delimiter);
+                spans.push(0, end, TokenKind::String);
+                if !is_closed {
+                    return (spans.0, state);
+                }
+                index = end;
+            }
+            _ => {}
+        }
+        let is_line_start = |index: usize| characters[..index].iter().all(|c| c.is_whitespace());
+        while index < length {
+            let character = characters[index];
+            let start = index;
+            if grammar.line_comments.iter().any(|comment| {
+                starts_with(characters, index, comment)
+                    && (!grammar.line_comment_needs_word_start
+                        || index == 0
+                        || characters[index - 1].is_whitespace())
+            }) {
+                spans.push(index, length, TokenKind::Comment);
+                break;
+            }
+            if let Some((open, _)) = grammar.block_comment {
+                if starts_with(characters, index, open) {
+                    let (end, depth) =
+                        scan_block_comment(grammar, characters, index + open.chars().count(), 1);
+                    spans.push(index, end, TokenKind::Comment);
+                    if depth > 0 {
+                        return (spans.0, LineState::BlockComment(depth));
+                    }
+                    index = end;
+                    continue;
+                }
+            }
+            if grammar.section_headers && character == '[' && is_line_start(index) {
+                spans.push(index, length, TokenKind::Heading);
+                break;
+            }
+            if grammar.string_delimiters.contains(&character) {
+                let (end, is_closed) = scan_string(grammar, characters, index + 1, character);
+                let kind = match grammar.key_separator {
+                    Some(separator)
+                        if is_closed && next_non_whitespace(characters, end) == Some(separator) =>
+                    {
+                        TokenKind::Key
+                    }
+                    _ => TokenKind::String,
+                };
+                spans.push(index, end, kind);
+                if !is_closed && grammar.multiline_strings {
+                    return (spans.0, LineState::String(character));
+                }
+                index = end;
+                continue;
+            }
+            if grammar.lifetimes && character == '\'' {
+                let is_character_literal = match characters.get(index + 1) {
+                    Some('\\') => true,
+                    Some(_) => characters.get(index + 2) == Some(&'\''),
+                    None => false,
+                };
+                if is_character_literal {
+                    let (end, _) = scan_string(grammar, characters, index + 1, '\'');
+                    spans.push(index, end, TokenKind::String);
+                    index = end;
+                } else {
+                    index += 1;
+                    while index < length && is_identifier_character(grammar, characters[index]) {
+                        index += 1;
+                    }
+                    spans.push(start, index, TokenKind::Type);
+                }
+                continue;
+            }
+            if grammar.variables && character == '$' {
+                index += 1;
+                if characters.get(index) == Some(&'{') {
+                    while index < length && characters[index] != '}' {
+                        index += 1;
+                    }
+                    index = (index + 1).min(length);
+                } else if index < length
+                    && !characters[index].is_alphanumeric()
+                    && characters[index] != '_'
+                {
+                    index += 1;
+                } else {
+                    while index < length
+                        && (characters[index].is_alphanumeric() || characters[index] == '_')
+                    {
+                        index += 1;
+                    }
+                }
+                spans.push(start, index, TokenKind::Variable);
+                continue;
+            }
+            if character.is_ascii_digit() {
+                while index < length
+                    && (characters[index].is_alphanumeric()
+                        || characters[index] == '_'
+                        || (characters[index] == '.'
+                            && characters
+                                .get(index + 1)
+                                .is_some_and(|c| c.is_ascii_digit())))
+                {
+                    index += 1;
+                }
+                spans.push(start, index, TokenKind::Number);
+                continue;
+            }
+            if character.is_alphabetic() || character == '_' {
+                while index < length && is_identifier_character(grammar, characters[index]) {
+                    index += 1;
+                }
+                let word = characters[start..index].iter().collect::<String>();
+                let next = next_non_whitespace(characters, index);
+                let kind = if grammar.key_separator.is_some()
+                    && (next == grammar.key_separator || next == Some('.'))
+                {
+                    TokenKind::Key
+                } else if grammar.keywords.contains(&word.as_str()) {
+                    TokenKind::Keyword
+                } else if grammar.constants.contains(&word.as_str()) {
+                    TokenKind::Constant
+                } else if grammar.types.contains(&word.as_str())
+                    || (grammar.capitalized_types && character.is_uppercase())
+                {
+                    TokenKind::Type
+                } else if grammar.capitalized_types && characters.get(index) == Some(&'!') {
+                    index += 1;
+                    TokenKind::Keyword
+                } else {
+                    TokenKind::Plain
+                };
+                spans.push(start, index, kind);
+                continue;
+            }
+            index += 1;
+            let kind = if character.is_whitespace() {
+                TokenKind::Plain
+            } else {
+                TokenKind::Punctuation
+            };
+            spans.push(start, index, kind);
+        }
+        (spans.0, LineState::Normal)
+    }
+
+    fn tokenize_markdown(characters: &[char], state: LineState) -> (Vec<Span>, LineState) {
+        let mut spans = Spans(vec![]);
+        let length = characters.len();
+        let indentation = characters
+            .iter()
+            .take_while(|character| character.is_whitespace())
+            .count();
+        let is_fence = starts_with(characters, indentation, "```");
+        if state == LineState::CodeFence {
+            spans.push(0, length, TokenKind::Code);
+            let state = if is_fence {
+                LineState::Normal
+            } else {
+                LineState::CodeFence
+            };
+            return (spans.0, state);
+        }
+        if is_fence {
+            spans.push(0, length, TokenKind::Code);
+            return (spans.0, LineState::CodeFence);
+        }
+        match characters.get(indentation) {
+            Some('#') => {
+                spans.push(0, length, TokenKind::Heading);
+                return (spans.0, LineState::Normal);
+            }
+            Some('>') => {
+                spans.push(0, length, TokenKind::Comment);
+                return (spans.0, LineState::Normal);
+            }
+            _ => {}
+        }
+        let mut index = indentation;
+        spans.push(0, index, TokenKind::Plain);
+        let marker_length = match characters.get(index) {
+            Some('-') | Some('*') | Some('+') => 1,
+            Some(character) if character.is_ascii_digit() => {
+                let digits = characters[index..]
+                    .iter()
+                    .take_while(|character| character.is_ascii_digit())
+                    .count();
+                if characters.get(index + digits) == Some(&'.') {
+                    digits + 1
+                } else {
+                    0
+                }
+            }
+            _ => 0,
+        };
+        if marker_length > 0 && characters.get(index + marker_length) == Some(&' ') {
+            spans.push(index, index + marker_length, TokenKind::Punctuation);
+            index += marker_length;
+        }
+        while index < length {
+            let character = characters[index];
+            let start = index;
+            let closing = |delimiter: &str, from: usize| {
+                (from..length).find(|offset| starts_with(characters, *offset, delimiter))
+            };
+            match character {
+                '`' => {
+                    if let Some(end) = closing("`", index + 1) {
+                        spans.push(start, end + 1, TokenKind::Code);
+                        index = end + 1;
+                        continue;
+                    }
+                }
+                '*' | '_' if index == 0 || !characters[index - 1].is_alphanumeric() => {
+                    let delimiter = if characters.get(index + 1) == Some(&character) {
+                        if character == '*' {
+                            "**"
+                        } else {
+                            "__"
+                        }
+                    } else if character == '*' {
+                        "*"
+                    } else {
+                        "_"
+                    };
+                    let delimiter_length = delimiter.len();
+                    if let Some(end) = closing(delimiter, index + delimiter_length) {
+                        if end > index + delimiter_length {
+                            spans.push(start, end + delimiter_length, TokenKind::Emphasis);
+                            index = end + delimiter_length;
+                            continue;
+                        }
+                    }
+                }
+                '[' => {
+                    if let Some(end) = closing("](", index + 1) {
+                        if let Some(url_end) = closing(")", end + 2) {
+                            spans.push(start, start + 1, TokenKind::Punctuation);
+                            spans.push(start + 1, end, TokenKind::Key);
+                            spans.push(end, end + 2, TokenKind::Punctuation);
+                            spans.push(end + 2, url_end, TokenKind::String);
+                            spans.push(url_end, url_end + 1, TokenKind::Punctuation);
+                            index = url_end + 1;
+                            continue;
+                        }
+                    }
+                }
+                _ => {}
+            }
+            index += 1;
+            spans.push(start, index, TokenKind::Plain);
+        }
+        (spans.0, LineState::Normal)
+    }
+
+    pub fn tokenize_line(
+        grammar: &Grammar,
+        line: &str,
+        state: LineState,
+    ) -> (Vec<Span>, LineState) {
+        let characters = line.chars().collect::<Vec<char>>();
+        match grammar.syntax {
+            Syntax::Code => tokenize_code(grammar, &characters, state),
+            Syntax::Markdown => tokenize_markdown(&characters, state),
+        }
+    }
+
+    #[derive(Debug)]
+    struct CachedLine {
+        text: String,
+        start_state: LineState,
+        end_state: LineState,
+        spans: Vec<Span>,
+    }
+
+    /// Keeps the spans of every line and re-tokenizes only what changed.
+    #[derive(Debug)]
+    pub struct Highlighter {
+        grammar: Option<&'static Grammar>,
+        lines: Vec<CachedLine>,
+    }
+
+    impl Highlighter {
+        pub fn new(grammar: Option<&'static Grammar>) -> Highlighter {
+            Highlighter {
+                grammar,
+                lines: vec![],
+            }
+        }
+
+        /// Brings the cache up to date with `lines`. Unchanged lines at the
+        /// start and end are kept, lines after an edit are re-tokenized until
+        /// their start state matches the cached one again. Returns how many
+        /// lines were tokenized.
+        pub fn update(&mut self, lines: &[String]) -> usize {
+            let grammar = match self.grammar {
+                Some(grammar) => grammar,
+                None => return 0,
+            };
+            let old_lines = &self.lines;
+            let prefix = old_lines
+                .iter()
+                .zip(lines.iter())
+                .take_while(|(cached, line)| cached.text == **line)
+                .count();
+            if prefix == lines.len() && prefix == old_lines.len() {
+                return 0;
+            }
+            let maximum_suffix = (old_lines.len() - prefix).min(lines.len() - prefix);
+            let suffix = old_lines
+                .iter()
+                .rev()
+                .zip(lines.iter().rev())
+                .take(maximum_suffix)
+                .take_while(|(cached, line)| cached.text == **line)
+                .count();
+
+            let mut old_suffix = self.lines.split_off(self.lines.len() - suffix);
+            self.lines.truncate(prefix);
+            let mut state = self
+                .lines
+                .last()
+                .map_or(LineState::Normal, |cached| cached.end_state);
+            let mut tokenized = 0;
+            for line in lines[prefix..lines.len() - suffix].iter() {
+                let cached = Highlighter::tokenize(grammar, line, state);
+                state = cached.end_state;
+                self.lines.push(cached);
+                tokenized += 1;
+            }
+            for cached in old_suffix.drain(..) {
+                if cached.start_state == state {
+                    state = cached.end_state;
+                    self.lines.push(cached);
+                } else {
+                    let cached = Highlighter::tokenize(grammar, &cached.text, state);
+                    state = cached.end_state;
+                    self.lines.push(cached);
+                    tokenized += 1;
+                }
+            }
+            tokenized
+        }
+
+        fn tokenize(grammar: &Grammar, line: &str, state: LineState) -> CachedLine {
+            let (spans, end_state) = tokenize_line(grammar, line, state);
+            CachedLine {
+                text: line.to_string(),
+                start_state: state,
+                end_state,
+                spans,
+            }
+        }
+
+        pub fn spans(&self, line: usize) -> &[Span] {
+            self.lines
+                .get(line)
+                .map_or(&[], |cached| cached.spans.as_slice())
+        }
+    }
+}
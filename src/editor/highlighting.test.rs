@@ -0,0 +1,283 @@
+#[path = "highlighting.rs"]
+mod highlighting;
+
+#[cfg(test)]
+mod tests {
+    use super::highlighting::highlighting::{
+        get_grammar_for_path, tokenize_line, Highlighter, LineState, TokenKind,
+    };
+
+    /// The text and kind of every span that is not plain.
+    fn tokens(path: &str, line: &str, state: LineState) -> (Vec<(String, TokenKind)>, LineState) {
+        let grammar = get_grammar_for_path(path).unwrap();
+        let characters = line.chars().collect::<Vec<char>>();
+        let (spans, state) = tokenize_line(grammar, line, state);
+        let tokens = spans
+            .iter()
+            .filter(|span| span.kind != TokenKind::Plain)
+            .map(|span| (characters[span.start..span.end].iter().collect(), span.kind))
+            .collect();
+        (tokens, state)
+    }
+    fn token(text: &str, kind: TokenKind) -> (String, TokenKind) {
+        (String::from(text), kind)
+    }
+    fn lines(text: &str) -> Vec<String> {
+        text.split('\n').map(String::from).collect()
+    }
+    #[test]
+    fn grammar_by_extension() {
+        assert!(get_grammar_for_path("src/main.rs").is_some());
+        assert!(get_grammar_for_path("README.MD").is_some());
+        assert!(get_grammar_for_path("Cargo.toml").is_some());
+        assert!(get_grammar_for_path("./editorTestFile").is_none());
+        assert!(get_grammar_for_path("image.png").is_none());
+    }
+    #[test]
+    fn spans_cover_the_line() {
+        let grammar = get_grammar_for_path("a.rs").unwrap();
+        let (spans, _) = tokenize_line(grammar, "let x = \"é\"; // ü", LineState::Normal);
+        assert_eq!(spans.first().unwrap().start, 0);
+        assert_eq!(spans.last().unwrap().end, 17);
+        for pair in spans.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+            assert_ne!(pair[0].kind, pair[1].kind);
+        }
+    }
+    #[test]
+    fn rust_tokens() {
+        let (tokens, state) = tokens(
+            "a.rs",
+            "pub fn f<'a>(x: &'a str) -> Option<u8> { println!(\"{}\\\"\", 'c', 1.5) } // end",
+            LineState::Normal,
+        );
+        assert_eq!(state, LineState::Normal);
+        assert_eq!(
+            tokens,
+            vec![
+                token("pub", TokenKind::Keyword),
+                token("fn", TokenKind::Keyword),
+                token("<", TokenKind::Punctuation),
+                token("'a", TokenKind::Type),
+                token(">(", TokenKind::Punctuation),
+                token(":", TokenKind::Punctuation),
+                token("&", TokenKind::Punctuation),
+                token("'a", TokenKind::Type),
+                token("str", TokenKind::Type),
+                token(")", TokenKind::Punctuation),
+                token("->", TokenKind::Punctuation),
+                token("Option", TokenKind::Type),
+                token("<", TokenKind::Punctuation),
+                token("u8", TokenKind::Type),
+                token(">", TokenKind::Punctuation),
+                token("{", TokenKind::Punctuation),
+                token("println!", TokenKind::Keyword),
+                token("(", TokenKind::Punctuation),
+                token("\"{}\\\"\"", TokenKind::String),
+                token(",", TokenKind::Punctuation),
+                token("'c'", TokenKind::String),
+                token(",", TokenKind::Punctuation),
+                token("1.5", TokenKind::Number),
+                token(")", TokenKind::Punctuation),
+                token("}", TokenKind::Punctuation),
+                token("// end", TokenKind::Comment),
+            ]
+        );
+    }
+    #[test]
+    fn rust_nested_block_comment_across_lines() {
+        let (tokens_first, state) = tokens("a.rs", "x /* a /* b */", LineState::Normal);
+        assert_eq!(
+            tokens_first,
+            vec![token("/* a /* b */", TokenKind::Comment)]
+        );
+        assert_eq!(state, LineState::BlockComment(1));
+        let (tokens_second, state) = tokens("a.rs", "c */ true", state);
+        assert_eq!(
+            tokens_second,
+            vec![
+                token("c */", TokenKind::Comment),
+                token("true", TokenKind::Constant)
+            ]
+        );
+        assert_eq!(state, LineState::Normal);
+    }
+    #[test]
+    fn rust_string_across_lines() {
+        let (_, state) = tokens("a.rs", "let s = \"first", LineState::Normal);
+        assert_eq!(state, LineState::String('"'));
+        let (tokens, state) = tokens("a.rs", "second\";", state);
+        assert_eq!(
+            tokens,
+            vec![
+                token("second\"", TokenKind::String),
+                token(";", TokenKind::Punctuation)
+            ]
+        );
+        assert_eq!(state, LineState::Normal);
+    }
+    #[test]
+    fn shell_tokens() {
+        let (tokens, _) = tokens(
+            "run.sh",
+            "if [ -n \"$HOME\" ]; then echo a#b ${PATH} '$x' $1; fi # done",
+            LineState::Normal,
+        );
+        assert_eq!(
+            tokens,
+            vec![
+                token("if", TokenKind::Keyword),
+                token("[", TokenKind::Punctuation),
+                token("-", TokenKind::Punctuation),
+                token("\"$HOME\"", TokenKind::String),
+                token("];", TokenKind::Punctuation),
+                token("then", TokenKind::Keyword),
+                token("#", TokenKind::Punctuation),
+                token("${PATH}", TokenKind::Variable),
+                token("'$x'", TokenKind::String),
+                token("$1", TokenKind::Variable),
+                token(";", TokenKind::Punctuation),
+                token("fi", TokenKind::Keyword),
+                token("# done", TokenKind::Comment),
+            ]
+        );
+    }
+    #[test]
+    fn json_keys_and_values() {
+        let (tokens, _) = tokens(
+            "a.json",
+            "{\"name\": \"x\", \"size\" : -12, \"ok\": [true, null]}",
+            LineState::Normal,
+        );
+        assert_eq!(
+            tokens,
+            vec![
+                token("{", TokenKind::Punctuation),
+                token("\"name\"", TokenKind::Key),
+                token(":", TokenKind::Punctuation),
+                token("\"x\"", TokenKind::String),
+                token(",", TokenKind::Punctuation),
+                token("\"size\"", TokenKind::Key),
+                token(":", TokenKind::Punctuation),
+                token("-", TokenKind::Punctuation),
+                token("12", TokenKind::Number),
+                token(",", TokenKind::Punctuation),
+                token("\"ok\"", TokenKind::Key),
+                token(":", TokenKind::Punctuation),
+                token("[", TokenKind::Punctuation),
+                token("true", TokenKind::Constant),
+                token(",", TokenKind::Punctuation),
+                token("null", TokenKind::Constant),
+                token("]}", TokenKind::Punctuation),
+            ]
+        );
+    }
+    #[test]
+    fn toml_sections_and_keys() {
+        let (section, _) = tokens("a.toml", "[package.metadata] # x", LineState::Normal);
+        assert_eq!(
+            section,
+            vec![token("[package.metadata] # x", TokenKind::Heading)]
+        );
+        let (entry, _) = tokens(
+            "a.toml",
+            "dotted.key-name = 'raw\\' # comment",
+            LineState::Normal,
+        );
+        assert_eq!(
+            entry,
+            vec![
+                token("dotted", TokenKind::Key),
+                token(".", TokenKind::Punctuation),
+                token("key-name", TokenKind::Key),
+                token("=", TokenKind::Punctuation),
+                token("'raw\\'", TokenKind::String),
+                token("# comment", TokenKind::Comment),
+            ]
+        );
+        let (array, _) = tokens("a.toml", "list = [1, true]", LineState::Normal);
+        assert_eq!(
+            array,
+            vec![
+                token("list", TokenKind::Key),
+                token("=", TokenKind::Punctuation),
+                token("[", TokenKind::Punctuation),
+                token("1", TokenKind::Number),
+                token(",", TokenKind::Punctuation),
+                token("true", TokenKind::Constant),
+                token("]", TokenKind::Punctuation),
+            ]
+        );
+    }
+    #[test]
+    fn markdown_inline() {
+        let (heading, _) = tokens("a.md", "## Title `x`", LineState::Normal);
+        assert_eq!(heading, vec![token("## Title `x`", TokenKind::Heading)]);
+        let (item, _) = tokens(
+            "a.md",
+            "- use `cargo` **now**, see [docs](http://a) or snake_case_name",
+            LineState::Normal,
+        );
+        assert_eq!(
+            item,
+            vec![
+                token("-", TokenKind::Punctuation),
+                token("`cargo`", TokenKind::Code),
+                token("**now**", TokenKind::Emphasis),
+                token("[", TokenKind::Punctuation),
+                token("docs", TokenKind::Key),
+                token("](", TokenKind::Punctuation),
+                token("http://a", TokenKind::String),
+                token(")", TokenKind::Punctuation),
+            ]
+        );
+    }
+    #[test]
+    fn markdown_code_fence() {
+        let (_, state) = tokens("a.md", "```rust", LineState::Normal);
+        assert_eq!(state, LineState::CodeFence);
+        let (inside, state) = tokens("a.md", "# not a heading", state);
+        assert_eq!(inside, vec![token("# not a heading", TokenKind::Code)]);
+        let (_, state) = tokens("a.md", "```", state);
+        assert_eq!(state, LineState::Normal);
+    }
+    #[test]
+    fn highlighter_without_grammar() {
+        let mut highlighter = Highlighter::new(None);
+        assert_eq!(highlighter.update(&lines("a\nb")), 0);
+        assert!(highlighter.spans(0).is_empty());
+    }
+    #[test]
+    fn highlighter_caches_lines() {
+        let mut highlighter = Highlighter::new(get_grammar_for_path("a.rs"));
+        let mut text = lines("fn a() {}\nlet x = 1;\nlet y = 2;\n// end");
+        assert_eq!(highlighter.update(&text), 4);
+        assert_eq!(highlighter.update(&text), 0);
+        text[1].push_str(" // note");
+        assert_eq!(highlighter.update(&text), 1);
+        assert_eq!(
+            highlighter.spans(1).last().unwrap().kind,
+            TokenKind::Comment
+        );
+        text.insert(2, String::from("let z = 3;"));
+        assert_eq!(highlighter.update(&text), 1);
+        assert_eq!(highlighter.spans(4)[0].kind, TokenKind::Comment);
+        text.remove(0);
+        assert_eq!(highlighter.update(&text), 0);
+        assert_eq!(highlighter.spans(0)[0].kind, TokenKind::Keyword);
+        assert!(highlighter.spans(10).is_empty());
+    }
+    #[test]
+    fn highlighter_retokenizes_until_state_matches() {
+        let mut highlighter = Highlighter::new(get_grammar_for_path("a.rs"));
+        let mut text = lines("a\nb\nc */\nd");
+        highlighter.update(&text);
+        assert_eq!(highlighter.spans(1)[0].kind, TokenKind::Plain);
+        text[0] = String::from("/* a");
+        assert_eq!(highlighter.update(&text), 3);
+        assert_eq!(highlighter.spans(1)[0].kind, TokenKind::Comment);
+        assert_eq!(highlighter.spans(3)[0].kind, TokenKind::Plain);
+        text[1] = String::from("b2");
+        assert_eq!(highlighter.update(&text), 1);
+    }
+}
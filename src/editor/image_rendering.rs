@@ -0,0 +1,166 @@
+pub mod image_rendering {
+    use super::super::config::config::{Layout, StrokeStyle};
+    use super::super::cursor::cursor::Cursor;
+    use super::super::font::font::Font;
+    use super::super::highlighting::highlighting::Highlighter;
+    use super::super::rasterizer::rasterizer::{blend, rasterize_stroke};
+    use super::super::rendering::rendering::{
+        draw_frame, draw_wide_lines, layout_frame, CaretLook, Renderer, View,
+    };
+    use super::super::theme::theme::Theme;
+    use image::{ImageFormat, Rgba, RgbaImage};
+    use sdl2::pixels::Color;
+    use std::path::Path;
+
+    fn to_pixel(color: Color) -> Rgba<u8> {
+        Rgba([color.r, color.g, color.b, color.a])
+    }
+
+    /// Draws a line with both ends included, leaving out the pixels outside
+    /// of the image.
+    pub fn draw_line(image: &mut RgbaImage, from: (i32, i32), to: (i32, i32), color: Color) {
+        let pixel = to_pixel(color);
+        let (width, height) = (image.width() as i32, image.height() as i32);
+        let (mut x, mut y) = from;
+        let (dx, dy) = ((to.0 - x).abs(), -(to.1 - y).abs());
+        let (step_x, step_y) = ((to.0 - x).signum(), (to.1 - y).signum());
+        let mut error = dx + dy;
+        loop {
+            if x >= 0 && x < width && y >= 0 && y < height {
+                image.put_pixel(x as u32, y as u32, pixel);
+            }
+            if (x, y) == to {
+                break;
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    /// Draws into an image in memory, without a display.
+    pub struct ImageRenderer<'a> {
+        pub image: &'a mut RgbaImage,
+        color: Color,
+        stroke_style: StrokeStyle,
+    }
+
+    impl<'a> ImageRenderer<'a> {
+        #[cfg(test)]
+        pub fn new(image: &'a mut RgbaImage) -> ImageRenderer<'a> {
+            ImageRenderer::with_stroke_style(image, StrokeStyle::Crisp)
+        }
+        pub fn with_stroke_style(
+            image: &'a mut RgbaImage,
+            stroke_style: StrokeStyle,
+        ) -> ImageRenderer<'a> {
+            ImageRenderer {
+                image,
+                color: Color::RGB(0, 0, 0),
+                stroke_style,
+            }
+        }
+    }
+
+    impl<'a> Renderer for ImageRenderer<'a> {
+        fn set_color(&mut self, color: Color) {
+            self.color = color;
+        }
+        fn clear(&mut self) {
+            let pixel = to_pixel(self.color);
+            for existing in self.image.pixels_mut() {
+                *existing = pixel;
+            }
+        }
+        fn draw_line(&mut self, from: (i32, i32), to: (i32, i32)) {
+            draw_line(self.image, from, to, self.color);
+        }
+        fn fill_rect(&mut self, left: i32, top: i32, width: u32, height: u32) {
+            let pixel = to_pixel(self.color);
+            let right = (left + width as i32).min(self.image.width() as i32);
+            let bottom = (top + height as i32).min(self.image.height() as i32);
+            for x in left.max(0)..right {
+                for y in top.max(0)..bottom {
+                    self.image.put_pixel(x as u32, y as u32, pixel);
+                }
+            }
+        }
+        fn draw_stroke(&mut self, points: &[(i32, i32)], width: u32) {
+            match self.stroke_style {
+                StrokeStyle::Crisp => draw_wide_lines(self, points, width),
+                StrokeStyle::Smooth => {
+                    let coverage = rasterize_stroke(points, width as f32, true);
+                    blend(self.image, &coverage, self.color);
+                }
+            }
+        }
+    }
+
+    /// What the editor window would show in an image of this size, with
+    /// one pixel wide strokes.
+    pub fn get_image_view<'a>(
+        image: &RgbaImage,
+        cursor: &'a Cursor,
+        highlighter: &'a Highlighter,
+        camera_line: i32,
+        layout: &Layout,
+    ) -> View<'a> {
+        let line_height = (layout.character_height + layout.line_gap).max(1);
+        let column_width = (layout.character_width + layout.character_gap).max(1);
+        View {
+            cursor,
+            highlighter,
+            camera_line,
+            lines: image.height() as usize / line_height as usize + 1,
+            columns: image.width() as i32 / column_width,
+            width: image.width(),
+            status_line: None,
+            caret: Some(CaretLook::default()),
+            preedit: None,
+            stroke_width: 1,
+            scrollbar: None,
+            minimap: None,
+        }
+    }
+
+    pub fn render_view(
+        image: &mut RgbaImage,
+        view: &View,
+        layout: &Layout,
+        font: &Font,
+        theme: &Theme,
+        stroke_style: StrokeStyle,
+    ) {
+        let frame = layout_frame(view, layout, theme);
+        let mut renderer = ImageRenderer::with_stroke_style(image, stroke_style);
+        draw_frame(&mut renderer, &frame, layout, font);
+    }
+
+    /// Draws the buffer from `camera_line` on like the editor window shows
+    /// it, with the caret and the selection, for tests.
+    #[cfg(test)]
+    pub fn render(
+        image: &mut RgbaImage,
+        cursor: &Cursor,
+        highlighter: &Highlighter,
+        camera_line: i32,
+        layout: &Layout,
+        font: &Font,
+        theme: &Theme,
+    ) {
+        let view = get_image_view(image, cursor, highlighter, camera_line, layout);
+        render_view(image, &view, layout, font, theme, StrokeStyle::Crisp);
+    }
+
+    pub fn save_png(image: &RgbaImage, path: &Path) -> Result<(), String> {
+        image
+            .save_with_format(path, ImageFormat::PNG)
+            .map_err(|error| format!("could not write {}: {}", path.display(), error))
+    }
+}
@@ -0,0 +1,313 @@
+#[path = "image_rendering.rs"]
+mod image_rendering;
+
+#[path = "rendering.rs"]
+mod rendering;
+
+#[path = "scrollbar.rs"]
+mod scrollbar;
+
+#[path = "rasterizer.rs"]
+mod rasterizer;
+
+#[path = "config.rs"]
+mod config;
+
+#[path = "commands.rs"]
+mod commands;
+
+#[path = "cursor.rs"]
+mod cursor;
+
+#[path = "kill_ring.rs"]
+mod kill_ring;
+
+#[path = "keymap.rs"]
+mod keymap;
+
+#[path = "font.rs"]
+mod font;
+
+#[path = "highlighting.rs"]
+mod highlighting;
+
+#[path = "theme.rs"]
+mod theme;
+
+#[path = "text_rendering.rs"]
+mod text_rendering;
+
+#[cfg(test)]
+mod tests {
+    use super::config::config::{
+        get_character_x, get_character_y, get_scaled_zoom, get_stroke_width, Layout, StrokeStyle,
+    };
+    use super::cursor::cursor::Cursor;
+    use super::font::font::Font;
+    use super::highlighting::highlighting::{get_grammar_for_path, Highlighter};
+    use super::image_rendering::image_rendering::{
+        draw_line, get_image_view, render, render_view, save_png, ImageRenderer,
+    };
+    use super::rendering::rendering::Renderer;
+    use super::theme::theme::Theme;
+    use image::{Rgba, RgbaImage};
+    use sdl2::pixels::Color;
+    use std::env;
+    use std::fs;
+    use std::time::Instant;
+    fn pixel(color: Color) -> Rgba<u8> {
+        Rgba([color.r, color.g, color.b, color.a])
+    }
+    fn cursor(lines: Vec<&str>) -> Cursor {
+        Cursor::new(lines.into_iter().map(String::from).collect())
+    }
+    fn rendered(cursor: &Cursor, camera_line: i32) -> RgbaImage {
+        let mut image = RgbaImage::new(200, 100);
+        render(
+            &mut image,
+            cursor,
+            &Highlighter::new(None),
+            camera_line,
+            &Layout::default(),
+            &Font::builtin(),
+            &Theme::dark(),
+        );
+        image
+    }
+    fn cell_has(image: &RgbaImage, column: i32, line: i32, color: Color) -> bool {
+        let layout = Layout::default();
+        let (left, top) = (
+            get_character_x(&layout, column),
+            get_character_y(&layout, line),
+        );
+        (left..left + layout.character_width).any(|x| {
+            (top..top + layout.character_height)
+                .any(|y| *image.get_pixel(x as u32, y as u32) == pixel(color))
+        })
+    }
+    #[test]
+    fn lines_include_both_ends() {
+        let white = Color::RGB(255, 255, 255);
+        let mut image = RgbaImage::new(8, 8);
+        draw_line(&mut image, (1, 1), (5, 1), white);
+        draw_line(&mut image, (0, 3), (3, 6), white);
+        for x in 1..=5 {
+            assert_eq!(*image.get_pixel(x, 1), pixel(white));
+        }
+        for step in 0..=3 {
+            assert_eq!(*image.get_pixel(step, 3 + step), pixel(white));
+        }
+        assert_eq!(*image.get_pixel(6, 1), Rgba([0, 0, 0, 0]));
+    }
+    #[test]
+    fn lines_outside_of_the_image_are_clipped() {
+        let white = Color::RGB(255, 255, 255);
+        let mut image = RgbaImage::new(4, 4);
+        draw_line(&mut image, (-5, -5), (10, 10), white);
+        draw_line(&mut image, (-20, 50), (20, 60), white);
+        for step in 0..4 {
+            assert_eq!(*image.get_pixel(step, step), pixel(white));
+        }
+        assert_eq!(image.pixels().filter(|p| **p == pixel(white)).count(), 4);
+    }
+    #[test]
+    fn rects_are_clipped_to_the_image() {
+        let white = Color::RGB(255, 255, 255);
+        let mut image = RgbaImage::new(4, 4);
+        let mut renderer = ImageRenderer::new(&mut image);
+        renderer.set_color(white);
+        renderer.fill_rect(-1, 2, 3, 10);
+        let filled = image
+            .enumerate_pixels()
+            .filter(|(_, _, p)| **p == pixel(white))
+            .map(|(x, y, _)| (x, y))
+            .collect::<Vec<(u32, u32)>>();
+        assert_eq!(filled, vec![(0, 2), (1, 2), (0, 3), (1, 3)]);
+    }
+    #[test]
+    fn background_fills_the_image() {
+        let image = rendered(&cursor(vec![""]), 0);
+        let background = pixel(Theme::dark().colors.background);
+        assert_eq!(*image.get_pixel(0, 0), background);
+        assert_eq!(*image.get_pixel(199, 99), background);
+    }
+    #[test]
+    fn text_is_drawn_in_its_cell() {
+        let mut cursor = cursor(vec!["a b"]);
+        cursor.move_to(1, 0, false);
+        let image = rendered(&cursor, 0);
+        let text = Theme::dark().colors.text;
+        assert!(cell_has(&image, 0, 0, text));
+        assert!(!cell_has(&image, 1, 0, text));
+        assert!(cell_has(&image, 2, 0, text));
+        assert!(!cell_has(&image, 0, 1, text));
+    }
+    #[test]
+    fn syntax_colors_come_from_the_highlighter() {
+        let cursor = cursor(vec!["fn"]);
+        let mut highlighter = Highlighter::new(get_grammar_for_path("a.rs"));
+        highlighter.update(&cursor.lines);
+        let theme = Theme::dark();
+        let mut image = RgbaImage::new(100, 40);
+        let layout = Layout::default();
+        render(
+            &mut image,
+            &cursor,
+            &highlighter,
+            0,
+            &layout,
+            &Font::builtin(),
+            &theme,
+        );
+        assert!(cell_has(&image, 1, 0, theme.syntax.keyword));
+        assert!(!cell_has(&image, 1, 0, theme.colors.text));
+    }
+    #[test]
+    fn caret_is_drawn_at_its_column() {
+        let mut cursor = cursor(vec!["ab"]);
+        cursor.move_to(0, 2, false);
+        let image = rendered(&cursor, 0);
+        let layout = Layout::default();
+        let x = get_character_x(&layout, 2) as u32;
+        let y = get_character_y(&layout, 0) as u32;
+        let caret = pixel(Theme::dark().colors.cursor);
+        assert_eq!(*image.get_pixel(x, y), caret);
+        assert_eq!(*image.get_pixel(x, y + 15), caret);
+        assert_ne!(*image.get_pixel(x + 1, y), caret);
+    }
+    #[test]
+    fn camera_line_scrolls_the_buffer() {
+        let text = Theme::dark().colors.text;
+        let image = rendered(&cursor(vec!["", "a"]), 1);
+        assert!(cell_has(&image, 0, 0, text));
+        assert!(!cell_has(&image, 0, 0, Theme::dark().colors.cursor));
+    }
+    #[test]
+    fn selection_is_underlined_through_the_middle_of_the_line() {
+        let mut cursor = cursor(vec!["abc"]);
+        cursor.move_to(0, 3, true);
+        let image = rendered(&cursor, 0);
+        let layout = Layout::default();
+        let y = (get_character_y(&layout, 0) + layout.character_height / 2) as u32;
+        let x = get_character_x(&layout, 1) as u32 + 1;
+        assert_eq!(
+            *image.get_pixel(x, y),
+            pixel(Theme::dark().colors.cursor_selection)
+        );
+    }
+    #[test]
+    fn simulated_display_scale_doubles_cells_and_strokes() {
+        let mut cursor = cursor(vec!["ab"]);
+        cursor.move_to(0, 1, false);
+        let highlighter = Highlighter::new(None);
+        let layout = Layout::default().zoomed(get_scaled_zoom(100, 2.0));
+        let mut image = RgbaImage::new(400, 200);
+        let mut view = get_image_view(&image, &cursor, &highlighter, 0, &layout);
+        view.stroke_width = get_stroke_width(1, get_scaled_zoom(100, 2.0));
+        render_view(
+            &mut image,
+            &view,
+            &layout,
+            &Font::builtin(),
+            &Theme::dark(),
+            StrokeStyle::Crisp,
+        );
+        let caret = pixel(Theme::dark().colors.cursor);
+        let x = get_character_x(&layout, 1) as u32;
+        let y = get_character_y(&layout, 0) as u32;
+        assert_eq!(x, 46);
+        assert_eq!(*image.get_pixel(x, y), caret);
+        assert_eq!(*image.get_pixel(x + 1, y), caret);
+        assert_ne!(*image.get_pixel(x + 2, y), caret);
+        assert_eq!(*image.get_pixel(x, y + 31), caret);
+        let text = pixel(Theme::dark().colors.text);
+        let text_pixels = |image: &RgbaImage| image.pixels().filter(|p| **p == text).count();
+        let unscaled = rendered(&cursor, 0);
+        assert!(text_pixels(&image) >= 3 * text_pixels(&unscaled));
+    }
+    fn render_with_strokes(
+        cursor: &Cursor,
+        width: u32,
+        height: u32,
+        zoom: u32,
+        stroke_style: StrokeStyle,
+    ) -> RgbaImage {
+        let highlighter = Highlighter::new(None);
+        let layout = Layout::default().zoomed(zoom);
+        let mut image = RgbaImage::new(width, height);
+        let mut view = get_image_view(&image, cursor, &highlighter, 0, &layout);
+        view.stroke_width = get_stroke_width(1, zoom);
+        render_view(
+            &mut image,
+            &view,
+            &layout,
+            &Font::builtin(),
+            &Theme::dark(),
+            stroke_style,
+        );
+        image
+    }
+    #[test]
+    fn smooth_strokes_are_anti_aliased() {
+        let cursor = cursor(vec!["avoid zigzag"]);
+        let colors = Theme::dark().colors;
+        let is_mixed = |image: &RgbaImage| {
+            image.pixels().any(|p| {
+                *p != pixel(colors.background)
+                    && *p != pixel(colors.text)
+                    && *p != pixel(colors.cursor)
+            })
+        };
+        let crisp = render_with_strokes(&cursor, 300, 60, 200, StrokeStyle::Crisp);
+        let smooth = render_with_strokes(&cursor, 300, 60, 200, StrokeStyle::Smooth);
+        assert!(!is_mixed(&crisp));
+        assert!(is_mixed(&smooth));
+        assert_eq!(
+            smooth.get_pixel(0, 59),
+            &pixel(colors.background),
+            "the background stays untouched"
+        );
+    }
+    /// Run with `cargo test --release -- --ignored frame_time` to see how
+    /// long a full window takes to draw with each stroke style.
+    #[test]
+    #[ignore]
+    fn frame_time() {
+        let line = "fn main() { println!(\"{}\", (0..10).map(|x| x * 2).sum::<i32>()); }";
+        let cursor = cursor(vec![line; 60]);
+        let frames = 20;
+        for &(zoom, stroke_style) in &[
+            (100, StrokeStyle::Crisp),
+            (100, StrokeStyle::Smooth),
+            (200, StrokeStyle::Crisp),
+            (200, StrokeStyle::Smooth),
+        ] {
+            let (width, height) = (1170 * zoom / 100, 800 * zoom / 100);
+            let start = Instant::now();
+            for _ in 0..frames {
+                render_with_strokes(&cursor, width, height, zoom, stroke_style);
+            }
+            let frame_time = start.elapsed() / frames;
+            println!(
+                "{:?} at {}%: {:?} per frame",
+                stroke_style, zoom, frame_time
+            );
+            assert!(
+                frame_time.as_millis() < 33,
+                "slower than 30 frames per second"
+            );
+        }
+    }
+    #[test]
+    fn png_roundtrip() {
+        let mut cursor = cursor(vec!["hello"]);
+        cursor.move_to(0, 2, true);
+        let image = rendered(&cursor, 0);
+        let path = env::temp_dir().join("editor_image_rendering_test.png");
+        save_png(&image, &path).unwrap();
+        let loaded = image::open(&path).unwrap().to_rgba();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.dimensions(), (200, 100));
+        assert!(loaded.pixels().eq(image.pixels()));
+    }
+}
@@ -0,0 +1,240 @@
+pub mod keymap {
+    use super::super::commands::commands::Command;
+    use std::collections::HashMap;
+    use std::fmt;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct Modifiers {
+        pub ctrl: bool,
+        pub alt: bool,
+        pub shift: bool,
+    }
+
+    /// One key press together with the modifiers held down, e.g. `ctrl+shift+left`.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct Chord {
+        pub modifiers: Modifiers,
+        pub key: String,
+    }
+
+    static KEY_ALIASES: [(&str, &str); 8] = [
+        ("plus", "+"),
+        ("minus", "-"),
+        ("equals", "="),
+        ("enter", "return"),
+        ("esc", "escape"),
+        ("del", "delete"),
+        ("pageup", "page_up"),
+        ("pagedown", "page_down"),
+    ];
+
+    impl Chord {
+        pub fn new(key: &str, modifiers: Modifiers) -> Chord {
+            Chord {
+                modifiers,
+                key: normalize_key(key),
+            }
+        }
+
+        /// Parses chords like `ctrl+s`, `shift+left`, `ctrl+plus` or `ctrl++`.
+        pub fn parse(chord: &str) -> Result<Chord, String> {
+            let (modifier_part, key) = if chord == "+" {
+                ("", "+")
+            } else if chord.ends_with("++") {
+                (&chord[..chord.len() - 1], "+")
+            } else {
+                match chord.rfind('+') {
+                    Some(index) => (&chord[..index + 1], &chord[index + 1..]),
+                    None => ("", chord),
+                }
+            };
+            if key.is_empty() {
+                return Err(format!("\"{}\" is missing a key", chord));
+            }
+            let mut modifiers = Modifiers::default();
+            for modifier in modifier_part.split('+').filter(|part| !part.is_empty()) {
+                match modifier.to_lowercase().as_str() {
+                    "ctrl" | "control" => modifiers.ctrl = true,
+                    "alt" | "meta" => modifiers.alt = true,
+                    "shift" => modifiers.shift = true,
+                    other => return Err(format!("unknown modifier \"{}\"", other)),
+                }
+            }
+            Ok(Chord::new(key, modifiers))
+        }
+    }
+
+    fn normalize_key(key: &str) -> String {
+        let key = key.to_lowercase().replace(' ', "_");
+        KEY_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == key)
+            .map(|(_, name)| name.to_string())
+            .unwrap_or(key)
+    }
+
+    impl fmt::Display for Chord {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            if self.modifiers.ctrl {
+                write!(formatter, "ctrl+")?;
+            }
+            if self.modifiers.alt {
+                write!(formatter, "alt+")?;
+            }
+            if self.modifiers.shift {
+                write!(formatter, "shift+")?;
+            }
+            write!(formatter, "{}", self.key)
+        }
+    }
+
+    /// Parses a space separated sequence of chords, e.g. `ctrl+k ctrl+u`.
+    pub fn parse_sequence(sequence: &str) -> Result<Vec<Chord>, String> {
+        let chords = sequence
+            .split_whitespace()
+            .map(Chord::parse)
+            .collect::<Result<Vec<Chord>, String>>()?;
+        if chords.is_empty() {
+            Err("empty key sequence".to_string())
+        } else {
+            Ok(chords)
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum KeyResult {
+        Command(Command),
+        /// The chord starts a longer sequence; wait for the next one.
+        Pending,
+        Unbound,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Keymap {
+        bindings: HashMap<Vec<Chord>, Command>,
+        pending: Vec<Chord>,
+    }
+
+    static DEFAULT_BINDINGS: [(&str, Command); 28] = [
+        ("left", Command::CursorLeft),
+        ("right", Command::CursorRight),
+        ("up", Command::CursorUp),
+        ("down", Command::CursorDown),
+        ("home", Command::CursorHome),
+        ("end", Command::CursorEnd),
+        ("shift+left", Command::SelectLeft),
+        ("shift+right", Command::SelectRight),
+        ("shift+up", Command::SelectUp),
+        ("shift+down", Command::SelectDown),
+        ("shift+home", Command::SelectHome),
+        ("shift+end", Command::SelectEnd),
+        ("ctrl+a", Command::SelectAll),
+        ("delete", Command::EditDelete),
+        ("backspace", Command::EditBackspace),
+        ("return", Command::EditNewLine),
+        ("insert", Command::EditToggleOverwrite),
+        ("ctrl+s", Command::FileSave),
+        ("ctrl+k ctrl+e", Command::FileExportSvg),
+        ("ctrl+k ctrl+t", Command::ThemeNext),
+        ("ctrl+k ctrl+g", Command::FontEditGlyph),
+        ("ctrl+=", Command::ViewZoomIn),
+        ("ctrl+shift+=", Command::ViewZoomIn),
+        ("ctrl++", Command::ViewZoomIn),
+        ("ctrl+-", Command::ViewZoomOut),
+        ("ctrl+0", Command::ViewZoomReset),
+        ("f12", Command::ViewToggleFrameTime),
+        ("escape", Command::AppQuit),
+    ];
+
+    static EMACS_BINDINGS: [(&str, Command); 32] = [
+        ("ctrl+f", Command::CursorRight),
+        ("ctrl+b", Command::CursorLeft),
+        ("ctrl+n", Command::CursorDown),
+        ("ctrl+p", Command::CursorUp),
+        ("ctrl+a", Command::CursorHome),
+        ("ctrl+e", Command::CursorEnd),
+        ("left", Command::CursorLeft),
+        ("right", Command::CursorRight),
+        ("up", Command::CursorUp),
+        ("down", Command::CursorDown),
+        ("home", Command::CursorHome),
+        ("end", Command::CursorEnd),
+        ("ctrl+d", Command::EditDelete),
+        ("delete", Command::EditDelete),
+        ("backspace", Command::EditBackspace),
+        ("return", Command::EditNewLine),
+        ("ctrl+space", Command::MarkSet),
+        ("ctrl+g", Command::MarkCancel),
+        ("ctrl+k", Command::KillLine),
+        ("ctrl+w", Command::KillRegion),
+        ("alt+w", Command::CopyRegion),
+        ("ctrl+y", Command::Yank),
+        ("alt+y", Command::YankPop),
+        ("ctrl+x h", Command::SelectAll),
+        ("ctrl+x ctrl+s", Command::FileSave),
+        ("ctrl+x ctrl+c", Command::AppQuit),
+        ("ctrl+=", Command::ViewZoomIn),
+        ("ctrl+shift+=", Command::ViewZoomIn),
+        ("ctrl++", Command::ViewZoomIn),
+        ("ctrl+-", Command::ViewZoomOut),
+        ("ctrl+0", Command::ViewZoomReset),
+        ("escape", Command::MarkCancel),
+    ];
+
+    impl Default for Keymap {
+        fn default() -> Keymap {
+            let mut keymap = Keymap::empty();
+            for (sequence, command) in DEFAULT_BINDINGS.iter() {
+                keymap.bind(sequence, *command).unwrap();
+            }
+            keymap
+        }
+    }
+
+    impl Keymap {
+        /// Emacs movement, kill ring and mark instead of Shift-selection.
+        pub fn emacs() -> Keymap {
+            let mut keymap = Keymap::empty();
+            for (sequence, command) in EMACS_BINDINGS.iter() {
+                keymap.bind(sequence, *command).unwrap();
+            }
+            keymap
+        }
+        pub fn empty() -> Keymap {
+            Keymap {
+                bindings: HashMap::new(),
+                pending: vec![],
+            }
+        }
+        pub fn bind(&mut self, sequence: &str, command: Command) -> Result<(), String> {
+            self.bindings.insert(parse_sequence(sequence)?, command);
+            Ok(())
+        }
+        pub fn unbind(&mut self, sequence: &str) -> Result<(), String> {
+            self.bindings.remove(&parse_sequence(sequence)?);
+            Ok(())
+        }
+
+        /// Feeds one chord into the keymap. Sequences that cannot be completed
+        /// anymore are dropped as a whole.
+        pub fn press(&mut self, chord: Chord) -> KeyResult {
+            self.pending.push(chord);
+            if let Some(command) = self.bindings.get(&self.pending) {
+                let command = *command;
+                self.pending.clear();
+                return KeyResult::Command(command);
+            }
+            let pending = &self.pending;
+            if self
+                .bindings
+                .keys()
+                .any(|sequence| sequence.len() > pending.len() && sequence.starts_with(pending))
+            {
+                KeyResult::Pending
+            } else {
+                self.pending.clear();
+                KeyResult::Unbound
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+#[path = "commands.rs"]
+mod commands;
+
+#[path = "cursor.rs"]
+mod cursor;
+
+#[path = "kill_ring.rs"]
+mod kill_ring;
+
+#[path = "keymap.rs"]
+mod keymap;
+
+#[cfg(test)]
+mod tests {
+    use super::commands::commands::Command;
+    use super::keymap::keymap::{parse_sequence, Chord, KeyResult, Keymap, Modifiers};
+    fn press(keymap: &mut Keymap, chord: &str) -> KeyResult {
+        keymap.press(Chord::parse(chord).unwrap())
+    }
+    #[test]
+    fn parse_plain_key() {
+        let chord = Chord::parse("left").unwrap();
+        assert_eq!(chord.key, "left");
+        assert_eq!(chord.modifiers, Modifiers::default());
+    }
+    #[test]
+    fn parse_modifiers() {
+        let chord = Chord::parse("Ctrl+Shift+Left").unwrap();
+        assert_eq!(chord.key, "left");
+        assert!(chord.modifiers.ctrl);
+        assert!(chord.modifiers.shift);
+        assert!(!chord.modifiers.alt);
+    }
+    #[test]
+    fn parse_plus_key() {
+        assert_eq!(
+            Chord::parse("ctrl++").unwrap(),
+            Chord::parse("ctrl+plus").unwrap()
+        );
+        assert_eq!(Chord::parse("+").unwrap().key, "+");
+    }
+    #[test]
+    fn parse_aliases() {
+        assert_eq!(Chord::parse("enter").unwrap().key, "return");
+        assert_eq!(Chord::parse("esc").unwrap().key, "escape");
+    }
+    #[test]
+    fn parse_sdl_key_names() {
+        assert_eq!(
+            Chord::new("Page Up", Modifiers::default()),
+            Chord::parse("pageup").unwrap()
+        );
+    }
+    #[test]
+    fn parse_errors() {
+        assert!(Chord::parse("ctrl+").is_err());
+        assert!(Chord::parse("super+a").is_err());
+        assert!(parse_sequence("  ").is_err());
+    }
+    #[test]
+    fn display_roundtrip() {
+        let chord = Chord::parse("shift+alt+ctrl+x").unwrap();
+        assert_eq!(chord.to_string(), "ctrl+alt+shift+x");
+        assert_eq!(Chord::parse(&chord.to_string()).unwrap(), chord);
+    }
+    #[test]
+    fn default_bindings() {
+        let mut keymap = Keymap::default();
+        assert_eq!(
+            press(&mut keymap, "left"),
+            KeyResult::Command(Command::CursorLeft)
+        );
+        assert_eq!(
+            press(&mut keymap, "shift+left"),
+            KeyResult::Command(Command::SelectLeft)
+        );
+        assert_eq!(
+            press(&mut keymap, "ctrl+s"),
+            KeyResult::Command(Command::FileSave)
+        );
+        assert_eq!(press(&mut keymap, "a"), KeyResult::Unbound);
+    }
+    #[test]
+    fn sequence() {
+        let mut keymap = Keymap::empty();
+        keymap.bind("ctrl+k ctrl+u", Command::CursorUp).unwrap();
+        assert_eq!(press(&mut keymap, "ctrl+k"), KeyResult::Pending);
+        assert_eq!(
+            press(&mut keymap, "ctrl+u"),
+            KeyResult::Command(Command::CursorUp)
+        );
+    }
+    #[test]
+    fn sequence_broken_off() {
+        let mut keymap = Keymap::empty();
+        keymap.bind("ctrl+k ctrl+u", Command::CursorUp).unwrap();
+        keymap.bind("ctrl+u", Command::CursorDown).unwrap();
+        assert_eq!(press(&mut keymap, "ctrl+k"), KeyResult::Pending);
+        assert_eq!(press(&mut keymap, "x"), KeyResult::Unbound);
+        assert_eq!(
+            press(&mut keymap, "ctrl+u"),
+            KeyResult::Command(Command::CursorDown)
+        );
+    }
+    #[test]
+    fn unbind() {
+        let mut keymap = Keymap::default();
+        keymap.unbind("escape").unwrap();
+        assert_eq!(press(&mut keymap, "escape"), KeyResult::Unbound);
+    }
+    #[test]
+    fn zoom_bindings() {
+        for mut keymap in [Keymap::default(), Keymap::emacs()] {
+            for chord in &["ctrl+=", "ctrl+shift+=", "ctrl+plus"] {
+                assert_eq!(
+                    press(&mut keymap, chord),
+                    KeyResult::Command(Command::ViewZoomIn)
+                );
+            }
+            assert_eq!(
+                press(&mut keymap, "ctrl+minus"),
+                KeyResult::Command(Command::ViewZoomOut)
+            );
+            assert_eq!(
+                press(&mut keymap, "ctrl+0"),
+                KeyResult::Command(Command::ViewZoomReset)
+            );
+        }
+    }
+    #[test]
+    fn emacs_bindings() {
+        let mut keymap = Keymap::emacs();
+        assert_eq!(
+            press(&mut keymap, "ctrl+f"),
+            KeyResult::Command(Command::CursorRight)
+        );
+        assert_eq!(
+            press(&mut keymap, "alt+y"),
+            KeyResult::Command(Command::YankPop)
+        );
+        assert_eq!(
+            press(&mut keymap, "ctrl+space"),
+            KeyResult::Command(Command::MarkSet)
+        );
+        assert_eq!(press(&mut keymap, "shift+left"), KeyResult::Unbound);
+        assert_eq!(press(&mut keymap, "ctrl+x"), KeyResult::Pending);
+        assert_eq!(
+            press(&mut keymap, "ctrl+s"),
+            KeyResult::Command(Command::FileSave)
+        );
+    }
+}
@@ -0,0 +1,47 @@
+pub mod kill_ring {
+    use std::collections::VecDeque;
+
+    static CAPACITY: usize = 120;
+
+    /// Emacs-style ring of killed text. The newest kill is yanked first,
+    /// rotating moves on to older ones and wraps around.
+    #[derive(Debug, Default)]
+    pub struct KillRing {
+        entries: VecDeque<String>,
+        index: usize,
+    }
+
+    impl KillRing {
+        pub fn new() -> KillRing {
+            KillRing::default()
+        }
+        pub fn push(&mut self, text: String) {
+            if text.is_empty() {
+                return;
+            }
+            self.entries.push_front(text);
+            self.entries.truncate(CAPACITY);
+            self.index = 0;
+        }
+        /// Extends the newest kill, so that consecutive kills yank as one.
+        pub fn append(&mut self, text: &str) {
+            match self.entries.front_mut() {
+                Some(newest) => {
+                    newest.push_str(text);
+                    self.index = 0;
+                }
+                None => self.push(text.to_string()),
+            }
+        }
+        pub fn current(&self) -> Option<&str> {
+            self.entries.get(self.index).map(|entry| entry.as_str())
+        }
+        pub fn rotate(&mut self) -> Option<&str> {
+            if self.entries.is_empty() {
+                return None;
+            }
+            self.index = (self.index + 1) % self.entries.len();
+            self.current()
+        }
+    }
+}
@@ -0,0 +1,67 @@
+#[path = "kill_ring.rs"]
+mod kill_ring;
+
+#[cfg(test)]
+mod tests {
+    use super::kill_ring::kill_ring::KillRing;
+    #[test]
+    fn empty() {
+        let mut kill_ring = KillRing::new();
+        assert_eq!(kill_ring.current(), None);
+        assert_eq!(kill_ring.rotate(), None);
+    }
+    #[test]
+    fn newest_first() {
+        let mut kill_ring = KillRing::new();
+        kill_ring.push(String::from("a"));
+        kill_ring.push(String::from("b"));
+        assert_eq!(kill_ring.current(), Some("b"));
+    }
+    #[test]
+    fn rotate_wraps_around() {
+        let mut kill_ring = KillRing::new();
+        kill_ring.push(String::from("a"));
+        kill_ring.push(String::from("b"));
+        kill_ring.push(String::from("c"));
+        assert_eq!(kill_ring.rotate(), Some("b"));
+        assert_eq!(kill_ring.rotate(), Some("a"));
+        assert_eq!(kill_ring.rotate(), Some("c"));
+    }
+    #[test]
+    fn push_resets_rotation() {
+        let mut kill_ring = KillRing::new();
+        kill_ring.push(String::from("a"));
+        kill_ring.push(String::from("b"));
+        kill_ring.rotate();
+        kill_ring.push(String::from("c"));
+        assert_eq!(kill_ring.current(), Some("c"));
+    }
+    #[test]
+    fn append() {
+        let mut kill_ring = KillRing::new();
+        kill_ring.append("a");
+        kill_ring.append("b");
+        assert_eq!(kill_ring.current(), Some("ab"));
+        assert_eq!(kill_ring.rotate(), Some("ab"));
+    }
+    #[test]
+    fn ignores_empty_kills() {
+        let mut kill_ring = KillRing::new();
+        kill_ring.push(String::from("a"));
+        kill_ring.push(String::from(""));
+        assert_eq!(kill_ring.current(), Some("a"));
+    }
+    #[test]
+    fn capacity() {
+        let mut kill_ring = KillRing::new();
+        for index in 0..200 {
+            kill_ring.push(index.to_string());
+        }
+        let mut oldest = String::new();
+        for _ in 0..119 {
+            oldest = kill_ring.rotate().unwrap().to_string();
+        }
+        assert_eq!(oldest, "80");
+        assert_eq!(kill_ring.rotate(), Some("199"));
+    }
+}
@@ -0,0 +1,148 @@
+pub mod rasterizer {
+    use image::{Rgba, RgbaImage};
+    use sdl2::pixels::Color;
+
+    /// How much of every pixel in a rectangle a shape covers, from 0 to 1.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Coverage {
+        pub left: i32,
+        pub top: i32,
+        pub width: u32,
+        pub height: u32,
+        values: Vec<f32>,
+    }
+
+    impl Coverage {
+        fn new(left: i32, top: i32, width: u32, height: u32) -> Coverage {
+            Coverage {
+                left,
+                top,
+                width,
+                height,
+                values: vec![0.0; (width * height) as usize],
+            }
+        }
+        /// Nothing outside of the rectangle is covered.
+        pub fn get(&self, x: i32, y: i32) -> f32 {
+            let (column, row) = (x - self.left, y - self.top);
+            if column < 0 || row < 0 || column >= self.width as i32 || row >= self.height as i32 {
+                return 0.0;
+            }
+            self.values[(row as u32 * self.width + column as u32) as usize]
+        }
+        /// Keeps the larger coverage, so overlapping segments at joins do
+        /// not add up.
+        fn cover(&mut self, x: i32, y: i32, value: f32) {
+            let index = ((y - self.top) as u32 * self.width + (x - self.left) as u32) as usize;
+            if value > self.values[index] {
+                self.values[index] = value;
+            }
+        }
+    }
+
+    fn get_squared_distance_to_segment(point: (f32, f32), from: (f32, f32), to: (f32, f32)) -> f32 {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let length_squared = dx * dx + dy * dy;
+        let along = if length_squared == 0.0 {
+            0.0
+        } else {
+            (((point.0 - from.0) * dx + (point.1 - from.1) * dy) / length_squared).clamp(0.0, 1.0)
+        };
+        let (x, y) = (from.0 + along * dx, from.1 + along * dy);
+        (point.0 - x).powi(2) + (point.1 - y).powi(2)
+    }
+
+    /// The pixels a stroke through `points` covers when it is `width`
+    /// pixels wide with round caps and joins. Points are pixel centers.
+    /// Smooth strokes cover the pixels along their edges in part, others
+    /// cover a pixel whole or not at all.
+    pub fn rasterize_stroke(points: &[(i32, i32)], width: f32, is_smooth: bool) -> Coverage {
+        if points.is_empty() {
+            return Coverage::new(0, 0, 0, 0);
+        }
+        let radius = (width / 2.0).max(0.5);
+        // Smooth strokes fade out over the half pixel past their radius.
+        let outer = radius + 0.5;
+        let reach = outer.ceil() as i32;
+        let left = points.iter().map(|(x, _)| x).min().unwrap() - reach;
+        let top = points.iter().map(|(_, y)| y).min().unwrap() - reach;
+        let right = points.iter().map(|(x, _)| x).max().unwrap() + reach;
+        let bottom = points.iter().map(|(_, y)| y).max().unwrap() + reach;
+        let mut coverage = Coverage::new(
+            left,
+            top,
+            (right - left + 1) as u32,
+            (bottom - top + 1) as u32,
+        );
+        let to_float = |(x, y): (i32, i32)| (x as f32, y as f32);
+        // A single point is a segment from the point to itself.
+        let last = points.len().max(2) - 1;
+        for index in 0..last {
+            let from = points[index];
+            let to = points[(index + 1).min(points.len() - 1)];
+            for y in (from.1.min(to.1) - reach)..=(from.1.max(to.1) + reach) {
+                for x in (from.0.min(to.0) - reach)..=(from.0.max(to.0) + reach) {
+                    let squared_distance = get_squared_distance_to_segment(
+                        (x as f32, y as f32),
+                        to_float(from),
+                        to_float(to),
+                    );
+                    let value = if !is_smooth {
+                        if squared_distance > radius * radius {
+                            continue;
+                        }
+                        1.0
+                    } else if squared_distance >= outer * outer {
+                        continue;
+                    } else {
+                        (outer - squared_distance.sqrt()).min(1.0)
+                    };
+                    coverage.cover(x, y, value);
+                }
+            }
+        }
+        coverage
+    }
+
+    /// The covered rectangle in `color`, with the coverage as alpha, to be
+    /// blended over something else later.
+    pub fn get_coverage_image(coverage: &Coverage, color: Color) -> RgbaImage {
+        RgbaImage::from_fn(coverage.width, coverage.height, |x, y| {
+            let alpha = coverage.get(coverage.left + x as i32, coverage.top + y as i32);
+            Rgba([
+                color.r,
+                color.g,
+                color.b,
+                (alpha * color.a as f32).round() as u8,
+            ])
+        })
+    }
+
+    /// Mixes `color` into the covered pixels of `image` as far as they are
+    /// covered.
+    pub fn blend(image: &mut RgbaImage, coverage: &Coverage, color: Color) {
+        let (width, height) = (image.width() as i32, image.height() as i32);
+        let left = coverage.left.max(0);
+        let top = coverage.top.max(0);
+        let right = (coverage.left + coverage.width as i32).min(width);
+        let bottom = (coverage.top + coverage.height as i32).min(height);
+        for y in top..bottom {
+            for x in left..right {
+                let alpha = coverage.get(x, y) * color.a as f32 / 255.0;
+                if alpha <= 0.0 {
+                    continue;
+                }
+                let pixel = image.get_pixel_mut(x as u32, y as u32);
+                let mix = |old: u8, new: u8| {
+                    (old as f32 + (new as f32 - old as f32) * alpha).round() as u8
+                };
+                *pixel = Rgba([
+                    mix(pixel[0], color.r),
+                    mix(pixel[1], color.g),
+                    mix(pixel[2], color.b),
+                    mix(pixel[3], 255),
+                ]);
+            }
+        }
+    }
+}
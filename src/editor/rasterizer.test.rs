@@ -0,0 +1,85 @@
+#[path = "rasterizer.rs"]
+mod rasterizer;
+
+#[cfg(test)]
+mod tests {
+    use super::rasterizer::rasterizer::{blend, get_coverage_image, rasterize_stroke};
+    use image::{Rgba, RgbaImage};
+    use sdl2::pixels::Color;
+    #[test]
+    fn crisp_strokes_cover_whole_pixels() {
+        let coverage = rasterize_stroke(&[(2, 2), (6, 2)], 1.0, false);
+        for x in 2..=6 {
+            assert_eq!(coverage.get(x, 2), 1.0);
+            assert_eq!(coverage.get(x, 1), 0.0);
+            assert_eq!(coverage.get(x, 3), 0.0);
+        }
+        assert_eq!(coverage.get(1, 2), 0.0);
+        assert_eq!(coverage.get(7, 2), 0.0);
+        assert_eq!(coverage.get(-50, 50), 0.0);
+    }
+    #[test]
+    fn smooth_strokes_fade_at_their_edges() {
+        let coverage = rasterize_stroke(&[(0, 0), (10, 5)], 1.0, true);
+        assert_eq!(coverage.get(0, 0), 1.0);
+        assert_eq!(coverage.get(10, 5), 1.0);
+        let edge = coverage.get(5, 3);
+        assert!(edge > 0.0 && edge < 1.0, "{}", edge);
+        assert_eq!(coverage.get(0, 5), 0.0);
+    }
+    #[test]
+    fn wide_strokes_have_round_caps() {
+        let coverage = rasterize_stroke(&[(10, 10), (20, 10)], 6.0, false);
+        assert_eq!(coverage.get(15, 7), 1.0);
+        assert_eq!(coverage.get(15, 13), 1.0);
+        assert_eq!(coverage.get(15, 14), 0.0);
+        assert_eq!(coverage.get(7, 10), 1.0);
+        assert_eq!(coverage.get(6, 10), 0.0);
+        // A square cap would cover the corners as well.
+        assert_eq!(coverage.get(7, 7), 0.0);
+        assert_eq!(coverage.get(23, 13), 0.0);
+    }
+    #[test]
+    fn joins_do_not_add_up() {
+        let coverage = rasterize_stroke(&[(0, 0), (5, 0), (5, 5)], 2.0, true);
+        assert_eq!(coverage.get(5, 0), 1.0);
+        let single = rasterize_stroke(&[(0, 0), (5, 0)], 2.0, true);
+        assert_eq!(coverage.get(2, 1), single.get(2, 1));
+    }
+    #[test]
+    fn single_points_are_dots() {
+        let coverage = rasterize_stroke(&[(3, 3)], 4.0, false);
+        assert_eq!(coverage.get(3, 3), 1.0);
+        assert_eq!(coverage.get(5, 3), 1.0);
+        assert_eq!(coverage.get(5, 5), 0.0);
+        assert_eq!(rasterize_stroke(&[], 1.0, true).get(0, 0), 0.0);
+    }
+    #[test]
+    fn blending_mixes_by_coverage() {
+        let mut image = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 0, 255]));
+        let coverage = rasterize_stroke(&[(0, 0), (7, 7)], 1.0, true);
+        blend(&mut image, &coverage, Color::RGB(200, 100, 0));
+        assert_eq!(*image.get_pixel(3, 3), Rgba([200, 100, 0, 255]));
+        let partial = image.get_pixel(3, 4);
+        assert!(partial[0] > 0 && partial[0] < 200, "{:?}", partial);
+        assert_eq!(*image.get_pixel(0, 7), Rgba([0, 0, 0, 255]));
+    }
+    #[test]
+    fn blending_leaves_out_pixels_outside_of_the_image() {
+        let mut image = RgbaImage::new(4, 4);
+        let coverage = rasterize_stroke(&[(-10, 2), (10, 2)], 1.0, false);
+        blend(&mut image, &coverage, Color::RGB(255, 255, 255));
+        assert_eq!(*image.get_pixel(0, 2), Rgba([255, 255, 255, 255]));
+        assert_eq!(*image.get_pixel(3, 2), Rgba([255, 255, 255, 255]));
+        assert_eq!(*image.get_pixel(3, 3), Rgba([0, 0, 0, 0]));
+    }
+    #[test]
+    fn coverage_images_carry_coverage_as_alpha() {
+        let coverage = rasterize_stroke(&[(2, 2), (2, 2)], 1.0, true);
+        let image = get_coverage_image(&coverage, Color::RGB(10, 20, 30));
+        assert_eq!(image.dimensions(), (coverage.width, coverage.height));
+        let (x, y) = ((2 - coverage.left) as u32, (2 - coverage.top) as u32);
+        assert_eq!(*image.get_pixel(x, y), Rgba([10, 20, 30, 255]));
+        assert_eq!(*image.get_pixel(0, 0), Rgba([10, 20, 30, 0]));
+    }
+}
@@ -0,0 +1,799 @@
+pub mod rendering {
+    use super::super::config::config::{
+        get_character_x, get_character_y, Caret, CaretStyle, Layout,
+    };
+    use super::super::cursor::cursor::Cursor;
+    use super::super::font::font::Font;
+    use super::super::highlighting::highlighting::{Highlighter, Span, TokenKind};
+    use super::super::scrollbar::scrollbar::{Minimap, Scrollbar, MINIMAP_COLUMNS};
+    use super::super::text_rendering::text_rendering::get_character_strokes;
+    use super::super::theme::theme::Theme;
+    use sdl2::pixels::Color;
+    use sdl2::rect::{Point, Rect};
+    use sdl2::render::{Canvas, RenderTarget};
+    use std::time::Duration;
+
+    /// Something to draw lines and rectangles on, in the color set last.
+    pub trait Renderer {
+        fn set_color(&mut self, color: Color);
+        /// Fills everything.
+        fn clear(&mut self);
+        /// A line with both ends included.
+        fn draw_line(&mut self, from: (i32, i32), to: (i32, i32));
+        fn fill_rect(&mut self, left: i32, top: i32, width: u32, height: u32);
+        /// Lines connecting `points` in order.
+        fn draw_lines(&mut self, points: &[(i32, i32)]) {
+            for segment in points.windows(2) {
+                self.draw_line(segment[0], segment[1]);
+            }
+        }
+        /// Lines connecting `points` in order, `width` pixels wide.
+        fn draw_stroke(&mut self, points: &[(i32, i32)], width: u32) {
+            draw_wide_lines(self, points, width);
+        }
+        fn draw_glyph(
+            &mut self,
+            layout: &Layout,
+            font: &Font,
+            glyph: &PlacedGlyph,
+            stroke_width: u32,
+        ) {
+            self.set_color(glyph.color);
+            draw_character(
+                self,
+                layout,
+                font,
+                glyph.character,
+                glyph.column,
+                glyph.line,
+                stroke_width,
+            );
+        }
+        /// Glyphs that follow each other on one line.
+        fn draw_glyph_run(
+            &mut self,
+            layout: &Layout,
+            font: &Font,
+            glyphs: &[PlacedGlyph],
+            stroke_width: u32,
+        ) {
+            for glyph in glyphs {
+                self.draw_glyph(layout, font, glyph, stroke_width);
+            }
+        }
+    }
+
+    impl<T: RenderTarget> Renderer for Canvas<T> {
+        fn set_color(&mut self, color: Color) {
+            self.set_draw_color(color);
+        }
+        fn clear(&mut self) {
+            Canvas::clear(self);
+        }
+        fn draw_line(&mut self, from: (i32, i32), to: (i32, i32)) {
+            Canvas::draw_line(self, from, to).unwrap();
+        }
+        fn fill_rect(&mut self, left: i32, top: i32, width: u32, height: u32) {
+            Canvas::fill_rect(self, Rect::new(left, top, width, height)).unwrap();
+        }
+        fn draw_lines(&mut self, points: &[(i32, i32)]) {
+            let points = points
+                .iter()
+                .map(|(x, y)| Point::new(*x, *y))
+                .collect::<Vec<Point>>();
+            Canvas::draw_lines(self, points.as_slice()).unwrap();
+        }
+    }
+
+    /// What a `Renderer` was asked to do, for tests.
+    #[cfg(test)]
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum DrawCall {
+        Clear(Color),
+        Line((i32, i32), (i32, i32), Color),
+        Rect(i32, i32, u32, u32, Color),
+    }
+
+    /// Records the calls instead of drawing.
+    #[cfg(test)]
+    pub struct RecordingRenderer {
+        pub calls: Vec<DrawCall>,
+        color: Color,
+    }
+
+    #[cfg(test)]
+    impl RecordingRenderer {
+        pub fn new() -> RecordingRenderer {
+            RecordingRenderer {
+                calls: vec![],
+                color: Color::RGB(0, 0, 0),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    impl Renderer for RecordingRenderer {
+        fn set_color(&mut self, color: Color) {
+            self.color = color;
+        }
+        fn clear(&mut self) {
+            self.calls.push(DrawCall::Clear(self.color));
+        }
+        fn draw_line(&mut self, from: (i32, i32), to: (i32, i32)) {
+            self.calls.push(DrawCall::Line(from, to, self.color));
+        }
+        fn fill_rect(&mut self, left: i32, top: i32, width: u32, height: u32) {
+            self.calls
+                .push(DrawCall::Rect(left, top, width, height, self.color));
+        }
+    }
+
+    /// Lines connecting `points`, `width` pixels wide by drawing them again
+    /// shifted by up to half the width in every direction.
+    pub fn draw_wide_lines<R: Renderer + ?Sized>(
+        renderer: &mut R,
+        points: &[(i32, i32)],
+        width: u32,
+    ) {
+        if width <= 1 {
+            renderer.draw_lines(points);
+            return;
+        }
+        let offsets = -((width as i32 - 1) / 2)..=(width as i32 / 2);
+        for offset_x in offsets.clone() {
+            for offset_y in offsets.clone() {
+                let shifted = points
+                    .iter()
+                    .map(|(x, y)| (x + offset_x, y + offset_y))
+                    .collect::<Vec<(i32, i32)>>();
+                renderer.draw_lines(&shifted);
+            }
+        }
+    }
+
+    pub fn draw_character<R: Renderer + ?Sized>(
+        renderer: &mut R,
+        layout: &Layout,
+        font: &Font,
+        character: char,
+        column: i32,
+        line: i32,
+        stroke_width: u32,
+    ) {
+        let (left, top) = (
+            get_character_x(layout, column),
+            get_character_y(layout, line),
+        );
+        let strokes = get_character_strokes(
+            font,
+            &character,
+            layout.character_width,
+            layout.character_height,
+        );
+        for stroke in strokes.iter() {
+            let points = stroke
+                .iter()
+                .map(|(x, y)| (left + x, top + y))
+                .collect::<Vec<(i32, i32)>>();
+            renderer.draw_stroke(&points, stroke_width);
+        }
+    }
+
+    pub fn draw_text<R: Renderer>(
+        renderer: &mut R,
+        layout: &Layout,
+        font: &Font,
+        text: &str,
+        column: i32,
+        line: i32,
+        stroke_width: u32,
+    ) {
+        for (column_index, character) in text.chars().enumerate() {
+            draw_character(
+                renderer,
+                layout,
+                font,
+                character,
+                column + column_index as i32,
+                line,
+                stroke_width,
+            );
+        }
+    }
+
+    /// A character in a cell, counted in columns and lines from the camera.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct PlacedGlyph {
+        pub character: char,
+        pub column: i32,
+        pub line: i32,
+        pub color: Color,
+    }
+
+    /// Everything drawn on top of the background, in pixels apart from the
+    /// glyphs.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Shape {
+        Glyph(PlacedGlyph),
+        Line((i32, i32), (i32, i32), Color),
+        Rect(i32, i32, u32, u32, Color),
+    }
+
+    /// A frame laid out, drawn in order.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Frame {
+        pub background: Color,
+        pub shapes: Vec<Shape>,
+        /// How wide glyph strokes and lines are, in pixels.
+        pub stroke_width: u32,
+        /// Drawn last, in its own layout with one pixel wide strokes.
+        pub minimap: Option<MinimapFrame>,
+    }
+
+    /// The shapes of the minimap, with glyphs in the minimap's cells.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct MinimapFrame {
+        pub layout: Layout,
+        pub shapes: Vec<Shape>,
+    }
+
+    /// The texts at the left and right end of the status line.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct StatusLine {
+        pub left: String,
+        pub right: String,
+    }
+
+    /// How the caret is drawn.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct CaretLook {
+        pub style: CaretStyle,
+        /// Only the outline of the cell, while the window is not focused.
+        pub is_hollow: bool,
+    }
+
+    impl Default for CaretLook {
+        fn default() -> CaretLook {
+            CaretLook {
+                style: CaretStyle::Bar,
+                is_hollow: false,
+            }
+        }
+    }
+
+    /// The configured caret as it is shown now, `None` while it blinks off.
+    /// Overwriting can turn it into a block, losing focus makes it hollow
+    /// and keeps it on.
+    pub fn get_caret_look(
+        caret: &Caret,
+        is_overwrite: bool,
+        is_focused: bool,
+        is_on: bool,
+    ) -> Option<CaretLook> {
+        if is_focused && !is_on {
+            return None;
+        }
+        let style = if is_overwrite && caret.block_when_overwriting {
+            CaretStyle::Block
+        } else {
+            caret.style
+        };
+        Some(CaretLook {
+            style,
+            is_hollow: !is_focused,
+        })
+    }
+
+    /// Text an input method is still composing.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Preedit {
+        pub text: String,
+        /// Where the input method's cursor is in `text`, in characters.
+        pub cursor: usize,
+    }
+
+    /// What to lay out.
+    pub struct View<'a> {
+        pub cursor: &'a Cursor,
+        pub highlighter: &'a Highlighter,
+        pub camera_line: i32,
+        /// How many lines of text are shown, the last one may be cut off.
+        pub lines: usize,
+        pub columns: i32,
+        /// In pixels.
+        pub width: u32,
+        /// Shown below the text.
+        pub status_line: Option<StatusLine>,
+        /// `None` while a blinking caret is off.
+        pub caret: Option<CaretLook>,
+        /// Shown underlined at the caret, moving the rest of the line right.
+        pub preedit: Option<Preedit>,
+        /// In pixels, wider on high density displays.
+        pub stroke_width: u32,
+        pub scrollbar: Option<Scrollbar>,
+        pub minimap: Option<Minimap>,
+    }
+
+    /// The selected columns as line, first and last column, in the order
+    /// they are drawn.
+    pub fn get_selection_segments(cursor: &Cursor) -> Vec<(usize, usize, usize)> {
+        let (start, end) = if cursor.current > cursor.extender {
+            (&cursor.extender, &cursor.current)
+        } else {
+            (&cursor.current, &cursor.extender)
+        };
+        if start == end {
+            return vec![];
+        }
+        if start.line == end.line {
+            return vec![(start.line, start.column, end.column)];
+        }
+        let line_length = |line: usize| cursor.lines[line].chars().count();
+        let mut segments = ((start.line + 1)..end.line)
+            .map(|line| (line, 0, line_length(line)))
+            .collect::<Vec<(usize, usize, usize)>>();
+        segments.push((start.line, start.column, line_length(start.line)));
+        segments.push((end.line, 0, end.column));
+        segments
+    }
+
+    /// Where the text selected within one line occurs, the selection
+    /// included, as line, first and last column. Nothing for selections of
+    /// whitespace only.
+    pub fn get_selection_matches(cursor: &Cursor) -> Vec<(usize, usize, usize)> {
+        let (line, from, to) = match get_selection_segments(cursor).as_slice() {
+            [segment] => *segment,
+            _ => return vec![],
+        };
+        let needle = cursor.lines[line]
+            .chars()
+            .skip(from)
+            .take(to - from)
+            .collect::<Vec<char>>();
+        if needle.iter().all(|character| character.is_whitespace()) {
+            return vec![];
+        }
+        let mut matches = vec![];
+        for (index, text) in cursor.lines.iter().enumerate() {
+            let characters = text.chars().collect::<Vec<char>>();
+            let mut column = 0;
+            while column + needle.len() <= characters.len() {
+                if characters[column..column + needle.len()] == needle[..] {
+                    matches.push((index, column, column + needle.len()));
+                    column += needle.len();
+                } else {
+                    column += 1;
+                }
+            }
+        }
+        matches
+    }
+
+    /// The camera line after the cursor moved, scrolled just enough to keep
+    /// `cursor_line` in view.
+    pub fn get_camera_line_following_cursor(
+        camera_line: i32,
+        cursor_line: usize,
+        height_in_lines: f32,
+    ) -> i32 {
+        if cursor_line > (camera_line as usize + height_in_lines as usize) {
+            cursor_line as i32 - height_in_lines as i32
+        } else if cursor_line < camera_line as usize {
+            cursor_line as i32
+        } else {
+            camera_line
+        }
+    }
+
+    /// The glyphs of a line, each in the color of the span it is in and
+    /// characters outside of every span in the text color.
+    fn layout_line(shapes: &mut Vec<Shape>, theme: &Theme, text: &str, spans: &[Span], line: i32) {
+        let mut spans = spans.iter().peekable();
+        for (column, character) in text.chars().enumerate() {
+            while spans.peek().is_some_and(|span| span.end <= column) {
+                spans.next();
+            }
+            let kind = match spans.peek() {
+                Some(span) if span.start <= column => span.kind,
+                _ => TokenKind::Plain,
+            };
+            shapes.push(Shape::Glyph(PlacedGlyph {
+                character,
+                column: column as i32,
+                line,
+                color: theme.get_token_color(kind),
+            }));
+        }
+    }
+
+    fn caret(
+        layout: &Layout,
+        camera_line: i32,
+        line: usize,
+        column: usize,
+        color: Color,
+    ) -> Option<Shape> {
+        if line < camera_line.max(0) as usize {
+            return None;
+        }
+        let x = get_character_x(layout, column as i32);
+        let y = get_character_y(layout, line as i32 - camera_line);
+        Some(Shape::Line(
+            (x, y - layout.character_height / 5),
+            (x, y + layout.character_height * 6 / 5),
+            color,
+        ))
+    }
+
+    /// The column the caret is shown in and the character shown there,
+    /// inside the pre-edit text while there is one.
+    fn get_displayed_caret(view: &View) -> (usize, Option<char>) {
+        let caret = &view.cursor.current;
+        let character_after_preedit = view.cursor.lines[caret.line].chars().nth(caret.column);
+        match view.preedit.as_ref() {
+            Some(preedit) => (
+                caret.column + preedit.cursor,
+                preedit
+                    .text
+                    .chars()
+                    .nth(preedit.cursor)
+                    .or(character_after_preedit),
+            ),
+            None => (caret.column, character_after_preedit),
+        }
+    }
+
+    /// The cell of the caret in pixels as left, top, width and height, for
+    /// placing the input method's candidate window next to it.
+    pub fn get_caret_cell(view: &View, layout: &Layout) -> (i32, i32, u32, u32) {
+        let (column, _) = get_displayed_caret(view);
+        let row = view.cursor.current.line as i32 - view.camera_line;
+        (
+            get_character_x(layout, column as i32),
+            get_character_y(layout, row),
+            layout.character_width as u32,
+            (layout.character_height + layout.line_gap) as u32,
+        )
+    }
+
+    /// The caret drawn in `look`, with the character under a block in the
+    /// background color.
+    fn layout_caret(
+        shapes: &mut Vec<Shape>,
+        view: &View,
+        layout: &Layout,
+        theme: &Theme,
+        look: CaretLook,
+    ) {
+        let line = view.cursor.current.line;
+        let (column, character) = get_displayed_caret(view);
+        if line < view.camera_line.max(0) as usize {
+            return;
+        }
+        let row = line as i32 - view.camera_line;
+        let (x, y) = (
+            get_character_x(layout, column as i32),
+            get_character_y(layout, row),
+        );
+        let (width, height) = (layout.character_width, layout.character_height);
+        let color = theme.colors.cursor;
+        if look.is_hollow {
+            let corners = [
+                (x, y),
+                (x + width, y),
+                (x + width, y + height),
+                (x, y + height),
+            ];
+            for index in 0..corners.len() {
+                let next = corners[(index + 1) % corners.len()];
+                shapes.push(Shape::Line(corners[index], next, color));
+            }
+            return;
+        }
+        match look.style {
+            CaretStyle::Bar => shapes.extend(caret(layout, view.camera_line, line, column, color)),
+            CaretStyle::Block => {
+                shapes.push(Shape::Rect(x, y, width as u32, height as u32, color));
+                if let Some(character) = character {
+                    shapes.push(Shape::Glyph(PlacedGlyph {
+                        character,
+                        column: column as i32,
+                        line: row,
+                        color: theme.colors.background,
+                    }));
+                }
+            }
+            CaretStyle::Underline => {
+                let y = y + height + layout.line_gap / 2;
+                shapes.push(Shape::Line((x, y), (x + width, y), color));
+            }
+        }
+    }
+
+    /// Moves the glyphs from the caret on, laid out from `first_glyph` on,
+    /// to the right and puts the underlined pre-edit text in between.
+    fn layout_preedit(
+        shapes: &mut Vec<Shape>,
+        first_glyph: usize,
+        view: &View,
+        layout: &Layout,
+        theme: &Theme,
+        preedit: &Preedit,
+    ) {
+        let caret = &view.cursor.current;
+        let (column, line) = (caret.column as i32, caret.line as i32 - view.camera_line);
+        let length = preedit.text.chars().count() as i32;
+        for shape in shapes[first_glyph..].iter_mut() {
+            if let Shape::Glyph(glyph) = shape {
+                if glyph.column >= column {
+                    glyph.column += length;
+                }
+            }
+        }
+        for (index, character) in preedit.text.chars().enumerate() {
+            shapes.push(Shape::Glyph(PlacedGlyph {
+                character,
+                column: column + index as i32,
+                line,
+                color: theme.colors.text,
+            }));
+        }
+        if length > 0 {
+            let y = get_character_y(layout, line) + layout.character_height + layout.line_gap / 2;
+            shapes.push(Shape::Line(
+                (get_character_x(layout, column), y),
+                (
+                    get_character_x(layout, column + length) - layout.character_gap,
+                    y,
+                ),
+                theme.colors.text,
+            ));
+        }
+    }
+
+    /// Where everything goes: the text from the camera line on, the status
+    /// line, the caret, the selection and the selection's moving end.
+    pub fn layout_frame(view: &View, layout: &Layout, theme: &Theme) -> Frame {
+        let colors = &theme.colors;
+        let cursor = view.cursor;
+        let camera_line = view.camera_line;
+        let mut shapes = vec![];
+        let first_line = camera_line.max(0) as usize;
+        for (index, text) in cursor
+            .lines
+            .iter()
+            .enumerate()
+            .skip(first_line)
+            .take(view.lines)
+        {
+            let spans = view.highlighter.spans(index);
+            let first_glyph = shapes.len();
+            layout_line(&mut shapes, theme, text, spans, index as i32 - camera_line);
+            if index == cursor.current.line {
+                if let Some(preedit) = view.preedit.as_ref() {
+                    layout_preedit(&mut shapes, first_glyph, view, layout, theme, preedit);
+                }
+            }
+        }
+        // Long lines stop short of the scrollbar and the minimap.
+        if view.scrollbar.is_some() || view.minimap.is_some() {
+            shapes.retain(|shape| match shape {
+                Shape::Glyph(glyph) => glyph.column < view.columns,
+                _ => true,
+            });
+        }
+
+        if let Some(status_line) = view.status_line.as_ref() {
+            let line = view.lines as i32;
+            shapes.push(Shape::Rect(
+                0,
+                get_character_y(layout, line) - layout.line_gap / 2,
+                view.width,
+                (layout.character_height + layout.line_gap) as u32,
+                colors.status_bar,
+            ));
+            let right_column = view.columns - status_line.right.chars().count() as i32 - 1;
+            for (text, column) in &[(&status_line.left, 0), (&status_line.right, right_column)] {
+                for (index, character) in text.chars().enumerate() {
+                    shapes.push(Shape::Glyph(PlacedGlyph {
+                        character,
+                        column: column + index as i32,
+                        line,
+                        color: colors.status_bar_text,
+                    }));
+                }
+            }
+        }
+
+        if let Some(look) = view.caret {
+            layout_caret(&mut shapes, view, layout, theme, look);
+        }
+        for (line, from, to) in get_selection_segments(cursor) {
+            let y =
+                get_character_y(layout, line as i32 - camera_line) + layout.character_height / 2;
+            shapes.push(Shape::Line(
+                (get_character_x(layout, from as i32), y),
+                (get_character_x(layout, to as i32), y),
+                colors.cursor_selection,
+            ));
+        }
+        if cursor.current != cursor.extender {
+            shapes.extend(caret(
+                layout,
+                camera_line,
+                cursor.extender.line,
+                cursor.extender.column,
+                colors.cursor_extender,
+            ));
+        }
+        if let Some(scrollbar) = view.scrollbar.as_ref() {
+            shapes.push(Shape::Rect(
+                scrollbar.left,
+                0,
+                scrollbar.width,
+                scrollbar.height,
+                colors.status_bar,
+            ));
+            shapes.push(Shape::Rect(
+                scrollbar.left,
+                scrollbar.thumb_top,
+                scrollbar.width,
+                scrollbar.thumb_height,
+                colors.gutter,
+            ));
+        }
+        Frame {
+            background: colors.background,
+            shapes,
+            stroke_width: view.stroke_width,
+            minimap: view
+                .minimap
+                .as_ref()
+                .map(|minimap| layout_minimap(view, minimap, theme)),
+        }
+    }
+
+    /// The visible lines on the status bar color, then the selection, the
+    /// other matches of the selection, the text and the caret's line.
+    fn layout_minimap(view: &View, minimap: &Minimap, theme: &Theme) -> MinimapFrame {
+        let colors = &theme.colors;
+        let cursor = view.cursor;
+        let line_height = minimap.get_line_height();
+        let mut shapes = vec![Shape::Rect(
+            minimap.left,
+            0,
+            minimap.width,
+            minimap.height,
+            colors.background,
+        )];
+        shapes.push(Shape::Rect(
+            minimap.left,
+            minimap.get_line_y(view.camera_line.max(0) as usize),
+            minimap.width,
+            (view.lines as i32 * line_height) as u32,
+            colors.status_bar,
+        ));
+        let column_width = minimap.layout.character_width + minimap.layout.character_gap;
+        let mut mark = |(line, from, to): (usize, usize, usize), color: Color| {
+            let from = (from as i32).min(MINIMAP_COLUMNS);
+            let to = (to as i32).min(MINIMAP_COLUMNS).max(from + 1);
+            shapes.push(Shape::Rect(
+                get_character_x(&minimap.layout, from),
+                minimap.get_line_y(line),
+                ((to - from) * column_width) as u32,
+                line_height as u32,
+                color,
+            ));
+        };
+        let selection = get_selection_segments(cursor);
+        for segment in selection.iter() {
+            mark(*segment, colors.cursor_selection);
+        }
+        for found in get_selection_matches(cursor) {
+            if !selection.contains(&found) {
+                mark(found, colors.cursor_extender);
+            }
+        }
+        let lines = cursor.lines.iter().enumerate();
+        for (index, text) in lines.skip(minimap.first_line).take(minimap.lines) {
+            let line = (index - minimap.first_line) as i32;
+            let mut glyphs = vec![];
+            layout_line(
+                &mut glyphs,
+                theme,
+                text,
+                view.highlighter.spans(index),
+                line,
+            );
+            shapes.extend(glyphs.into_iter().filter(|shape| match shape {
+                Shape::Glyph(glyph) => glyph.column < MINIMAP_COLUMNS,
+                _ => true,
+            }));
+        }
+        let caret_y = minimap.get_line_y(cursor.extender.line) + line_height / 2;
+        shapes.push(Shape::Line(
+            (minimap.left, caret_y),
+            (minimap.left + minimap.width as i32 - 1, caret_y),
+            colors.cursor,
+        ));
+        MinimapFrame {
+            layout: minimap.layout.clone(),
+            shapes,
+        }
+    }
+
+    pub fn format_frame_time(time: Duration) -> String {
+        format!("{:.1} ms", time.as_secs_f64() * 1000.0)
+    }
+
+    /// `text` on the status bar color in the top right corner, over
+    /// everything else.
+    pub fn layout_overlay(
+        frame: &mut Frame,
+        view: &View,
+        layout: &Layout,
+        theme: &Theme,
+        text: &str,
+    ) {
+        let length = text.chars().count() as i32;
+        let column = (view.columns - length - 1).max(0);
+        frame.shapes.push(Shape::Rect(
+            get_character_x(layout, column) - layout.character_gap / 2,
+            get_character_y(layout, 0) - layout.line_gap / 2,
+            (length * (layout.character_width + layout.character_gap)) as u32,
+            (layout.character_height + layout.line_gap) as u32,
+            theme.colors.status_bar,
+        ));
+        for (index, character) in text.chars().enumerate() {
+            frame.shapes.push(Shape::Glyph(PlacedGlyph {
+                character,
+                column: column + index as i32,
+                line: 0,
+                color: theme.colors.status_bar_text,
+            }));
+        }
+    }
+
+    fn draw_shapes<R: Renderer>(
+        renderer: &mut R,
+        shapes: &[Shape],
+        layout: &Layout,
+        font: &Font,
+        stroke_width: u32,
+    ) {
+        let mut run: Vec<PlacedGlyph> = vec![];
+        let mut shapes = shapes.iter().peekable();
+        while let Some(shape) = shapes.next() {
+            match shape {
+                Shape::Glyph(glyph) => {
+                    run.push(glyph.clone());
+                    let is_run_going_on = match shapes.peek() {
+                        Some(Shape::Glyph(next)) => next.line == glyph.line,
+                        _ => false,
+                    };
+                    if !is_run_going_on {
+                        renderer.draw_glyph_run(layout, font, &run, stroke_width);
+                        run.clear();
+                    }
+                }
+                Shape::Line(from, to, color) => {
+                    renderer.set_color(*color);
+                    renderer.draw_stroke(&[*from, *to], stroke_width);
+                }
+                Shape::Rect(left, top, width, height, color) => {
+                    renderer.set_color(*color);
+                    renderer.fill_rect(*left, *top, *width, *height);
+                }
+            }
+        }
+    }
+
+    pub fn draw_frame<R: Renderer>(renderer: &mut R, frame: &Frame, layout: &Layout, font: &Font) {
+        renderer.set_color(frame.background);
+        renderer.clear();
+        draw_shapes(renderer, &frame.shapes, layout, font, frame.stroke_width);
+        if let Some(minimap) = frame.minimap.as_ref() {
+            draw_shapes(renderer, &minimap.shapes, &minimap.layout, font, 1);
+        }
+    }
+}
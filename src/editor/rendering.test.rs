@@ -0,0 +1,605 @@
+#[path = "rendering.rs"]
+mod rendering;
+
+#[path = "scrollbar.rs"]
+mod scrollbar;
+
+#[path = "config.rs"]
+mod config;
+
+#[path = "commands.rs"]
+mod commands;
+
+#[path = "cursor.rs"]
+mod cursor;
+
+#[path = "kill_ring.rs"]
+mod kill_ring;
+
+#[path = "keymap.rs"]
+mod keymap;
+
+#[path = "font.rs"]
+mod font;
+
+#[path = "highlighting.rs"]
+mod highlighting;
+
+#[path = "theme.rs"]
+mod theme;
+
+#[path = "text_rendering.rs"]
+mod text_rendering;
+
+#[cfg(test)]
+mod tests {
+    use super::config::config::{Caret, CaretStyle, Layout};
+    use super::cursor::cursor::Cursor;
+    use super::font::font::Font;
+    use super::highlighting::highlighting::{get_grammar_for_path, Highlighter};
+    use super::rendering::rendering::{
+        draw_frame, draw_wide_lines, format_frame_time, get_caret_cell, get_caret_look,
+        get_selection_matches, get_selection_segments, layout_frame, layout_overlay, CaretLook,
+        DrawCall, Frame, PlacedGlyph, Preedit, RecordingRenderer, Renderer, Shape, StatusLine,
+        View,
+    };
+    use super::scrollbar::scrollbar::{get_minimap_layout, Minimap, Scrollbar};
+    use super::theme::theme::Theme;
+    use sdl2::pixels::Color;
+    use std::str::FromStr;
+    use std::time::Duration;
+    fn cursor(lines: Vec<&str>) -> Cursor {
+        Cursor::new(lines.into_iter().map(String::from).collect())
+    }
+    fn frame(cursor: &Cursor, camera_line: i32, status_line: Option<StatusLine>) -> Frame {
+        let highlighter = Highlighter::new(None);
+        let view = View {
+            cursor,
+            highlighter: &highlighter,
+            camera_line,
+            lines: 2,
+            columns: 20,
+            width: 260,
+            status_line,
+            caret: Some(CaretLook::default()),
+            preedit: None,
+            stroke_width: 1,
+            scrollbar: None,
+            minimap: None,
+        };
+        layout_frame(&view, &Layout::default(), &Theme::dark())
+    }
+    fn frame_with_caret(cursor: &Cursor, caret: Option<CaretLook>) -> Frame {
+        let highlighter = Highlighter::new(None);
+        let view = View {
+            cursor,
+            highlighter: &highlighter,
+            camera_line: 0,
+            lines: 2,
+            columns: 20,
+            width: 260,
+            status_line: None,
+            caret,
+            preedit: None,
+            stroke_width: 1,
+            scrollbar: None,
+            minimap: None,
+        };
+        layout_frame(&view, &Layout::default(), &Theme::dark())
+    }
+    fn glyphs(frame: &Frame) -> Vec<(char, i32, i32)> {
+        frame
+            .shapes
+            .iter()
+            .filter_map(|shape| match shape {
+                Shape::Glyph(glyph) => Some((glyph.character, glyph.column, glyph.line)),
+                _ => None,
+            })
+            .collect()
+    }
+    type Line = ((i32, i32), (i32, i32), Color);
+    fn lines(frame: &Frame) -> Vec<Line> {
+        frame
+            .shapes
+            .iter()
+            .filter_map(|shape| match shape {
+                Shape::Line(from, to, color) => Some((*from, *to, *color)),
+                _ => None,
+            })
+            .collect()
+    }
+    #[test]
+    fn glyphs_are_placed_from_the_camera_line() {
+        let frame = frame(&cursor(vec!["ab", "c", "de", "f"]), 1, None);
+        assert_eq!(glyphs(&frame), vec![('c', 0, 0), ('d', 0, 1), ('e', 1, 1)]);
+        assert_eq!(frame.background, Theme::dark().colors.background);
+    }
+    #[test]
+    fn glyphs_take_the_colors_of_their_spans() {
+        let cursor = cursor(vec!["fn x"]);
+        let mut highlighter = Highlighter::new(get_grammar_for_path("a.rs"));
+        highlighter.update(&cursor.lines);
+        let theme = Theme::dark();
+        let view = View {
+            cursor: &cursor,
+            highlighter: &highlighter,
+            camera_line: 0,
+            lines: 1,
+            columns: 20,
+            width: 260,
+            status_line: None,
+            caret: Some(CaretLook::default()),
+            preedit: None,
+            stroke_width: 1,
+            scrollbar: None,
+            minimap: None,
+        };
+        let frame = layout_frame(&view, &Layout::default(), &theme);
+        assert_eq!(
+            frame.shapes[0],
+            Shape::Glyph(PlacedGlyph {
+                character: 'f',
+                column: 0,
+                line: 0,
+                color: theme.syntax.keyword,
+            })
+        );
+        match &frame.shapes[3] {
+            Shape::Glyph(glyph) => assert_eq!(glyph.color, theme.colors.text),
+            shape => panic!("expected a glyph, found {:?}", shape),
+        }
+    }
+    #[test]
+    fn caret_reaches_above_and_below_the_cell() {
+        let mut cursor = cursor(vec!["ab"]);
+        cursor.move_to(0, 1, false);
+        let frame = frame(&cursor, 0, None);
+        let cursor_color = Theme::dark().colors.cursor;
+        assert_eq!(lines(&frame), vec![((23, 2), (23, 24), cursor_color)]);
+    }
+    #[test]
+    fn caret_above_the_camera_is_left_out() {
+        let frame = frame(&cursor(vec!["a", "b"]), 1, None);
+        assert_eq!(lines(&frame), vec![]);
+    }
+    #[test]
+    fn block_caret_inverts_the_glyph_under_it() {
+        let mut cursor = cursor(vec!["ab"]);
+        cursor.move_to(0, 1, false);
+        let look = CaretLook {
+            style: CaretStyle::Block,
+            is_hollow: false,
+        };
+        let frame = frame_with_caret(&cursor, Some(look));
+        let colors = Theme::dark().colors;
+        assert_eq!(
+            frame.shapes[2..],
+            [
+                Shape::Rect(23, 5, 10, 16, colors.cursor),
+                Shape::Glyph(PlacedGlyph {
+                    character: 'b',
+                    column: 1,
+                    line: 0,
+                    color: colors.background,
+                }),
+            ]
+        );
+    }
+    #[test]
+    fn underline_and_hollow_carets() {
+        let mut cursor = cursor(vec!["ab"]);
+        cursor.move_to(0, 1, false);
+        let cursor_color = Theme::dark().colors.cursor;
+        let underline = CaretLook {
+            style: CaretStyle::Underline,
+            is_hollow: false,
+        };
+        assert_eq!(
+            lines(&frame_with_caret(&cursor, Some(underline))),
+            vec![((23, 23), (33, 23), cursor_color)]
+        );
+        let hollow = CaretLook {
+            style: CaretStyle::Block,
+            is_hollow: true,
+        };
+        assert_eq!(
+            lines(&frame_with_caret(&cursor, Some(hollow))),
+            vec![
+                ((23, 5), (33, 5), cursor_color),
+                ((33, 5), (33, 21), cursor_color),
+                ((33, 21), (23, 21), cursor_color),
+                ((23, 21), (23, 5), cursor_color),
+            ]
+        );
+        assert_eq!(lines(&frame_with_caret(&cursor, None)), vec![]);
+    }
+    #[test]
+    fn caret_look_follows_overwriting_focus_and_blinking() {
+        let caret = Caret::default();
+        let bar = Some(CaretLook::default());
+        assert_eq!(get_caret_look(&caret, false, true, true), bar);
+        assert_eq!(get_caret_look(&caret, false, true, false), None);
+        assert_eq!(
+            get_caret_look(&caret, true, true, true).map(|look| look.style),
+            Some(CaretStyle::Block)
+        );
+        let unfocused = get_caret_look(&caret, false, false, false).unwrap();
+        assert!(unfocused.is_hollow);
+        let caret = Caret {
+            block_when_overwriting: false,
+            ..Caret::default()
+        };
+        assert_eq!(get_caret_look(&caret, true, true, true), bar);
+    }
+    #[test]
+    fn preedit_is_underlined_at_the_caret() {
+        let mut cursor = cursor(vec!["ab", "c"]);
+        cursor.move_to(0, 1, false);
+        let highlighter = Highlighter::new(None);
+        let view = View {
+            cursor: &cursor,
+            highlighter: &highlighter,
+            camera_line: 0,
+            lines: 2,
+            columns: 20,
+            width: 260,
+            status_line: None,
+            caret: Some(CaretLook::default()),
+            preedit: Some(Preedit {
+                text: String::from("xy"),
+                cursor: 1,
+            }),
+            stroke_width: 1,
+            scrollbar: None,
+            minimap: None,
+        };
+        let layout = Layout::default();
+        let frame = layout_frame(&view, &layout, &Theme::dark());
+        assert_eq!(
+            glyphs(&frame),
+            vec![
+                ('a', 0, 0),
+                ('b', 3, 0),
+                ('x', 1, 0),
+                ('y', 2, 0),
+                ('c', 0, 1)
+            ]
+        );
+        let colors = Theme::dark().colors;
+        assert_eq!(
+            lines(&frame),
+            vec![
+                ((23, 23), (46, 23), colors.text),
+                ((36, 2), (36, 24), colors.cursor),
+            ]
+        );
+        assert_eq!(get_caret_cell(&view, &layout), (36, 5, 10, 21));
+    }
+    #[test]
+    fn selection_and_its_moving_end() {
+        let mut cursor = cursor(vec!["abc", "de"]);
+        cursor.move_to(0, 1, false);
+        cursor.move_to(1, 1, true);
+        let colors = Theme::dark().colors;
+        assert_eq!(
+            lines(&frame(&cursor, 0, None)),
+            vec![
+                ((23, 2), (23, 24), colors.cursor),
+                ((23, 13), (49, 13), colors.cursor_selection),
+                ((10, 34), (23, 34), colors.cursor_selection),
+                ((23, 23), (23, 45), colors.cursor_extender),
+            ]
+        );
+    }
+    #[test]
+    fn selection_segments() {
+        let mut cursor = cursor(vec!["abc", "de", "fgh"]);
+        cursor.move_to(0, 1, false);
+        assert_eq!(get_selection_segments(&cursor), vec![]);
+        cursor.move_to(0, 3, true);
+        assert_eq!(get_selection_segments(&cursor), vec![(0, 1, 3)]);
+        cursor.move_to(2, 1, true);
+        assert_eq!(
+            get_selection_segments(&cursor),
+            vec![(1, 0, 2), (0, 1, 3), (2, 0, 1)]
+        );
+    }
+    #[test]
+    fn status_line_below_the_text() {
+        let status_line = StatusLine {
+            left: String::from("N"),
+            right: String::from("dw"),
+        };
+        let frame = frame(&cursor(vec!["a"]), 0, Some(status_line));
+        let colors = Theme::dark().colors;
+        assert!(frame
+            .shapes
+            .contains(&Shape::Rect(0, 45, 260, 21, colors.status_bar)));
+        assert_eq!(
+            glyphs(&frame),
+            vec![('a', 0, 0), ('N', 0, 2), ('d', 17, 2), ('w', 18, 2)]
+        );
+    }
+    #[test]
+    fn frames_are_drawn_in_order() {
+        let colors = Theme::dark().colors;
+        let frame = Frame {
+            background: colors.background,
+            shapes: vec![
+                Shape::Rect(1, 2, 3, 4, colors.status_bar),
+                Shape::Glyph(PlacedGlyph {
+                    character: 'a',
+                    column: 0,
+                    line: 0,
+                    color: colors.text,
+                }),
+                Shape::Line((0, 0), (5, 5), colors.cursor),
+            ],
+            stroke_width: 1,
+            minimap: None,
+        };
+        let font = Font::from_str("a 0,0 1,1 0,1").unwrap();
+        let mut renderer = RecordingRenderer::new();
+        draw_frame(&mut renderer, &frame, &Layout::default(), &font);
+        assert_eq!(
+            renderer.calls,
+            vec![
+                DrawCall::Clear(colors.background),
+                DrawCall::Rect(1, 2, 3, 4, colors.status_bar),
+                DrawCall::Line((10, 5), (20, 21), colors.text),
+                DrawCall::Line((20, 21), (10, 21), colors.text),
+                DrawCall::Line((0, 0), (5, 5), colors.cursor),
+            ]
+        );
+    }
+    #[test]
+    fn wide_lines_are_drawn_shifted() {
+        let mut renderer = RecordingRenderer::new();
+        draw_wide_lines(&mut renderer, &[(0, 0), (4, 0)], 2);
+        let color = Color::RGB(0, 0, 0);
+        assert_eq!(
+            renderer.calls,
+            vec![
+                DrawCall::Line((0, 0), (4, 0), color),
+                DrawCall::Line((0, 1), (4, 1), color),
+                DrawCall::Line((1, 0), (5, 0), color),
+                DrawCall::Line((1, 1), (5, 1), color),
+            ]
+        );
+    }
+    #[test]
+    fn draw_lines_connects_the_points() {
+        let mut renderer = RecordingRenderer::new();
+        renderer.set_color(Color::RGB(1, 2, 3));
+        renderer.draw_lines(&[(0, 0), (1, 0), (1, 1)]);
+        renderer.draw_lines(&[(5, 5)]);
+        assert_eq!(
+            renderer.calls,
+            vec![
+                DrawCall::Line((0, 0), (1, 0), Color::RGB(1, 2, 3)),
+                DrawCall::Line((1, 0), (1, 1), Color::RGB(1, 2, 3)),
+            ]
+        );
+    }
+    /// Records which glyphs are drawn together.
+    struct RunRecorder {
+        runs: Vec<Vec<char>>,
+    }
+    impl Renderer for RunRecorder {
+        fn set_color(&mut self, _color: Color) {}
+        fn clear(&mut self) {}
+        fn draw_line(&mut self, _from: (i32, i32), _to: (i32, i32)) {}
+        fn fill_rect(&mut self, _left: i32, _top: i32, _width: u32, _height: u32) {}
+        fn draw_glyph_run(
+            &mut self,
+            _layout: &Layout,
+            _font: &Font,
+            glyphs: &[PlacedGlyph],
+            _stroke_width: u32,
+        ) {
+            self.runs
+                .push(glyphs.iter().map(|glyph| glyph.character).collect());
+        }
+    }
+    #[test]
+    fn glyphs_are_drawn_in_runs_per_line() {
+        let glyph = |character, column, line| {
+            Shape::Glyph(PlacedGlyph {
+                character,
+                column,
+                line,
+                color: Color::RGB(0, 0, 0),
+            })
+        };
+        let frame = Frame {
+            background: Color::RGB(0, 0, 0),
+            shapes: vec![
+                glyph('a', 0, 0),
+                glyph('b', 1, 0),
+                glyph('c', 0, 1),
+                Shape::Rect(0, 0, 1, 1, Color::RGB(0, 0, 0)),
+                glyph('d', 1, 1),
+            ],
+            stroke_width: 1,
+            minimap: None,
+        };
+        let mut renderer = RunRecorder { runs: vec![] };
+        draw_frame(&mut renderer, &frame, &Layout::default(), &Font::builtin());
+        assert_eq!(renderer.runs, vec![vec!['a', 'b'], vec!['c'], vec!['d']]);
+    }
+    #[test]
+    fn frame_time_overlay_in_the_top_right_corner() {
+        assert_eq!(format_frame_time(Duration::from_micros(4250)), "4.2 ms");
+        assert_eq!(format_frame_time(Duration::from_millis(16)), "16.0 ms");
+        let cursor = cursor(vec!["a"]);
+        let highlighter = Highlighter::new(None);
+        let view = View {
+            cursor: &cursor,
+            highlighter: &highlighter,
+            camera_line: 0,
+            lines: 2,
+            columns: 20,
+            width: 260,
+            status_line: None,
+            caret: None,
+            preedit: None,
+            stroke_width: 1,
+            scrollbar: None,
+            minimap: None,
+        };
+        let layout = Layout::default();
+        let theme = Theme::dark();
+        let mut frame = layout_frame(&view, &layout, &theme);
+        layout_overlay(&mut frame, &view, &layout, &theme, "1.5 ms");
+        assert_eq!(
+            glyphs(&frame),
+            vec![
+                ('a', 0, 0),
+                ('1', 13, 0),
+                ('.', 14, 0),
+                ('5', 15, 0),
+                (' ', 16, 0),
+                ('m', 17, 0),
+                ('s', 18, 0)
+            ]
+        );
+        let overlay = frame
+            .shapes
+            .iter()
+            .position(|shape| match shape {
+                Shape::Rect(_, _, _, _, color) => *color == theme.colors.status_bar,
+                _ => false,
+            })
+            .unwrap();
+        assert_eq!(overlay, 1, "the background goes right before the text");
+    }
+    #[test]
+    fn selection_matches_cover_every_occurrence_of_one_line_selections() {
+        let mut cursor = cursor(vec!["ab x ab", "abab", "a b"]);
+        cursor.move_to(0, 2, true);
+        assert_eq!(
+            get_selection_matches(&cursor),
+            vec![(0, 0, 2), (0, 5, 7), (1, 0, 2), (1, 2, 4)]
+        );
+        cursor.move_to(1, 1, true);
+        assert_eq!(get_selection_matches(&cursor), vec![]);
+        cursor.move_to(2, 1, false);
+        cursor.move_to(2, 2, true);
+        assert_eq!(get_selection_matches(&cursor), vec![], "only whitespace");
+    }
+    #[test]
+    fn scrollbar_track_and_thumb_come_last() {
+        let cursor = cursor(vec!["a"; 31]);
+        let highlighter = Highlighter::new(None);
+        let view = View {
+            cursor: &cursor,
+            highlighter: &highlighter,
+            camera_line: 15,
+            lines: 10,
+            columns: 20,
+            width: 260,
+            status_line: None,
+            caret: None,
+            preedit: None,
+            stroke_width: 1,
+            scrollbar: Some(Scrollbar::new(250, 10, 200, 15, 10.0, 31)),
+            minimap: None,
+        };
+        let colors = Theme::dark().colors;
+        let frame = layout_frame(&view, &Layout::default(), &Theme::dark());
+        let count = frame.shapes.len();
+        assert_eq!(
+            frame.shapes[count - 2..],
+            [
+                Shape::Rect(250, 0, 10, 200, colors.status_bar),
+                Shape::Rect(250, 75, 10, 50, colors.gutter),
+            ]
+        );
+        assert_eq!(frame.minimap, None);
+    }
+    #[test]
+    fn minimap_marks_the_view_the_selection_and_its_matches() {
+        let mut cursor = cursor(vec!["ab", "x", "ab"]);
+        cursor.move_to(0, 2, true);
+        let highlighter = Highlighter::new(None);
+        let layout = get_minimap_layout(100);
+        let view = View {
+            cursor: &cursor,
+            highlighter: &highlighter,
+            camera_line: 1,
+            lines: 2,
+            columns: 20,
+            width: 260,
+            status_line: None,
+            caret: None,
+            preedit: None,
+            stroke_width: 1,
+            scrollbar: None,
+            minimap: Some(Minimap::new(170, 40, &layout, 1, 3)),
+        };
+        let colors = Theme::dark().colors;
+        let minimap = layout_frame(&view, &Layout::default(), &Theme::dark())
+            .minimap
+            .unwrap();
+        assert_eq!(minimap.layout.x_offset, 170);
+        assert_eq!(
+            minimap.shapes[..4],
+            [
+                Shape::Rect(170, 0, 80, 40, colors.background),
+                Shape::Rect(170, 4, 80, 8, colors.status_bar),
+                Shape::Rect(170, 0, 4, 4, colors.cursor_selection),
+                Shape::Rect(170, 8, 4, 4, colors.cursor_extender),
+            ]
+        );
+        let characters = minimap
+            .shapes
+            .iter()
+            .filter_map(|shape| match shape {
+                Shape::Glyph(glyph) => Some((glyph.character, glyph.column, glyph.line)),
+                _ => None,
+            })
+            .collect::<Vec<(char, i32, i32)>>();
+        assert_eq!(
+            characters,
+            vec![
+                ('a', 0, 0),
+                ('b', 1, 0),
+                ('x', 0, 1),
+                ('a', 0, 2),
+                ('b', 1, 2)
+            ]
+        );
+        assert_eq!(
+            minimap.shapes.last(),
+            Some(&Shape::Line((170, 2), (249, 2), colors.cursor))
+        );
+    }
+    #[test]
+    fn long_lines_are_cut_off_in_the_minimap() {
+        let line = "x".repeat(60);
+        let cursor = cursor(vec![&line]);
+        let highlighter = Highlighter::new(None);
+        let view = View {
+            cursor: &cursor,
+            highlighter: &highlighter,
+            camera_line: 0,
+            lines: 2,
+            columns: 20,
+            width: 260,
+            status_line: None,
+            caret: None,
+            preedit: None,
+            stroke_width: 1,
+            scrollbar: None,
+            minimap: Some(Minimap::new(170, 40, &get_minimap_layout(100), 0, 1)),
+        };
+        let minimap = layout_frame(&view, &Layout::default(), &Theme::dark())
+            .minimap
+            .unwrap();
+        let glyphs = minimap
+            .shapes
+            .iter()
+            .filter(|shape| matches!(shape, Shape::Glyph(_)));
+        assert_eq!(glyphs.count(), 40);
+    }
+}
@@ -0,0 +1,146 @@
+pub mod scrollbar {
+    use super::super::config::config::Layout;
+
+    /// How many columns of each line the minimap shows.
+    pub const MINIMAP_COLUMNS: i32 = 40;
+
+    /// As wide as a character, so it grows with the zoom.
+    pub fn get_scrollbar_width(layout: &Layout) -> u32 {
+        layout.character_width.max(4) as u32
+    }
+
+    /// The tiny cells of the minimap, which follow the display scale but
+    /// not the zoom.
+    pub fn get_minimap_layout(display_zoom: u32) -> Layout {
+        let mut layout = Layout::from_character_size(2, 3).zoomed(display_zoom);
+        layout.line_gap = layout.line_gap.max(1);
+        layout.x_offset = 0;
+        layout.y_offset = 0;
+        layout
+    }
+
+    pub fn get_minimap_width(minimap_layout: &Layout) -> u32 {
+        (MINIMAP_COLUMNS * (minimap_layout.character_width + minimap_layout.character_gap)) as u32
+    }
+
+    /// A track along the right edge, `height` pixels tall, with a thumb as
+    /// much of it as the visible lines are of everything the camera can
+    /// show. The camera goes as far as the last line.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Scrollbar {
+        pub left: i32,
+        pub width: u32,
+        pub height: u32,
+        pub thumb_top: i32,
+        pub thumb_height: u32,
+        last_camera_line: i32,
+    }
+
+    impl Scrollbar {
+        pub fn new(
+            left: i32,
+            width: u32,
+            height: u32,
+            camera_line: i32,
+            visible_lines: f32,
+            line_count: usize,
+        ) -> Scrollbar {
+            let last_camera_line = (line_count as i32 - 1).max(0);
+            let visible_lines = visible_lines.max(1.0);
+            let share = visible_lines / (last_camera_line as f32 + visible_lines);
+            let thumb_height = ((height as f32 * share).round() as u32)
+                .max(width)
+                .min(height);
+            let travel = height - thumb_height;
+            let thumb_top = if last_camera_line == 0 {
+                0
+            } else {
+                let camera_line = camera_line.max(0).min(last_camera_line);
+                (travel as f32 * camera_line as f32 / last_camera_line as f32).round() as i32
+            };
+            Scrollbar {
+                left,
+                width,
+                height,
+                thumb_top,
+                thumb_height,
+                last_camera_line,
+            }
+        }
+        pub fn contains(&self, x: i32, y: i32) -> bool {
+            x >= self.left && x < self.left + self.width as i32 && y >= 0 && y < self.height as i32
+        }
+        pub fn is_on_thumb(&self, y: i32) -> bool {
+            y >= self.thumb_top && y < self.thumb_top + self.thumb_height as i32
+        }
+        /// The camera line that moves the top of the thumb to `thumb_top`.
+        pub fn get_camera_line(&self, thumb_top: i32) -> i32 {
+            let travel = (self.height - self.thumb_height) as i32;
+            if travel == 0 {
+                return 0;
+            }
+            let thumb_top = thumb_top.max(0).min(travel);
+            (thumb_top as f32 * self.last_camera_line as f32 / travel as f32).round() as i32
+        }
+    }
+
+    /// The buffer in miniature in a column at `left`, as many lines of it
+    /// from `first_line` on as fit. Longer buffers scroll along with the
+    /// camera, reaching their end together.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Minimap {
+        pub left: i32,
+        pub width: u32,
+        pub height: u32,
+        pub first_line: usize,
+        pub lines: usize,
+        /// The cells of the minimap, offset to its left edge.
+        pub layout: Layout,
+    }
+
+    impl Minimap {
+        pub fn new(
+            left: i32,
+            height: u32,
+            layout: &Layout,
+            camera_line: i32,
+            line_count: usize,
+        ) -> Minimap {
+            let line_height = (layout.character_height + layout.line_gap).max(1) as usize;
+            let capacity = height as usize / line_height;
+            let last_camera_line = line_count.max(1) - 1;
+            let first_line = if line_count <= capacity || last_camera_line == 0 {
+                0
+            } else {
+                let camera_line = (camera_line.max(0) as usize).min(last_camera_line);
+                ((line_count - capacity) as f32 * camera_line as f32 / last_camera_line as f32)
+                    .round() as usize
+            };
+            let mut layout = layout.clone();
+            layout.x_offset = left;
+            Minimap {
+                left,
+                width: get_minimap_width(&layout),
+                height,
+                first_line,
+                lines: capacity.min(line_count - first_line),
+                layout,
+            }
+        }
+        pub fn get_line_height(&self) -> i32 {
+            self.layout.character_height + self.layout.line_gap
+        }
+        /// The top edge of the buffer line `line`, which may be out of view.
+        pub fn get_line_y(&self, line: usize) -> i32 {
+            (line as i32 - self.first_line as i32) * self.get_line_height()
+        }
+        pub fn contains(&self, x: i32, y: i32) -> bool {
+            x >= self.left && x < self.left + self.width as i32 && y >= 0 && y < self.height as i32
+        }
+        /// The buffer line at `y`, the last one below the end.
+        pub fn get_line_at(&self, y: i32) -> usize {
+            let line = self.first_line + (y.max(0) / self.get_line_height()) as usize;
+            line.min((self.first_line + self.lines).max(1) - 1)
+        }
+    }
+}
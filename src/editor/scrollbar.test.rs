@@ -0,0 +1,121 @@
+#[path = "scrollbar.rs"]
+mod scrollbar;
+
+#[path = "config.rs"]
+mod config;
+
+#[path = "commands.rs"]
+mod commands;
+
+#[path = "cursor.rs"]
+mod cursor;
+
+#[path = "kill_ring.rs"]
+mod kill_ring;
+
+#[path = "keymap.rs"]
+mod keymap;
+
+#[path = "font.rs"]
+mod font;
+
+#[path = "highlighting.rs"]
+mod highlighting;
+
+#[path = "theme.rs"]
+mod theme;
+
+#[cfg(test)]
+mod tests {
+    use super::config::config::Layout;
+    use super::scrollbar::scrollbar::{
+        get_minimap_layout, get_minimap_width, get_scrollbar_width, Minimap, Scrollbar,
+    };
+    #[test]
+    fn scrollbar_is_as_wide_as_a_character() {
+        assert_eq!(get_scrollbar_width(&Layout::default()), 10);
+        assert_eq!(get_scrollbar_width(&Layout::from_character_size(1, 2)), 4);
+    }
+    #[test]
+    fn thumb_shows_the_visible_share_of_the_buffer() {
+        let scrollbar = Scrollbar::new(100, 10, 200, 15, 10.0, 31);
+        assert_eq!(scrollbar.thumb_height, 50);
+        assert_eq!(scrollbar.thumb_top, 75);
+        let at_the_end = Scrollbar::new(100, 10, 200, 30, 10.0, 31);
+        assert_eq!(at_the_end.thumb_top, 150);
+    }
+    #[test]
+    fn thumb_fills_the_track_of_short_buffers() {
+        let scrollbar = Scrollbar::new(100, 10, 200, 0, 10.0, 1);
+        assert_eq!((scrollbar.thumb_top, scrollbar.thumb_height), (0, 200));
+        assert_eq!(scrollbar.get_camera_line(50), 0);
+    }
+    #[test]
+    fn thumb_stays_large_enough_to_grab() {
+        let scrollbar = Scrollbar::new(100, 10, 200, 0, 10.0, 100_000);
+        assert_eq!(scrollbar.thumb_height, 10);
+    }
+    #[test]
+    fn dragging_the_thumb_moves_the_camera() {
+        let scrollbar = Scrollbar::new(100, 10, 200, 0, 10.0, 31);
+        assert_eq!(scrollbar.get_camera_line(75), 15);
+        assert_eq!(scrollbar.get_camera_line(-20), 0);
+        assert_eq!(scrollbar.get_camera_line(1000), 30);
+        for camera_line in 0..31 {
+            let moved = Scrollbar::new(100, 10, 200, camera_line, 10.0, 31);
+            assert_eq!(moved.get_camera_line(moved.thumb_top), camera_line);
+        }
+    }
+    #[test]
+    fn clicks_hit_the_track_and_the_thumb() {
+        let scrollbar = Scrollbar::new(100, 10, 200, 15, 10.0, 31);
+        assert!(scrollbar.contains(100, 0));
+        assert!(scrollbar.contains(109, 199));
+        assert!(!scrollbar.contains(99, 10));
+        assert!(!scrollbar.contains(110, 10));
+        assert!(!scrollbar.contains(105, 200));
+        assert!(scrollbar.is_on_thumb(75));
+        assert!(scrollbar.is_on_thumb(124));
+        assert!(!scrollbar.is_on_thumb(125));
+    }
+    #[test]
+    fn minimap_cells_follow_the_display_scale() {
+        let layout = get_minimap_layout(100);
+        assert_eq!((layout.character_width, layout.character_height), (2, 3));
+        assert_eq!(layout.line_gap, 1);
+        assert_eq!(get_minimap_width(&layout), 80);
+        let doubled = get_minimap_layout(200);
+        assert_eq!((doubled.character_width, doubled.character_height), (4, 6));
+        assert_eq!(get_minimap_width(&doubled), 160);
+    }
+    #[test]
+    fn short_buffers_fit_the_minimap() {
+        let minimap = Minimap::new(50, 160, &get_minimap_layout(100), 10, 20);
+        assert_eq!((minimap.first_line, minimap.lines), (0, 20));
+        assert_eq!(minimap.layout.x_offset, 50);
+        assert_eq!(minimap.width, 80);
+        assert_eq!(minimap.get_line_y(3), 12);
+    }
+    #[test]
+    fn long_buffers_scroll_the_minimap_with_the_camera() {
+        let layout = get_minimap_layout(100);
+        let top = Minimap::new(50, 160, &layout, 0, 140);
+        assert_eq!((top.first_line, top.lines), (0, 40));
+        let bottom = Minimap::new(50, 160, &layout, 139, 140);
+        assert_eq!((bottom.first_line, bottom.lines), (100, 40));
+        assert_eq!(bottom.get_line_y(100), 0);
+        assert_eq!(bottom.get_line_y(99), -4);
+    }
+    #[test]
+    fn clicks_on_the_minimap_find_their_line() {
+        let layout = get_minimap_layout(100);
+        let minimap = Minimap::new(50, 160, &layout, 139, 140);
+        assert!(minimap.contains(50, 0));
+        assert!(!minimap.contains(130, 0));
+        assert_eq!(minimap.get_line_at(0), 100);
+        assert_eq!(minimap.get_line_at(21), 105);
+        assert_eq!(minimap.get_line_at(1000), 139);
+        let short = Minimap::new(50, 160, &layout, 0, 3);
+        assert_eq!(short.get_line_at(100), 2);
+    }
+}
@@ -0,0 +1,84 @@
+pub mod snapshot {
+    use super::super::image_rendering::image_rendering::save_png;
+    use image::{Rgba, RgbaImage};
+    use std::env;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    /// The checked-in reference images.
+    pub fn snapshots_directory() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("src/editor/snapshots")
+    }
+
+    /// Where the rendered and diff images of failed comparisons go.
+    pub fn failures_directory() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("target/snapshots")
+    }
+
+    /// The expected image dimmed, with every pixel that differs in red, or
+    /// `None` if the images are the same. Pixels only one of the images has
+    /// differ.
+    pub fn get_diff(expected: &RgbaImage, actual: &RgbaImage) -> Option<RgbaImage> {
+        let width = expected.width().max(actual.width());
+        let height = expected.height().max(actual.height());
+        let get = |image: &RgbaImage, x: u32, y: u32| {
+            if x < image.width() && y < image.height() {
+                Some(*image.get_pixel(x, y))
+            } else {
+                None
+            }
+        };
+        let mut is_different = false;
+        let diff = RgbaImage::from_fn(width, height, |x, y| {
+            match (get(expected, x, y), get(actual, x, y)) {
+                (Some(expected), Some(actual)) if expected == actual => {
+                    let [r, g, b, _] = expected.0;
+                    let gray = ((r as u32 + g as u32 + b as u32) / 3 / 4) as u8;
+                    Rgba([gray, gray, gray, 255])
+                }
+                _ => {
+                    is_different = true;
+                    Rgba([255, 0, 0, 255])
+                }
+            }
+        });
+        if is_different {
+            Some(diff)
+        } else {
+            None
+        }
+    }
+
+    /// Compares `actual` with the reference image `name`.png. With
+    /// `UPDATE_SNAPSHOTS` set the reference image is written instead.
+    pub fn assert_snapshot(name: &str, actual: &RgbaImage) {
+        let reference_path = snapshots_directory().join(format!("{}.png", name));
+        if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            fs::create_dir_all(snapshots_directory()).unwrap();
+            save_png(actual, &reference_path).unwrap();
+            return;
+        }
+        let expected = match image::open(&reference_path) {
+            Ok(expected) => expected.to_rgba(),
+            Err(error) => panic!(
+                "could not read {}: {}, run the tests with UPDATE_SNAPSHOTS=1 to create it",
+                reference_path.display(),
+                error
+            ),
+        };
+        if let Some(diff) = get_diff(&expected, actual) {
+            let failures = failures_directory();
+            fs::create_dir_all(&failures).unwrap();
+            let actual_path = failures.join(format!("{}.png", name));
+            let diff_path = failures.join(format!("{}.diff.png", name));
+            save_png(actual, &actual_path).unwrap();
+            save_png(&diff, &diff_path).unwrap();
+            panic!(
+                "{} does not match {}, the differences are red in {}",
+                actual_path.display(),
+                reference_path.display(),
+                diff_path.display()
+            );
+        }
+    }
+}
@@ -0,0 +1,211 @@
+#[path = "snapshot.rs"]
+mod snapshot;
+
+#[path = "image_rendering.rs"]
+mod image_rendering;
+
+#[path = "rendering.rs"]
+mod rendering;
+
+#[path = "scrollbar.rs"]
+mod scrollbar;
+
+#[path = "rasterizer.rs"]
+mod rasterizer;
+
+#[path = "config.rs"]
+mod config;
+
+#[path = "commands.rs"]
+mod commands;
+
+#[path = "cursor.rs"]
+mod cursor;
+
+#[path = "kill_ring.rs"]
+mod kill_ring;
+
+#[path = "keymap.rs"]
+mod keymap;
+
+#[path = "font.rs"]
+mod font;
+
+#[path = "highlighting.rs"]
+mod highlighting;
+
+#[path = "theme.rs"]
+mod theme;
+
+#[path = "text_rendering.rs"]
+mod text_rendering;
+
+#[cfg(test)]
+mod tests {
+    use super::config::config::{get_character_x, get_character_y, Layout, StrokeStyle};
+    use super::cursor::cursor::Cursor;
+    use super::font::font::Font;
+    use super::highlighting::highlighting::{get_grammar_for_path, Highlighter};
+    use super::image_rendering::image_rendering::{get_image_view, render, render_view};
+    use super::scrollbar::scrollbar::{
+        get_minimap_layout, get_minimap_width, get_scrollbar_width, Minimap, Scrollbar,
+    };
+    use super::snapshot::snapshot::{assert_snapshot, get_diff};
+    use super::theme::theme::Theme;
+    use image::{Rgba, RgbaImage};
+    fn cursor(lines: Vec<&str>) -> Cursor {
+        Cursor::new(lines.into_iter().map(String::from).collect())
+    }
+    fn rendered(
+        cursor: &Cursor,
+        path: &str,
+        camera_line: i32,
+        columns: i32,
+        lines: i32,
+    ) -> RgbaImage {
+        let layout = Layout::default();
+        let mut highlighter = Highlighter::new(get_grammar_for_path(path));
+        highlighter.update(&cursor.lines);
+        let mut image = RgbaImage::new(
+            get_character_x(&layout, columns) as u32,
+            get_character_y(&layout, lines) as u32,
+        );
+        render(
+            &mut image,
+            cursor,
+            &highlighter,
+            camera_line,
+            &layout,
+            &Font::builtin(),
+            &Theme::dark(),
+        );
+        image
+    }
+    /// The characters from `from` to `to` in lines of up to 32.
+    fn rows(from: char, to: char) -> Vec<String> {
+        let characters = (from..=to).collect::<Vec<char>>();
+        characters
+            .chunks(32)
+            .map(|row| row.iter().collect())
+            .collect()
+    }
+    #[test]
+    fn glyphs() {
+        let mut lines = rows('!', '~');
+        lines.extend(rows('¡', 'ÿ'));
+        lines.extend(rows('Α', 'Ω'));
+        lines.extend(rows('α', 'ω'));
+        lines.extend(rows('А', 'я'));
+        lines.push(String::from("─│┌┐└┘├┤┬┴┼═║╔╗╚╝ ░▒▓█ ▀▄ \u{1}\u{7f} 中 😀"));
+        let cursor = Cursor::new(lines);
+        assert_snapshot("glyphs", &rendered(&cursor, "", 0, 33, 11));
+    }
+    #[test]
+    fn selection_across_lines() {
+        let mut cursor = cursor(vec!["first line", "second", "", "fourth line", "fifth"]);
+        cursor.move_to(0, 6, false);
+        cursor.move_to(3, 6, true);
+        assert_snapshot("selection_across_lines", &rendered(&cursor, "", 0, 12, 5));
+    }
+    #[test]
+    fn selection_backwards() {
+        let mut cursor = cursor(vec!["one two", "three four"]);
+        cursor.move_to(1, 5, false);
+        cursor.move_to(0, 4, true);
+        assert_snapshot("selection_backwards", &rendered(&cursor, "", 0, 12, 2));
+    }
+    #[test]
+    fn scrolled_camera() {
+        let lines = (0..20)
+            .map(|line| format!("line {}", line))
+            .collect::<Vec<String>>();
+        let mut cursor = Cursor::new(lines);
+        cursor.move_to(8, 2, false);
+        cursor.move_to(9, 4, true);
+        assert_snapshot("scrolled_camera", &rendered(&cursor, "", 6, 10, 5));
+    }
+    #[test]
+    fn caret_at_end_of_line() {
+        let mut cursor = cursor(vec!["abc", "defgh"]);
+        cursor.move_to(1, 5, false);
+        assert_snapshot("caret_at_end_of_line", &rendered(&cursor, "", 0, 8, 2));
+    }
+    #[test]
+    fn syntax_colors() {
+        let cursor = cursor(vec![
+            "// comment",
+            "fn main() {",
+            "    let x: u8 = 42;",
+            "    print(\"text\");",
+            "}",
+        ]);
+        assert_snapshot("syntax_colors", &rendered(&cursor, "a.rs", 0, 20, 5));
+    }
+    #[test]
+    fn long_lines_stop_at_the_scrollbar_and_minimap() {
+        let layout = Layout::default();
+        let line = "let line = \"longer than the twenty columns in view\";";
+        let mut cursor = Cursor::new(vec![String::from(line); 30]);
+        cursor.move_to(1, 4, false);
+        cursor.move_to(1, 8, true);
+        let mut highlighter = Highlighter::new(get_grammar_for_path("a.rs"));
+        highlighter.update(&cursor.lines);
+        let minimap_layout = get_minimap_layout(100);
+        let scrollbar_width = get_scrollbar_width(&layout);
+        // Less than a column is left over next to the minimap.
+        let text_width = get_character_x(&layout, 20) as u32 + 8;
+        let height = get_character_y(&layout, 6) as u32;
+        let minimap_width = get_minimap_width(&minimap_layout);
+        let mut image = RgbaImage::new(text_width + minimap_width + scrollbar_width, height);
+        let mut view = get_image_view(&image, &cursor, &highlighter, 0, &layout);
+        view.columns = 20;
+        view.minimap = Some(Minimap::new(
+            text_width as i32,
+            height,
+            &minimap_layout,
+            0,
+            cursor.lines.len(),
+        ));
+        view.scrollbar = Some(Scrollbar::new(
+            (text_width + minimap_width) as i32,
+            scrollbar_width,
+            height,
+            0,
+            view.lines as f32,
+            cursor.lines.len(),
+        ));
+        render_view(
+            &mut image,
+            &view,
+            &layout,
+            &Font::builtin(),
+            &Theme::dark(),
+            StrokeStyle::Crisp,
+        );
+        assert_snapshot("scrollbar_and_minimap", &image);
+    }
+    #[test]
+    fn same_images_have_no_diff() {
+        let image = RgbaImage::from_pixel(3, 2, Rgba([1, 2, 3, 255]));
+        assert!(get_diff(&image, &image.clone()).is_none());
+    }
+    #[test]
+    fn diff_marks_different_pixels() {
+        let expected = RgbaImage::from_pixel(3, 2, Rgba([200, 200, 200, 255]));
+        let mut actual = expected.clone();
+        actual.put_pixel(1, 1, Rgba([0, 0, 0, 255]));
+        let diff = get_diff(&expected, &actual).unwrap();
+        assert_eq!(*diff.get_pixel(1, 1), Rgba([255, 0, 0, 255]));
+        assert_eq!(*diff.get_pixel(0, 0), Rgba([50, 50, 50, 255]));
+    }
+    #[test]
+    fn diff_covers_both_sizes() {
+        let expected = RgbaImage::new(2, 3);
+        let actual = RgbaImage::new(4, 1);
+        let diff = get_diff(&expected, &actual).unwrap();
+        assert_eq!(diff.dimensions(), (4, 3));
+        assert_eq!(*diff.get_pixel(0, 0), Rgba([0, 0, 0, 255]));
+        assert_eq!(*diff.get_pixel(3, 0), Rgba([255, 0, 0, 255]));
+        assert_eq!(*diff.get_pixel(0, 2), Rgba([255, 0, 0, 255]));
+    }
+}
@@ -0,0 +1,136 @@
+pub mod svg {
+    use super::super::config::config::{get_character_x, get_character_y, Export, Layout};
+    use super::super::cursor::cursor::Cursor;
+    use super::super::font::font::Font;
+    use super::super::highlighting::highlighting::{Highlighter, TokenKind};
+    use super::super::rendering::rendering::get_selection_segments;
+    use super::super::text_rendering::text_rendering::get_character_strokes;
+    use super::super::theme::theme::Theme;
+    use sdl2::pixels::Color;
+
+    fn format_color(color: Color) -> String {
+        if color.a == 255 {
+            format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+        } else {
+            format!(
+                "#{:02x}{:02x}{:02x}{:02x}",
+                color.r, color.g, color.b, color.a
+            )
+        }
+    }
+
+    /// The line, first and last column of what is exported: the selection,
+    /// or every line without one.
+    pub fn get_export_segments(cursor: &Cursor) -> Vec<(usize, usize, usize)> {
+        let mut segments = get_selection_segments(cursor);
+        if segments.is_empty() {
+            segments = cursor
+                .lines
+                .iter()
+                .enumerate()
+                .map(|(line, text)| (line, 0, text.chars().count()))
+                .collect();
+        }
+        segments.sort();
+        segments
+    }
+
+    fn polyline(points: &[(i32, i32)], color: Color) -> String {
+        let points = points
+            .iter()
+            .map(|(x, y)| format!("{},{}", x, y))
+            .collect::<Vec<String>>()
+            .join(" ");
+        format!(
+            "<polyline points=\"{}\" stroke=\"{}\"/>\n",
+            points,
+            format_color(color)
+        )
+    }
+
+    /// One polyline per stroke of each glyph, placed like the editor window
+    /// places them with the selection moved to the top left.
+    pub fn to_svg(
+        cursor: &Cursor,
+        highlighter: &Highlighter,
+        layout: &Layout,
+        font: &Font,
+        theme: &Theme,
+        export: &Export,
+    ) -> String {
+        let segments = get_export_segments(cursor);
+        let first_line = segments.first().map_or(0, |(line, _, _)| *line);
+        let left_column = segments.iter().map(|(_, from, _)| *from).min().unwrap_or(0);
+        let gutter_columns = if export.line_numbers {
+            let last_line = segments.last().map_or(0, |(line, _, _)| *line);
+            (last_line + 1).to_string().len() + 1
+        } else {
+            0
+        };
+        let columns = segments
+            .iter()
+            .map(|(_, _, to)| to - left_column + gutter_columns)
+            .max()
+            .unwrap_or(0);
+
+        let mut glyphs = String::new();
+        let mut draw_text = |text: &str, column: usize, line: i32, color: Color| {
+            for (index, character) in text.chars().enumerate() {
+                let left = get_character_x(layout, (column + index) as i32);
+                let top = get_character_y(layout, line);
+                let strokes = get_character_strokes(
+                    font,
+                    &character,
+                    layout.character_width,
+                    layout.character_height,
+                );
+                for stroke in strokes {
+                    let points = stroke
+                        .iter()
+                        .map(|(x, y)| (left + x, top + y))
+                        .collect::<Vec<(i32, i32)>>();
+                    glyphs.push_str(&polyline(&points, color));
+                }
+            }
+        };
+        for (line, from, to) in segments.iter() {
+            let row = (line - first_line) as i32;
+            if export.line_numbers {
+                let number = (line + 1).to_string();
+                let column = gutter_columns - 1 - number.len();
+                draw_text(&number, column, row, theme.colors.gutter);
+            }
+            let text = &cursor.lines[*line];
+            let spans = highlighter.spans(*line);
+            for (column, character) in text.chars().enumerate().take(*to).skip(*from) {
+                let kind = spans
+                    .iter()
+                    .find(|span| span.start <= column && column < span.end)
+                    .map_or(TokenKind::Plain, |span| span.kind);
+                let color = if export.syntax_colors {
+                    theme.get_token_color(kind)
+                } else {
+                    theme.colors.text
+                };
+                let text = character.to_string();
+                draw_text(&text, column - left_column + gutter_columns, row, color);
+            }
+        }
+
+        let width =
+            get_character_x(layout, columns as i32) - layout.character_gap + layout.x_offset;
+        let height =
+            get_character_y(layout, segments.len() as i32) - layout.line_gap + layout.y_offset;
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n\
+             <rect width=\"100%\" height=\"100%\" fill=\"{background}\"/>\n\
+             <g fill=\"none\" stroke-linecap=\"round\" stroke-linejoin=\"round\">\n\
+             {glyphs}</g>\n\
+             </svg>\n",
+            width = width,
+            height = height,
+            background = format_color(theme.colors.background),
+            glyphs = glyphs
+        )
+    }
+}
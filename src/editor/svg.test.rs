@@ -0,0 +1,175 @@
+#[path = "svg.rs"]
+mod svg;
+
+#[path = "image_rendering.rs"]
+mod image_rendering;
+
+#[path = "rendering.rs"]
+mod rendering;
+
+#[path = "scrollbar.rs"]
+mod scrollbar;
+
+#[path = "rasterizer.rs"]
+mod rasterizer;
+
+#[path = "config.rs"]
+mod config;
+
+#[path = "commands.rs"]
+mod commands;
+
+#[path = "cursor.rs"]
+mod cursor;
+
+#[path = "kill_ring.rs"]
+mod kill_ring;
+
+#[path = "keymap.rs"]
+mod keymap;
+
+#[path = "font.rs"]
+mod font;
+
+#[path = "highlighting.rs"]
+mod highlighting;
+
+#[path = "theme.rs"]
+mod theme;
+
+#[path = "text_rendering.rs"]
+mod text_rendering;
+
+#[cfg(test)]
+mod tests {
+    use super::config::config::{Export, Layout};
+    use super::cursor::cursor::Cursor;
+    use super::font::font::Font;
+    use super::highlighting::highlighting::{get_grammar_for_path, Highlighter};
+    use super::svg::svg::{get_export_segments, to_svg};
+    use super::theme::theme::Theme;
+    use std::str::FromStr;
+    fn cursor(lines: Vec<&str>) -> Cursor {
+        Cursor::new(lines.into_iter().map(String::from).collect())
+    }
+    fn font() -> Font {
+        Font::from_str("a 0,0 1,1\nb 0,0 1,0 | 0,1 1,1").unwrap()
+    }
+    fn export(cursor: &Cursor, path: &str, export: &Export) -> String {
+        let mut highlighter = Highlighter::new(get_grammar_for_path(path));
+        highlighter.update(&cursor.lines);
+        let layout = Layout::from_character_size(10, 20);
+        to_svg(
+            cursor,
+            &highlighter,
+            &layout,
+            &font(),
+            &Theme::dark(),
+            export,
+        )
+    }
+    fn polylines(svg: &str) -> Vec<&str> {
+        svg.lines()
+            .filter(|line| line.starts_with("<polyline"))
+            .collect()
+    }
+    fn hex(color: sdl2::pixels::Color) -> String {
+        format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+    }
+    #[test]
+    fn one_polyline_per_stroke() {
+        let svg = export(&cursor(vec!["ab", "a"]), "", &Export::default());
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(
+            polylines(&svg),
+            vec![
+                "<polyline points=\"10,5 20,25\" stroke=\"#c8c8c8\"/>",
+                "<polyline points=\"23,5 33,5\" stroke=\"#c8c8c8\"/>",
+                "<polyline points=\"23,25 33,25\" stroke=\"#c8c8c8\"/>",
+                "<polyline points=\"10,31 20,51\" stroke=\"#c8c8c8\"/>",
+            ]
+        );
+    }
+    #[test]
+    fn size_fits_the_text() {
+        let svg = export(&cursor(vec!["ab", "a"]), "", &Export::default());
+        assert!(svg.contains("width=\"43\" height=\"56\" viewBox=\"0 0 43 56\""));
+        let background = hex(Theme::dark().colors.background);
+        assert!(svg.contains(&format!("fill=\"{}\"", background)));
+    }
+    #[test]
+    fn selection_is_exported_from_the_top_left() {
+        let mut cursor = cursor(vec!["bba", "ab", "aaa"]);
+        cursor.move_to(0, 2, false);
+        cursor.move_to(1, 1, true);
+        assert_eq!(get_export_segments(&cursor), vec![(0, 2, 3), (1, 0, 1)]);
+        let svg = export(&cursor, "", &Export::default());
+        assert_eq!(
+            polylines(&svg),
+            vec![
+                "<polyline points=\"36,5 46,25\" stroke=\"#c8c8c8\"/>",
+                "<polyline points=\"10,31 20,51\" stroke=\"#c8c8c8\"/>",
+            ]
+        );
+        cursor.move_to(1, 2, false);
+        cursor.move_to(1, 1, true);
+        let svg = export(&cursor, "", &Export::default());
+        assert_eq!(
+            polylines(&svg),
+            vec![
+                "<polyline points=\"10,5 20,5\" stroke=\"#c8c8c8\"/>",
+                "<polyline points=\"10,25 20,25\" stroke=\"#c8c8c8\"/>",
+            ]
+        );
+    }
+    #[test]
+    fn whole_buffer_without_selection() {
+        assert_eq!(
+            get_export_segments(&cursor(vec!["ab", "", "a"])),
+            vec![(0, 0, 2), (1, 0, 0), (2, 0, 1)]
+        );
+    }
+    #[test]
+    fn line_numbers_are_right_aligned_in_the_gutter() {
+        let lines = vec!["a"; 10];
+        let export_options = Export {
+            syntax_colors: true,
+            line_numbers: true,
+        };
+        let svg = export(&cursor(lines), "", &export_options);
+        let gutter = hex(Theme::dark().colors.gutter);
+        let text = polylines(&svg)
+            .into_iter()
+            .filter(|line| !line.contains(&gutter))
+            .collect::<Vec<&str>>();
+        assert_eq!(text.len(), 10);
+        assert!(text[0].starts_with("<polyline points=\"49,5 "));
+        let first_number = polylines(&svg)
+            .into_iter()
+            .find(|line| line.contains(&gutter))
+            .unwrap();
+        assert!(first_number.starts_with("<polyline points=\"23,"));
+    }
+    #[test]
+    fn syntax_colors_are_optional() {
+        let cursor = cursor(vec!["fn"]);
+        let theme = Theme::dark();
+        let keyword = hex(theme.syntax.keyword);
+        let colored = export(&cursor, "a.rs", &Export::default());
+        assert!(polylines(&colored)
+            .iter()
+            .all(|line| line.contains(&keyword)));
+        let plain = export(
+            &cursor,
+            "a.rs",
+            &Export {
+                syntax_colors: false,
+                line_numbers: false,
+            },
+        );
+        assert!(polylines(&plain)
+            .iter()
+            .all(|line| line.contains(&hex(theme.colors.text))));
+    }
+}
@@ -1,731 +1,561 @@
 pub mod text_rendering {
-    pub fn get_character_coords(
+    use super::super::font::font::Font;
+
+    /// A polyline, separate strokes of a glyph are drawn with the pen lifted
+    /// in between.
+    pub type Stroke = Vec<(i32, i32)>;
+
+    /// Every stroke of `character`, each one drawn as a polyline. Characters
+    /// without a glyph are drawn as their placeholder.
+    pub fn get_character_strokes(
+        font: &Font,
         character: &char,
         character_width: i32,
         character_height: i32,
-    ) -> Vec<(i32, i32)> {
+    ) -> Vec<Stroke> {
+        let strokes = get_glyph_strokes(font, character, character_width, character_height);
+        if strokes.is_empty() {
+            return get_placeholder_strokes(font, character, character_width, character_height);
+        }
+        strokes
+    }
+
+    /// Control characters in caret notation, like `^M` for `\r`, and any
+    /// other character as a box with its hex code point. Empty for
+    /// whitespace and tabs.
+    pub fn get_placeholder_strokes(
+        font: &Font,
+        character: &char,
+        character_width: i32,
+        character_height: i32,
+    ) -> Vec<Stroke> {
+        let code = *character as u32;
+        if *character == '\t' || *character == '\n' {
+            return vec![];
+        }
+        if code < 0x20 || code == 0x7f {
+            let escaped = std::char::from_u32(code ^ 0x40).unwrap_or('?');
+            return get_caret_strokes(font, escaped, character_width, character_height);
+        }
+        if character.is_whitespace() {
+            return vec![];
+        }
+        get_tofu_strokes(font, code, character_width, character_height)
+    }
+
+    /// A small `^` next to a narrowed `escaped`, both inside one cell.
+    fn get_caret_strokes(
+        font: &Font,
+        escaped: char,
+        character_width: i32,
+        character_height: i32,
+    ) -> Vec<Stroke> {
+        let half_width = character_width / 2;
+        let mut strokes = get_glyph_strokes(font, &'^', half_width, character_height);
+        let mut letter = get_glyph_strokes(font, &escaped, half_width, character_height);
+        move_strokes(&mut letter, |(x, y)| (half_width + x, y));
+        strokes.append(&mut letter);
+        strokes
+    }
+
+    /// The outline of the cell with the code point in two rows of hex
+    /// digits, four for the basic plane and six above it.
+    fn get_tofu_strokes(
+        font: &Font,
+        code: u32,
+        character_width: i32,
+        character_height: i32,
+    ) -> Vec<Stroke> {
+        let (width, height) = (character_width - 1, character_height - 1);
+        let mut strokes = vec![vec![
+            (0, 0),
+            (width, 0),
+            (width, height),
+            (0, height),
+            (0, 0),
+        ]];
+        let digits = if code > 0xffff {
+            format!("{:06X}", code)
+        } else {
+            format!("{:04X}", code)
+        };
+        let columns = digits.len() as i32 / 2;
+        let margin = std::cmp::max(1, character_width / 8);
+        let cell_width = (character_width - 2 * margin) / columns;
+        let cell_height = (character_height - 2 * margin) / 2;
+        let gap = std::cmp::max(1, cell_width / 4);
+        for (index, digit) in digits.chars().enumerate() {
+            let (column, row) = (index as i32 % columns, index as i32 / columns);
+            let (left, top) = (
+                margin + column * cell_width + gap / 2,
+                margin + row * cell_height + gap / 2,
+            );
+            let mut digit_strokes =
+                get_glyph_strokes(font, &digit, cell_width - gap, cell_height - gap);
+            move_strokes(&mut digit_strokes, |(x, y)| (left + x, top + y));
+            strokes.append(&mut digit_strokes);
+        }
+        strokes
+    }
+
+    fn get_glyph_strokes(
+        font: &Font,
+        character: &char,
+        character_width: i32,
+        character_height: i32,
+    ) -> Vec<Stroke> {
+        let strokes = font.get_strokes(character, character_width, character_height);
+        if !strokes.is_empty() {
+            return strokes;
+        }
+        if let Some((_, lookalike)) = LOOKALIKES.iter().find(|(glyph, _)| glyph == character) {
+            return get_glyph_strokes(font, lookalike, character_width, character_height);
+        }
+        if let Some((_, capital)) = SMALL_CAPITALS.iter().find(|(glyph, _)| glyph == character) {
+            let mut strokes = get_glyph_strokes(font, capital, character_width, character_height);
+            move_strokes(&mut strokes, |(x, y)| (x, character_height / 2 + y / 2));
+            return strokes;
+        }
+        if let Some((_, base, accent)) = COMPOSED.iter().find(|(glyph, _, _)| glyph == character) {
+            return get_composed_strokes(font, *base, *accent, character_width, character_height);
+        }
+        if let Some((_, arms)) = BOX_DRAWING.iter().find(|(glyph, _)| glyph == character) {
+            return get_box_drawing_strokes(*arms, character_width, character_height);
+        }
+        get_diagonal_strokes(*character, character_width, character_height)
+    }
+
+    fn move_strokes(strokes: &mut [Stroke], transform: impl Fn((i32, i32)) -> (i32, i32)) {
+        for stroke in strokes.iter_mut() {
+            for point in stroke.iter_mut() {
+                *point = transform(*point);
+            }
+        }
+    }
+
+    /// Capitals are squeezed into the lower three quarters to make room for
+    /// accents above them.
+    fn get_composed_strokes(
+        font: &Font,
+        base: char,
+        accent: Accent,
+        character_width: i32,
+        character_height: i32,
+    ) -> Vec<Stroke> {
+        let is_uppercase = base.is_uppercase();
+        let mut strokes = get_glyph_strokes(font, &base, character_width, character_height);
+        let is_above = accent != Accent::Cedilla && accent != Accent::Stroke;
+        if is_uppercase && is_above {
+            move_strokes(&mut strokes, |(x, y)| (x, character_height / 4 + y * 3 / 4));
+        }
+        strokes.extend(get_accent_strokes(
+            accent,
+            is_uppercase,
+            character_width,
+            character_height,
+        ));
+        strokes
+    }
+
+    fn get_accent_strokes(
+        accent: Accent,
+        is_uppercase: bool,
+        character_width: i32,
+        character_height: i32,
+    ) -> Vec<Stroke> {
+        let (width, height) = (character_width, character_height);
+        let (top, bottom) = if is_uppercase {
+            (0, height * 3 / 16)
+        } else {
+            (height / 4, height * 7 / 16)
+        };
+        match accent {
+            Accent::Grave => vec![vec![(width * 3 / 8, top), (width * 5 / 8, bottom)]],
+            Accent::Acute => vec![vec![(width * 5 / 8, top), (width * 3 / 8, bottom)]],
+            Accent::Circumflex => vec![vec![
+                (width / 4, bottom),
+                (width / 2, top),
+                (width * 3 / 4, bottom),
+            ]],
+            Accent::Tilde => vec![vec![
+                (0, bottom),
+                (width / 3, top),
+                (width * 2 / 3, bottom),
+                (width, top),
+            ]],
+            Accent::Diaeresis => vec![
+                vec![(width / 4, top), (width / 4, bottom)],
+                vec![(width * 3 / 4, top), (width * 3 / 4, bottom)],
+            ],
+            Accent::Ring => vec![vec![
+                (width / 3, top),
+                (width * 2 / 3, top),
+                (width * 2 / 3, bottom),
+                (width / 3, bottom),
+                (width / 3, top),
+            ]],
+            Accent::Breve => vec![vec![
+                (width / 4, top),
+                (width / 4, bottom),
+                (width * 3 / 4, bottom),
+                (width * 3 / 4, top),
+            ]],
+            Accent::Cedilla => vec![vec![
+                (width / 2, height),
+                (width / 2, height + height / 8),
+                (width / 4, height + height / 4),
+            ]],
+            Accent::Stroke => vec![vec![
+                (0, height),
+                (width, if is_uppercase { 0 } else { height / 2 }),
+            ]],
+        }
+    }
+
+    /// The lines reach into the gaps around the cell, so that neighbouring
+    /// box drawing characters connect at the default gap sizes.
+    fn get_box_drawing_strokes(
+        arms: [u8; 4],
+        character_width: i32,
+        character_height: i32,
+    ) -> Vec<Stroke> {
+        let (left, right) = (-character_width / 6, character_width + character_width / 6);
+        let (top, bottom) = (
+            -character_height / 6,
+            character_height + character_height / 6,
+        );
+        let (center_x, center_y) = (character_width / 2, character_height / 2);
+        let double_offset = (character_width / 6).max(1);
+        let get_offsets = |weight: u8| match weight {
+            0 => vec![],
+            1 => vec![0],
+            2 => vec![-1, 0, 1],
+            _ => vec![-double_offset, double_offset],
+        };
+        let reach = arms
+            .iter()
+            .flat_map(|weight| get_offsets(*weight))
+            .max()
+            .unwrap_or(0);
+        let mut strokes = vec![];
+        for (direction, weight) in arms.iter().enumerate() {
+            for offset in get_offsets(*weight) {
+                strokes.push(match direction {
+                    0 => vec![
+                        (center_x + offset, top),
+                        (center_x + offset, center_y + reach),
+                    ],
+                    1 => vec![
+                        (center_x + offset, bottom),
+                        (center_x + offset, center_y - reach),
+                    ],
+                    2 => vec![
+                        (left, center_y + offset),
+                        (center_x + reach, center_y + offset),
+                    ],
+                    _ => vec![
+                        (right, center_y + offset),
+                        (center_x - reach, center_y + offset),
+                    ],
+                });
+            }
+        }
+        strokes
+    }
+
+    fn get_diagonal_strokes(
+        character: char,
+        character_width: i32,
+        character_height: i32,
+    ) -> Vec<Stroke> {
+        let (left, right) = (-character_width / 6, character_width + character_width / 6);
+        let (top, bottom) = (
+            -character_height / 6,
+            character_height + character_height / 6,
+        );
+        let rising = vec![(left, bottom), (right, top)];
+        let falling = vec![(left, top), (right, bottom)];
         match character {
-            'a' => vec![
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height),
-                (0, character_height),
-                (0, character_height - character_height / 4),
-                (character_width, character_height - character_height / 4),
-            ],
-            'b' => vec![
-                (0, 0),
-                (0, character_height),
-                (character_width, character_height),
-                (character_width, character_height / 2),
-                (0, character_height / 2),
-            ],
-            'c' => vec![
-                (character_width, character_height / 2),
-                (0, character_height / 2),
-                (0, character_height),
-                (character_width, character_height),
-            ],
-            'd' => vec![
-                (character_width, 0),
-                (character_width, character_height),
-                (0, character_height),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-            ],
-            'e' => vec![
-                (character_width, character_height),
-                (0, character_height),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height - character_height / 4),
-                (0, character_height - character_height / 4),
-            ],
-            'f' => vec![
-                (character_width / 2, character_height * 5 / 4),
-                (character_width / 2, character_height / 4),
-                (0, character_height / 4),
-                (character_width, character_height / 4),
-                (character_width / 2, character_height / 4),
-                (character_width / 2, 0),
-                (character_width, 0),
-            ],
-            'g' => vec![
-                (character_width, character_height),
-                (0, character_height),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height * 5 / 4),
-                (0, character_height * 5 / 4),
-            ],
-            'h' => vec![
-                (0, 0),
-                (0, character_height),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height),
-            ],
-            'i' => vec![
-                (character_width / 4, character_height),
-                (character_width * 3 / 4, character_height),
-                (character_width / 2, character_height),
-                (character_width / 2, character_height / 2),
-                (character_width / 4, character_height / 2),
-            ],
-            'j' => vec![
-                (character_width / 4, character_height / 2),
-                (character_width / 2, character_height / 2),
-                (character_width / 2, character_height * 5 / 4),
-                (0, character_height * 5 / 4),
-            ],
-            'k' => vec![
-                (character_width, character_height),
-                (character_width, character_height / 2),
-                (0, character_height / 2),
-                (0, character_height),
-                (0, character_height / 3),
-                (character_width, character_height / 3),
-                (0, character_height / 3),
-                (0, 0),
-            ],
-            'l' => vec![
-                (character_width / 4, 0),
-                (character_width / 4, character_height),
-                (character_width * 3 / 4, character_height),
-            ],
-            'm' => vec![
-                (0, character_height),
-                (0, character_height / 2),
-                (character_width / 2, character_height / 2),
-                (character_width / 2, character_height),
-                (character_width / 2, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height),
-            ],
-            'n' => vec![
-                (0, character_height),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height),
-            ],
-            'o' => vec![
-                (0, character_height / 2),
-                (0, character_height),
-                (character_width, character_height),
-                (character_width, character_height / 2),
-                (0, character_height / 2),
-            ],
-            'p' => vec![
-                (0, character_height * 5 / 4),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height),
-                (0, character_height),
-            ],
-            'q' => vec![
-                (character_width, character_height * 5 / 4),
-                (character_width, character_height / 2),
-                (0, character_height / 2),
-                (0, character_height),
-                (character_width, character_height),
-            ],
-            'r' => vec![
-                (0, character_height),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-            ],
-            's' => vec![
-                (character_width, character_height / 2),
-                (0, character_height / 2),
-                (0, character_height - character_height / 4),
-                (character_width, character_height - character_height / 4),
-                (character_width, character_height),
-                (0, character_height),
-            ],
-            't' => vec![
-                (character_width, character_height),
-                (character_width / 2, character_height),
-                (character_width / 2, character_height / 4),
-                (0, character_height / 4),
-                (character_width, character_height / 4),
-                (character_width / 2, character_height / 4),
-                (character_width / 2, 0),
-            ],
-            'u' => vec![
-                (0, character_height / 2),
-                (0, character_height),
-                (character_width, character_height),
-                (character_width, character_height / 2),
-            ],
-            'v' => vec![
-                (0, character_height / 2),
-                (character_width / 2, character_height),
-                (character_width, character_height / 2),
-            ],
-            'w' => vec![
-                (0, character_height / 2),
-                (0, character_height),
-                (character_width / 2, character_height),
-                (character_width / 2, character_height / 2),
-                (character_width / 2, character_height),
-                (character_width, character_height),
-                (character_width, character_height / 2),
-            ],
-            'x' => vec![
-                (0, character_height / 2),
-                (character_width, character_height),
-                (character_width / 2, character_height - character_height / 4),
-                (0, character_height),
-                (character_width, character_height / 2),
-            ],
-            'y' => vec![
-                (0, character_height / 2),
-                (0, character_height),
-                (character_width, character_height),
-                (character_width, character_height * 5 / 4),
-                (0, character_height * 5 / 4),
-                (character_width, character_height * 5 / 4),
-                (character_width, character_height / 2),
-            ],
-            'z' => vec![
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height - character_height / 4),
-                (0, character_height - character_height / 4),
-                (0, character_height),
-                (character_width, character_height),
-            ],
-            '0' => vec![
-                (0, 0),
-                (character_width / 2, 0),
-                (character_width / 2, character_height),
-                (character_width / 2, 0),
-                (character_width, 0),
-                (character_width, character_height),
-                (0, character_height),
-                (0, 0),
-            ],
-            '1' => vec![
-                (character_width / 4, character_height),
-                (character_width * 3 / 4, character_height),
-                (character_width / 2, character_height),
-                (character_width / 2, 0),
-                (character_width / 4, 0),
-            ],
-            '2' => vec![
-                (0, 0),
-                (character_width, 0),
-                (character_width, character_height / 2),
-                (0, character_height / 2),
-                (0, character_height),
-                (character_width, character_height),
-            ],
-            '3' => vec![
-                (0, 0),
-                (character_width, 0),
-                (character_width, character_height / 2),
-                (character_width / 2, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height),
-                (0, character_height),
-            ],
-            '4' => vec![
-                (0, 0),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height),
-                (character_width, 0),
-            ],
-            '5' => vec![
-                (character_width, 0),
-                (0, 0),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height),
-                (0, character_height),
-            ],
-            '6' => vec![
-                (character_width, 0),
-                (0, 0),
-                (0, character_height),
-                (character_width, character_height),
-                (character_width, character_height / 2),
-                (0, character_height / 2),
-            ],
-            '7' => vec![
-                (0, 0),
-                (character_width, 0),
-                (character_width, character_height / 2),
-                (character_width / 2, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height),
-            ],
-            '8' => vec![
-                (0, 0),
-                (character_width, 0),
-                (character_width, character_height),
-                (0, character_height),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-                (0, character_height / 2),
-                (0, 0),
-            ],
-            '9' => vec![
-                (0, character_height),
-                (character_width, character_height),
-                (character_width, 0),
-                (0, 0),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-            ],
-            ',' => vec![
-                (character_width / 2, character_height),
-                (character_width / 2, character_height * 5 / 4),
-            ],
-            '.' => vec![
-                (character_width / 2, character_height),
-                (character_width / 2, character_height * 11 / 12),
-            ],
-            '\'' => vec![
-                (character_width / 2, 0),
-                (character_width / 2, -character_height / 3),
-            ],
-            '"' => vec![
-                (character_width * 1 / 3, 0),
-                (character_width * 1 / 3, -character_height / 3),
-                (character_width * 2 / 3, 0),
-                (character_width * 2 / 3, -character_height / 3),
-            ],
-            '`' => vec![
-                (character_width * 3 / 5, 0),
-                (character_width * 2 / 5, -character_height / 3),
-            ],
-            '+' => vec![
-                (character_width / 4, character_height / 2),
-                (character_width * 3 / 4, character_height / 2),
-                (character_width / 2, character_height / 2),
-                (character_width / 2, character_height * 3 / 4),
-                (character_width / 2, character_height / 4),
-            ],
-            '-' => vec![
-                (character_width / 4, character_height / 2),
-                (character_width * 3 / 4, character_height / 2),
-            ],
-            '*' => vec![
-                (character_width / 6, character_height * 3 / 7),
-                (character_width / 2, character_height / 2),
-                (character_width * 5 / 6, character_height * 3 / 7),
-                (character_width / 2, character_height / 2),
-                (character_width / 2, character_height / 4),
-                (character_width / 2, character_height / 2),
-                (character_width * 5 / 6, character_height * 5 / 7),
-                (character_width / 2, character_height / 2),
-                (character_width / 6, character_height * 5 / 7),
-            ],
-            '/' => vec![(0, character_height), (character_width, 0)],
-            '=' => vec![
-                (character_width / 4, character_height * 2 / 5),
-                (character_width * 3 / 4, character_height * 2 / 5),
-                (character_width / 4, character_height * 3 / 5),
-                (character_width * 3 / 4, character_height * 3 / 5),
-            ],
-            '\\' => vec![(0, 0), (character_width, character_height)],
-            '#' => vec![
-                (character_width / 4, 0),
-                (character_width / 4, character_height),
-                (character_width / 4, character_height / 4),
-                (0, character_height / 4),
-                (character_width, character_height / 4),
-                (character_width * 3 / 4, character_height / 4),
-                (character_width * 3 / 4, 0),
-                (character_width * 3 / 4, character_height),
-                (character_width * 3 / 4, character_height / 4),
-                (character_width * 3 / 4, character_height * 3 / 4),
-                (character_width, character_height * 3 / 4),
-                (0, character_height * 3 / 4),
-            ],
-            '(' => vec![
-                (character_width * 3 / 4, 0),
-                (character_width / 4, character_height / 4),
-                (character_width / 4, character_height * 3 / 4),
-                (character_width * 3 / 4, character_height),
-            ],
-            ')' => vec![
-                (character_width / 4, 0),
-                (character_width * 3 / 4, character_height / 4),
-                (character_width * 3 / 4, character_height * 3 / 4),
-                (character_width / 4, character_height),
-            ],
-            '[' => vec![
-                (character_width * 3 / 4, 0),
-                (character_width / 4, 0),
-                (character_width / 4, character_height),
-                (character_width * 3 / 4, character_height),
-            ],
-            ']' => vec![
-                (character_width / 4, 0),
-                (character_width * 3 / 4, 0),
-                (character_width * 3 / 4, character_height),
-                (character_width / 4, character_height),
-            ],
-            '{' => vec![
-                (character_width, 0),
-                (character_width / 2, 0),
-                (character_width / 2, character_height * 5 / 11),
-                (character_width / 4, character_height / 2),
-                (character_width / 2, character_height * 6 / 11),
-                (character_width / 2, character_height),
-                (character_width, character_height),
-            ],
-            '}' => vec![
-                (0, 0),
-                (character_width / 2, 0),
-                (character_width / 2, character_height * 5 / 11),
-                (character_width * 3 / 4, character_height / 2),
-                (character_width / 2, character_height * 6 / 11),
-                (character_width / 2, character_height),
-                (0, character_height),
-            ],
-            '^' => vec![
-                (0, character_height / 4),
-                (character_width / 2, 0),
-                (character_width, character_height / 4),
-            ],
-            '°' => vec![
-                (character_width / 4, 0),
-                (character_width * 3 / 4, 0),
-                (character_width * 3 / 4, character_height / 4),
-                (character_width / 4, character_height / 4),
-                (character_width / 4, 0),
-            ],
-            '$' => vec![
-                (character_width, 0),
-                (character_width / 2, 0),
-                (character_width / 2, -character_height / 4),
-                (character_width / 2, character_height * 5 / 4),
-                (character_width / 2, 0),
-                (0, 0),
-                (0, character_height / 2),
-                (character_width / 2, character_height / 2),
-                (character_width / 2, character_height * 3 / 4),
-                (character_width / 2, character_height / 4),
-                (character_width / 2, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height),
-                (0, character_height),
-            ],
-            '%' => vec![
-                (character_width, 0),
-                (character_width / 2, character_height / 2),
-                (0, character_height / 2),
-                (0, character_height / 4),
-                (character_width / 2, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height * 3 / 4),
-                (character_width / 2, character_height / 2),
-                (0, character_height),
-            ],
-            '<' => vec![
-                (character_width, character_height / 4),
-                (0, character_height / 2),
-                (character_width, character_height * 3 / 4),
-            ],
-            '>' => vec![
-                (0, character_height / 4),
-                (character_width, character_height / 2),
-                (0, character_height * 3 / 4),
-            ],
-            '?' => vec![
-                (0, 0),
-                (character_width, 0),
-                (character_width, character_height / 2),
-                (character_width / 2, character_height / 2),
-                (character_width / 2, character_height * 3 / 4),
-                (character_width / 4, character_height),
-                (character_width * 3 / 4, character_height),
-                (character_width / 2, character_height * 3 / 4),
-            ],
-            '!' => vec![
-                (character_width / 2, 0),
-                (character_width / 2, character_height * 3 / 4),
-                (character_width / 4, character_height),
-                (character_width * 3 / 4, character_height),
-                (character_width / 2, character_height * 3 / 4),
-            ],
-            ':' => vec![
-                (character_width / 2, character_height / 2),
-                (character_width / 4, character_height / 4),
-                (character_width * 3 / 4, character_height / 4),
-                (character_width / 2, character_height / 2),
-                (character_width / 4, character_height * 3 / 4),
-                (character_width * 3 / 4, character_height * 3 / 4),
-                (character_width / 2, character_height / 2),
-            ],
-            ';' => vec![
-                (character_width / 2, character_height / 2),
-                (character_width / 4, character_height / 4),
-                (character_width * 3 / 4, character_height / 4),
-                (character_width / 2, character_height / 2),
-                (character_width / 2, character_height),
-            ],
-            '&' => vec![
-                (character_width, character_height),
-                (0, character_height / 3),
-                (character_width / 2, 0),
-                (character_width * 3 / 4, character_height / 4),
-                (0, character_height * 2 / 3),
-                (character_width / 2, character_height),
-                (character_width, character_height * 2 / 3),
-            ],
-            '|' => vec![
-                (character_width / 2, 0),
-                (character_width / 2, character_height),
-            ],
-            '_' => vec![(0, character_height), (character_width, character_height)],
-            '~' => vec![
-                (0, character_height * 3 / 5),
-                (character_width / 3, character_height * 2 / 5),
-                (character_width * 3 / 5, character_height * 3 / 5),
-                (character_width, character_height * 2 / 5),
-            ],
-            '€' => vec![
-                (character_width, 0),
-                (0, 0),
-                (0, character_height / 3),
-                (character_width / 2, character_height / 3),
-                (0, character_height / 3),
-                (0, character_height * 2 / 3),
-                (character_width / 2, character_height * 2 / 3),
-                (0, character_height * 2 / 3),
-                (0, character_height),
-                (character_width, character_height),
-            ],
-            '@' => vec![
-                (character_width, character_height / 6),
-                (character_width, character_height * 5 / 6),
-                (0, character_height * 5 / 6),
-                (0, character_height / 6),
-                (character_width * 5 / 7, character_height / 6),
-                (character_width * 5 / 7, character_height * 2 / 3),
-                (character_width * 3 / 8, character_height * 2 / 3),
-                (character_width * 3 / 8, character_height * 1 / 3),
-                (character_width * 5 / 7, character_height * 1 / 3),
-            ],
-            'A' => vec![
-                (0, character_height),
-                (0, 0),
-                (character_width, 0),
-                (character_width, character_height / 2),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height),
-            ],
-            'B' => vec![
-                (character_width, character_height / 2),
-                (character_width, character_height),
-                (0, character_height),
-                (0, 0),
-                (character_width, 0),
-                (character_width, character_height / 2),
-                (character_width / 3, character_height / 2),
-            ],
-            'C' => vec![
-                (character_width, 0),
-                (0, 0),
-                (0, character_height),
-                (character_width, character_height),
-            ],
-            'D' => vec![
-                (0, 0),
-                (character_width * 4 / 5, 0),
-                (character_width * 4 / 5, character_height / 6),
-                (character_width, character_height / 6),
-                (character_width, character_height * 5 / 6),
-                (character_width * 4 / 5, character_height * 5 / 6),
-                (character_width * 4 / 5, character_height),
-                (0, character_height),
-                (0, 0),
-            ],
-            'E' => vec![
-                (character_width, 0),
-                (0, 0),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-                (0, character_height / 2),
-                (0, character_height),
-                (character_width, character_height),
-            ],
-            'F' => vec![
-                (character_width, 0),
-                (0, 0),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-                (0, character_height / 2),
-                (0, character_height),
-            ],
-            'G' => vec![
-                (character_width, 0),
-                (0, 0),
-                (0, character_height),
-                (character_width, character_height),
-                (character_width, character_height / 2),
-                (character_width / 2, character_height / 2),
-            ],
-            'H' => vec![
-                (0, 0),
-                (0, character_height),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, 0),
-                (character_width, character_height),
-            ],
-            'I' => vec![
-                (0, 0),
-                (character_width, 0),
-                (character_width / 2, 0),
-                (character_width / 2, character_height),
-                (0, character_height),
-                (character_width, character_height),
-            ],
-            'J' => vec![
-                (0, 0),
-                (character_width, 0),
-                (character_width / 2, 0),
-                (character_width / 2, character_height),
-                (0, character_height),
-            ],
-            'K' => vec![
-                (character_width, character_height),
-                (character_width, character_height * 5 / 12),
-                (0, character_height * 5 / 12),
-                (0, character_height),
-                (0, 0),
-                (0, character_height * 7 / 12),
-                (character_width, character_height * 7 / 12),
-                (character_width, 0),
-            ],
-            'L' => vec![
-                (0, 0),
-                (0, character_height),
-                (character_width, character_height),
-            ],
-            'M' => vec![
-                (0, character_height),
-                (0, 0),
-                (character_width / 2, 0),
-                (character_width / 2, character_height),
-                (character_width / 2, 0),
-                (character_width, 0),
-                (character_width, character_height),
-            ],
-            'N' => vec![
-                (0, character_height),
-                (0, 0),
-                (character_width / 2, 0),
-                (character_width / 2, character_height),
-                (character_width, character_height),
-                (character_width, 0),
-            ],
-            'O' => vec![
-                (0, 0),
-                (character_width, 0),
-                (character_width, character_height),
-                (0, character_height),
-                (0, 0),
-            ],
-            'P' => vec![
-                (0, character_height),
-                (0, 0),
-                (character_width, 0),
-                (character_width, character_height / 2),
-                (0, character_height / 2),
-            ],
-            'Q' => vec![
-                (character_width, character_height),
-                (0, character_height),
-                (0, 0),
-                (character_width, 0),
-                (character_width, character_height),
-                (character_width / 2, character_height),
-                (character_width / 2, character_height / 2),
-            ],
-            'R' => vec![
-                (0, character_height),
-                (0, 0),
-                (character_width, 0),
-                (character_width, character_height / 2),
-                (0, character_height / 2),
-                (character_width * 3 / 4, character_height / 2),
-                (character_width * 3 / 4, character_height),
-            ],
-            'S' => vec![
-                (character_width, 0),
-                (0, 0),
-                (0, character_height / 2),
-                (character_width / 2, character_height / 2),
-                (character_width / 2, character_height * 3 / 4),
-                (character_width / 2, character_height / 4),
-                (character_width / 2, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, character_height),
-                (0, character_height),
-            ],
-            'T' => vec![
-                (0, 0),
-                (character_width, 0),
-                (character_width / 2, 0),
-                (character_width / 2, character_height),
-            ],
-            'U' => vec![
-                (0, 0),
-                (0, character_height),
-                (character_width, character_height),
-                (character_width, 0),
-            ],
-            'V' => vec![
-                (0, 0),
-                (character_width / 2, character_height),
-                (character_width, 0),
-            ],
-            'W' => vec![
-                (0, 0),
-                (0, character_height),
-                (character_width / 2, character_height),
-                (character_width / 2, 0),
-                (character_width / 2, character_height),
-                (character_width, character_height),
-                (character_width, 0),
-            ],
-            'X' => vec![
-                (0, 0),
-                (character_width, character_height),
-                (character_width / 2, character_height - character_height / 2),
-                (0, character_height),
-                (character_width, 0),
-            ],
-            'Y' => vec![
-                (character_width / 2, character_height),
-                (character_width / 2, character_height / 2),
-                (0, character_height / 2),
-                (0, 0),
-                (0, character_height / 2),
-                (character_width, character_height / 2),
-                (character_width, 0),
-            ],
-            'Z' => vec![
-                (0, 0),
-                (character_width, 0),
-                (character_width, character_height / 2),
-                (character_width / 2, character_height / 2),
-                (character_width / 2, character_height * 3 / 4),
-                (character_width / 2, character_height / 4),
-                (character_width / 2, character_height / 2),
-                (character_width, character_height / 2),
-                (0, character_height / 2),
-                (0, character_height),
-                (character_width, character_height),
-            ],
+            '╱' => vec![rising],
+            '╲' => vec![falling],
+            '╳' => vec![rising, falling],
             _ => vec![],
         }
     }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Accent {
+        Grave,
+        Acute,
+        Circumflex,
+        Tilde,
+        Diaeresis,
+        Ring,
+        Cedilla,
+        Stroke,
+        Breve,
+    }
+
+    static LOOKALIKES: [(char, char); 43] = [
+        ('Α', 'A'),
+        ('Β', 'B'),
+        ('Ε', 'E'),
+        ('Ζ', 'Z'),
+        ('Η', 'H'),
+        ('Ι', 'I'),
+        ('Κ', 'K'),
+        ('Μ', 'M'),
+        ('Ν', 'N'),
+        ('Ο', 'O'),
+        ('Ρ', 'P'),
+        ('Τ', 'T'),
+        ('Υ', 'Y'),
+        ('Χ', 'X'),
+        ('ν', 'v'),
+        ('ο', 'o'),
+        ('ρ', 'p'),
+        ('υ', 'u'),
+        ('χ', 'x'),
+        ('µ', 'μ'),
+        ('А', 'A'),
+        ('В', 'B'),
+        ('Г', 'Γ'),
+        ('Е', 'E'),
+        ('К', 'K'),
+        ('М', 'M'),
+        ('Н', 'H'),
+        ('О', 'O'),
+        ('П', 'Π'),
+        ('Р', 'P'),
+        ('С', 'C'),
+        ('Т', 'T'),
+        ('Ф', 'Φ'),
+        ('Х', 'X'),
+        ('а', 'a'),
+        ('б', 'Б'),
+        ('е', 'e'),
+        ('о', 'o'),
+        ('р', 'p'),
+        ('с', 'c'),
+        ('у', 'y'),
+        ('ф', 'φ'),
+        ('х', 'x'),
+    ];
+
+    static SMALL_CAPITALS: [(char, char); 22] = [
+        ('в', 'В'),
+        ('г', 'Г'),
+        ('д', 'Д'),
+        ('ж', 'Ж'),
+        ('з', 'З'),
+        ('и', 'И'),
+        ('к', 'К'),
+        ('л', 'Л'),
+        ('м', 'М'),
+        ('н', 'Н'),
+        ('п', 'П'),
+        ('т', 'Т'),
+        ('ц', 'Ц'),
+        ('ч', 'Ч'),
+        ('ш', 'Ш'),
+        ('щ', 'Щ'),
+        ('ъ', 'Ъ'),
+        ('ы', 'Ы'),
+        ('ь', 'Ь'),
+        ('э', 'Э'),
+        ('ю', 'Ю'),
+        ('я', 'Я'),
+    ];
+
+    static COMPOSED: [(char, char, Accent); 59] = [
+        ('À', 'A', Accent::Grave),
+        ('Á', 'A', Accent::Acute),
+        ('Â', 'A', Accent::Circumflex),
+        ('Ã', 'A', Accent::Tilde),
+        ('Ä', 'A', Accent::Diaeresis),
+        ('Å', 'A', Accent::Ring),
+        ('Ç', 'C', Accent::Cedilla),
+        ('È', 'E', Accent::Grave),
+        ('É', 'E', Accent::Acute),
+        ('Ê', 'E', Accent::Circumflex),
+        ('Ë', 'E', Accent::Diaeresis),
+        ('Ì', 'I', Accent::Grave),
+        ('Í', 'I', Accent::Acute),
+        ('Î', 'I', Accent::Circumflex),
+        ('Ï', 'I', Accent::Diaeresis),
+        ('Ñ', 'N', Accent::Tilde),
+        ('Ò', 'O', Accent::Grave),
+        ('Ó', 'O', Accent::Acute),
+        ('Ô', 'O', Accent::Circumflex),
+        ('Õ', 'O', Accent::Tilde),
+        ('Ö', 'O', Accent::Diaeresis),
+        ('Ø', 'O', Accent::Stroke),
+        ('Ù', 'U', Accent::Grave),
+        ('Ú', 'U', Accent::Acute),
+        ('Û', 'U', Accent::Circumflex),
+        ('Ü', 'U', Accent::Diaeresis),
+        ('Ý', 'Y', Accent::Acute),
+        ('à', 'a', Accent::Grave),
+        ('á', 'a', Accent::Acute),
+        ('â', 'a', Accent::Circumflex),
+        ('ã', 'a', Accent::Tilde),
+        ('ä', 'a', Accent::Diaeresis),
+        ('å', 'a', Accent::Ring),
+        ('ç', 'c', Accent::Cedilla),
+        ('è', 'e', Accent::Grave),
+        ('é', 'e', Accent::Acute),
+        ('ê', 'e', Accent::Circumflex),
+        ('ë', 'e', Accent::Diaeresis),
+        ('ì', 'i', Accent::Grave),
+        ('í', 'i', Accent::Acute),
+        ('î', 'i', Accent::Circumflex),
+        ('ï', 'i', Accent::Diaeresis),
+        ('ñ', 'n', Accent::Tilde),
+        ('ò', 'o', Accent::Grave),
+        ('ó', 'o', Accent::Acute),
+        ('ô', 'o', Accent::Circumflex),
+        ('õ', 'o', Accent::Tilde),
+        ('ö', 'o', Accent::Diaeresis),
+        ('ø', 'o', Accent::Stroke),
+        ('ù', 'u', Accent::Grave),
+        ('ú', 'u', Accent::Acute),
+        ('û', 'u', Accent::Circumflex),
+        ('ü', 'u', Accent::Diaeresis),
+        ('ý', 'y', Accent::Acute),
+        ('ÿ', 'y', Accent::Diaeresis),
+        ('Ё', 'Е', Accent::Diaeresis),
+        ('ё', 'е', Accent::Diaeresis),
+        ('Й', 'И', Accent::Breve),
+        ('й', 'и', Accent::Breve),
+    ];
+
+    /// Box drawing characters by their up, down, left and right arms: 0 for
+    /// none, 1 light, 2 heavy and 3 double. Dashed lines are drawn solid.
+    static BOX_DRAWING: [(char, [u8; 4]); 125] = [
+        ('─', [0, 0, 1, 1]),
+        ('━', [0, 0, 2, 2]),
+        ('│', [1, 1, 0, 0]),
+        ('┃', [2, 2, 0, 0]),
+        ('┄', [0, 0, 1, 1]),
+        ('┅', [0, 0, 2, 2]),
+        ('┆', [1, 1, 0, 0]),
+        ('┇', [2, 2, 0, 0]),
+        ('┈', [0, 0, 1, 1]),
+        ('┉', [0, 0, 2, 2]),
+        ('┊', [1, 1, 0, 0]),
+        ('┋', [2, 2, 0, 0]),
+        ('┌', [0, 1, 0, 1]),
+        ('┍', [0, 1, 0, 2]),
+        ('┎', [0, 2, 0, 1]),
+        ('┏', [0, 2, 0, 2]),
+        ('┐', [0, 1, 1, 0]),
+        ('┑', [0, 1, 2, 0]),
+        ('┒', [0, 2, 1, 0]),
+        ('┓', [0, 2, 2, 0]),
+        ('└', [1, 0, 0, 1]),
+        ('┕', [1, 0, 0, 2]),
+        ('┖', [2, 0, 0, 1]),
+        ('┗', [2, 0, 0, 2]),
+        ('┘', [1, 0, 1, 0]),
+        ('┙', [1, 0, 2, 0]),
+        ('┚', [2, 0, 1, 0]),
+        ('┛', [2, 0, 2, 0]),
+        ('├', [1, 1, 0, 1]),
+        ('┝', [1, 1, 0, 2]),
+        ('┞', [2, 1, 0, 1]),
+        ('┟', [1, 2, 0, 1]),
+        ('┠', [2, 2, 0, 1]),
+        ('┡', [2, 1, 0, 2]),
+        ('┢', [1, 2, 0, 2]),
+        ('┣', [2, 2, 0, 2]),
+        ('┤', [1, 1, 1, 0]),
+        ('┥', [1, 1, 2, 0]),
+        ('┦', [2, 1, 1, 0]),
+        ('┧', [1, 2, 1, 0]),
+        ('┨', [2, 2, 1, 0]),
+        ('┩', [2, 1, 2, 0]),
+        ('┪', [1, 2, 2, 0]),
+        ('┫', [2, 2, 2, 0]),
+        ('┬', [0, 1, 1, 1]),
+        ('┭', [0, 1, 2, 1]),
+        ('┮', [0, 1, 1, 2]),
+        ('┯', [0, 1, 2, 2]),
+        ('┰', [0, 2, 1, 1]),
+        ('┱', [0, 2, 2, 1]),
+        ('┲', [0, 2, 1, 2]),
+        ('┳', [0, 2, 2, 2]),
+        ('┴', [1, 0, 1, 1]),
+        ('┵', [1, 0, 2, 1]),
+        ('┶', [1, 0, 1, 2]),
+        ('┷', [1, 0, 2, 2]),
+        ('┸', [2, 0, 1, 1]),
+        ('┹', [2, 0, 2, 1]),
+        ('┺', [2, 0, 1, 2]),
+        ('┻', [2, 0, 2, 2]),
+        ('┼', [1, 1, 1, 1]),
+        ('┽', [1, 1, 2, 1]),
+        ('┾', [1, 1, 1, 2]),
+        ('┿', [1, 1, 2, 2]),
+        ('╀', [2, 1, 1, 1]),
+        ('╁', [1, 2, 1, 1]),
+        ('╂', [2, 2, 1, 1]),
+        ('╃', [2, 1, 2, 1]),
+        ('╄', [2, 1, 1, 2]),
+        ('╅', [1, 2, 2, 1]),
+        ('╆', [1, 2, 1, 2]),
+        ('╇', [2, 1, 2, 2]),
+        ('╈', [1, 2, 2, 2]),
+        ('╉', [2, 2, 2, 1]),
+        ('╊', [2, 2, 1, 2]),
+        ('╋', [2, 2, 2, 2]),
+        ('╌', [0, 0, 1, 1]),
+        ('╍', [0, 0, 2, 2]),
+        ('╎', [1, 1, 0, 0]),
+        ('╏', [2, 2, 0, 0]),
+        ('═', [0, 0, 3, 3]),
+        ('║', [3, 3, 0, 0]),
+        ('╒', [0, 1, 0, 3]),
+        ('╓', [0, 3, 0, 1]),
+        ('╔', [0, 3, 0, 3]),
+        ('╕', [0, 1, 3, 0]),
+        ('╖', [0, 3, 1, 0]),
+        ('╗', [0, 3, 3, 0]),
+        ('╘', [1, 0, 0, 3]),
+        ('╙', [3, 0, 0, 1]),
+        ('╚', [3, 0, 0, 3]),
+        ('╛', [1, 0, 3, 0]),
+        ('╜', [3, 0, 1, 0]),
+        ('╝', [3, 0, 3, 0]),
+        ('╞', [1, 1, 0, 3]),
+        ('╟', [3, 3, 0, 1]),
+        ('╠', [3, 3, 0, 3]),
+        ('╡', [1, 1, 3, 0]),
+        ('╢', [3, 3, 1, 0]),
+        ('╣', [3, 3, 3, 0]),
+        ('╤', [0, 1, 3, 3]),
+        ('╥', [0, 3, 1, 1]),
+        ('╦', [0, 3, 3, 3]),
+        ('╧', [1, 0, 3, 3]),
+        ('╨', [3, 0, 1, 1]),
+        ('╩', [3, 0, 3, 3]),
+        ('╪', [1, 1, 3, 3]),
+        ('╫', [3, 3, 1, 1]),
+        ('╬', [3, 3, 3, 3]),
+        ('╭', [0, 1, 0, 1]),
+        ('╮', [0, 1, 1, 0]),
+        ('╯', [1, 0, 1, 0]),
+        ('╰', [1, 0, 0, 1]),
+        ('╴', [0, 0, 1, 0]),
+        ('╵', [1, 0, 0, 0]),
+        ('╶', [0, 0, 0, 1]),
+        ('╷', [0, 1, 0, 0]),
+        ('╸', [0, 0, 2, 0]),
+        ('╹', [2, 0, 0, 0]),
+        ('╺', [0, 0, 0, 2]),
+        ('╻', [0, 2, 0, 0]),
+        ('╼', [0, 0, 1, 2]),
+        ('╽', [1, 2, 0, 0]),
+        ('╾', [0, 0, 2, 1]),
+        ('╿', [2, 1, 0, 0]),
+    ];
 }
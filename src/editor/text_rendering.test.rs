@@ -0,0 +1,187 @@
+#[path = "text_rendering.rs"]
+mod text_rendering;
+
+#[path = "font.rs"]
+mod font;
+
+#[cfg(test)]
+mod tests {
+    use super::font::font::{get_curve, Font};
+    use super::text_rendering::text_rendering::{get_character_strokes, get_placeholder_strokes};
+    fn strokes(character: char) -> Vec<Vec<(i32, i32)>> {
+        get_character_strokes(&Font::builtin(), &character, 10, 16)
+    }
+    /// Printable ASCII, Latin-1, Greek, Cyrillic and box drawing.
+    fn covered_characters() -> Vec<char> {
+        let ranges = [
+            ('!', '~'),
+            ('À', 'ÿ'),
+            ('Α', 'Ρ'),
+            ('Σ', 'Ω'),
+            ('α', 'ω'),
+            ('А', 'я'),
+            ('─', '╿'),
+        ];
+        ranges
+            .iter()
+            .flat_map(|(first, last)| *first..=*last)
+            .chain("¡¢£¥§«¬±µ·»ЁёЙй".chars())
+            .collect()
+    }
+    #[test]
+    fn every_covered_character_has_a_glyph() {
+        let font = Font::builtin();
+        let missing = covered_characters()
+            .into_iter()
+            .filter(|character| {
+                get_character_strokes(&font, character, 10, 16)
+                    == get_placeholder_strokes(&font, character, 10, 16)
+            })
+            .collect::<String>();
+        assert_eq!(missing, "");
+    }
+    #[test]
+    fn space_and_tab_are_blank() {
+        assert!(strokes(' ').is_empty());
+        assert!(strokes('\t').is_empty());
+        assert!(strokes('\u{a0}').is_empty());
+    }
+    fn offset(mut strokes: Vec<Vec<(i32, i32)>>, left: i32, top: i32) -> Vec<Vec<(i32, i32)>> {
+        for stroke in strokes.iter_mut() {
+            for point in stroke.iter_mut() {
+                *point = (point.0 + left, point.1 + top);
+            }
+        }
+        strokes
+    }
+    #[test]
+    fn control_characters_use_caret_notation() {
+        for (character, escaped) in &[('\r', 'M'), ('\0', '@'), ('\u{1b}', '['), ('\u{7f}', '?')] {
+            let mut expected = get_character_strokes(&Font::builtin(), &'^', 5, 16);
+            expected.append(&mut offset(
+                get_character_strokes(&Font::builtin(), escaped, 5, 16),
+                5,
+                0,
+            ));
+            assert_eq!(strokes(*character), expected);
+        }
+    }
+    #[test]
+    fn unknown_characters_show_their_code_point() {
+        let tofu = strokes('中');
+        assert_eq!(tofu[0], vec![(0, 0), (9, 0), (9, 15), (0, 15), (0, 0)]);
+        // 4E2D in two rows of two digits, each 3 by 6 in a 4 by 7 cell.
+        let mut expected = vec![tofu[0].clone()];
+        for (digit, left, top) in &[('4', 1, 1), ('E', 5, 1), ('2', 1, 8), ('D', 5, 8)] {
+            expected.append(&mut offset(
+                get_character_strokes(&Font::builtin(), digit, 3, 6),
+                *left,
+                *top,
+            ));
+        }
+        assert_eq!(tofu, expected);
+    }
+    #[test]
+    fn code_points_above_the_basic_plane_use_six_digits() {
+        let tofu = strokes('\u{1f600}');
+        let mut expected = vec![tofu[0].clone()];
+        for (index, digit) in "01F600".chars().enumerate() {
+            let (left, top) = (1 + (index as i32 % 3) * 2, 1 + (index as i32 / 3) * 7);
+            expected.append(&mut offset(
+                get_character_strokes(&Font::builtin(), &digit, 1, 6),
+                left,
+                top,
+            ));
+        }
+        assert_eq!(tofu, expected);
+    }
+    #[test]
+    fn ascii_is_a_single_stroke() {
+        assert_eq!(strokes('a').len(), 1);
+        assert_eq!(strokes('L').len(), 1);
+    }
+    #[test]
+    fn no_ascii_segment_is_drawn_twice() {
+        for character in '!'..='~' {
+            let mut segments = vec![];
+            for stroke in strokes(character) {
+                for pair in stroke.windows(2) {
+                    let (from, to) = (pair[0], pair[1]);
+                    assert!(
+                        !segments.contains(&(from, to)) && !segments.contains(&(to, from)),
+                        "{}",
+                        character
+                    );
+                    segments.push((from, to));
+                }
+            }
+        }
+    }
+    #[test]
+    fn dots_are_separate_strokes() {
+        assert_eq!(strokes('i').len(), 3);
+        assert_eq!(strokes('j').len(), 2);
+        assert_eq!(
+            strokes(':'),
+            vec![vec![(5, 8), (5, 6)], vec![(5, 16), (5, 14)]]
+        );
+        assert_eq!(strokes('!')[1], strokes('.')[0]);
+    }
+    #[test]
+    fn curves_start_and_end_at_their_points() {
+        let curve = get_curve((0, 0), (8, 0), (8, 8));
+        assert_eq!(curve.first(), Some(&(0, 0)));
+        assert_eq!(curve.last(), Some(&(8, 8)));
+        assert_eq!(curve[4], (6, 2));
+        assert_eq!(strokes('(').len(), 1);
+        assert!(strokes('(')[0].len() > 4);
+    }
+    #[test]
+    fn accents_are_added_to_the_base() {
+        let base = strokes('a');
+        let accented = strokes('ä');
+        assert_eq!(accented.len(), base.len() + 2);
+        assert_eq!(accented[0], base[0]);
+        assert_ne!(strokes('é'), strokes('è'));
+    }
+    #[test]
+    fn accented_capitals_stay_in_the_cell() {
+        for character in "ÀÉÎÕÜÅÑЁЙ".chars() {
+            for (x, y) in strokes(character).into_iter().flatten() {
+                assert!((0..=10).contains(&x), "{}", character);
+                assert!((0..=16).contains(&y), "{}", character);
+            }
+        }
+    }
+    #[test]
+    fn cedilla_keeps_the_capital_size() {
+        assert_eq!(strokes('Ç')[0], strokes('C')[0]);
+        assert!(strokes('Ç')[1].iter().any(|(_, y)| *y > 16));
+    }
+    #[test]
+    fn lookalikes_share_glyphs() {
+        assert_eq!(strokes('Α'), strokes('A'));
+        assert_eq!(strokes('о'), strokes('o'));
+        assert_eq!(strokes('П'), strokes('Π'));
+    }
+    #[test]
+    fn cyrillic_lowercase_is_a_small_capital() {
+        for (x, y) in strokes('и').into_iter().flatten() {
+            assert!((0..=10).contains(&x));
+            assert!((8..=16).contains(&y));
+        }
+    }
+    #[test]
+    fn box_drawing_reaches_into_the_gaps() {
+        let xs = strokes('─')
+            .into_iter()
+            .flatten()
+            .map(|(x, _)| x)
+            .collect::<Vec<i32>>();
+        assert!(xs.contains(&-1) && xs.contains(&11));
+        assert_eq!(strokes('─').len(), 2);
+        assert_eq!(strokes('━').len(), 6);
+        assert_eq!(strokes('╬').len(), 8);
+        assert_eq!(strokes('┼').len(), 4);
+    }
+}
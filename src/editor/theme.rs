@@ -0,0 +1,313 @@
+pub mod theme {
+    use super::super::config::config::{
+        config_directory, get_color, get_string, parse, ConfigError, Entry,
+    };
+    use super::super::highlighting::highlighting::TokenKind;
+    use sdl2::pixels::Color;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Colors {
+        pub background: Color,
+        pub text: Color,
+        pub cursor: Color,
+        pub cursor_extender: Color,
+        pub cursor_selection: Color,
+        pub gutter: Color,
+        pub status_bar: Color,
+        pub status_bar_text: Color,
+    }
+
+    /// Colors of the syntax highlighting token kinds, plain text uses `Colors::text`.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Syntax {
+        pub keyword: Color,
+        pub type_name: Color,
+        pub constant: Color,
+        pub string: Color,
+        pub number: Color,
+        pub comment: Color,
+        pub punctuation: Color,
+        pub heading: Color,
+        pub key: Color,
+        pub variable: Color,
+        pub code: Color,
+        pub emphasis: Color,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Theme {
+        pub name: String,
+        pub colors: Colors,
+        pub syntax: Syntax,
+    }
+
+    impl Default for Theme {
+        fn default() -> Theme {
+            Theme::dark()
+        }
+    }
+
+    impl Theme {
+        pub fn dark() -> Theme {
+            Theme {
+                name: String::from("dark"),
+                colors: Colors {
+                    background: Color::RGB(24, 24, 28),
+                    text: Color::RGB(200, 200, 200),
+                    cursor: Color::RGB(240, 240, 240),
+                    cursor_extender: Color::RGB(135, 200, 200),
+                    cursor_selection: Color::RGB(250, 150, 100),
+                    gutter: Color::RGB(100, 100, 110),
+                    status_bar: Color::RGB(50, 50, 60),
+                    status_bar_text: Color::RGB(200, 200, 200),
+                },
+                syntax: Syntax {
+                    keyword: Color::RGB(190, 110, 190),
+                    type_name: Color::RGB(110, 170, 210),
+                    constant: Color::RGB(210, 150, 80),
+                    string: Color::RGB(140, 190, 100),
+                    number: Color::RGB(210, 150, 80),
+                    comment: Color::RGB(100, 130, 100),
+                    punctuation: Color::RGB(150, 150, 150),
+                    heading: Color::RGB(220, 190, 110),
+                    key: Color::RGB(110, 170, 210),
+                    variable: Color::RGB(210, 110, 110),
+                    code: Color::RGB(140, 190, 100),
+                    emphasis: Color::RGB(230, 230, 230),
+                },
+            }
+        }
+
+        pub fn light() -> Theme {
+            Theme {
+                name: String::from("light"),
+                colors: Colors {
+                    background: Color::RGB(250, 250, 245),
+                    text: Color::RGB(40, 40, 40),
+                    cursor: Color::RGB(0, 0, 0),
+                    cursor_extender: Color::RGB(0, 120, 160),
+                    cursor_selection: Color::RGB(230, 120, 60),
+                    gutter: Color::RGB(150, 150, 150),
+                    status_bar: Color::RGB(225, 225, 220),
+                    status_bar_text: Color::RGB(40, 40, 40),
+                },
+                syntax: Syntax {
+                    keyword: Color::RGB(150, 40, 150),
+                    type_name: Color::RGB(20, 90, 160),
+                    constant: Color::RGB(170, 90, 0),
+                    string: Color::RGB(40, 120, 30),
+                    number: Color::RGB(170, 90, 0),
+                    comment: Color::RGB(120, 140, 120),
+                    punctuation: Color::RGB(90, 90, 90),
+                    heading: Color::RGB(160, 90, 0),
+                    key: Color::RGB(20, 90, 160),
+                    variable: Color::RGB(180, 40, 40),
+                    code: Color::RGB(40, 120, 30),
+                    emphasis: Color::RGB(0, 0, 0),
+                },
+            }
+        }
+
+        pub fn high_contrast() -> Theme {
+            Theme {
+                name: String::from("high-contrast"),
+                colors: Colors {
+                    background: Color::RGB(0, 0, 0),
+                    text: Color::RGB(255, 255, 255),
+                    cursor: Color::RGB(255, 255, 0),
+                    cursor_extender: Color::RGB(0, 255, 255),
+                    cursor_selection: Color::RGB(255, 128, 0),
+                    gutter: Color::RGB(200, 200, 200),
+                    status_bar: Color::RGB(255, 255, 255),
+                    status_bar_text: Color::RGB(0, 0, 0),
+                },
+                syntax: Syntax {
+                    keyword: Color::RGB(255, 128, 255),
+                    type_name: Color::RGB(128, 200, 255),
+                    constant: Color::RGB(255, 200, 0),
+                    string: Color::RGB(128, 255, 128),
+                    number: Color::RGB(255, 200, 0),
+                    comment: Color::RGB(190, 190, 190),
+                    punctuation: Color::RGB(255, 255, 255),
+                    heading: Color::RGB(255, 255, 0),
+                    key: Color::RGB(128, 200, 255),
+                    variable: Color::RGB(255, 128, 128),
+                    code: Color::RGB(128, 255, 128),
+                    emphasis: Color::RGB(255, 255, 255),
+                },
+            }
+        }
+
+        /// The original grey on black colors.
+        pub fn classic() -> Theme {
+            Theme {
+                name: String::from("classic"),
+                colors: Colors {
+                    background: Color::RGB(0, 0, 0),
+                    text: Color::RGB(80, 80, 80),
+                    cursor: Color::RGB(200, 200, 200),
+                    cursor_extender: Color::RGB(135, 200, 200),
+                    cursor_selection: Color::RGB(250, 150, 100),
+                    gutter: Color::RGB(80, 80, 80),
+                    status_bar: Color::RGB(0, 0, 0),
+                    status_bar_text: Color::RGB(135, 200, 200),
+                },
+                syntax: Theme::dark().syntax,
+            }
+        }
+
+        pub fn presets() -> Vec<Theme> {
+            vec![
+                Theme::dark(),
+                Theme::light(),
+                Theme::high_contrast(),
+                Theme::classic(),
+            ]
+        }
+
+        /// The color of a syntax highlighting token kind.
+        pub fn get_token_color(&self, kind: TokenKind) -> Color {
+            let syntax = &self.syntax;
+            match kind {
+                TokenKind::Plain => self.colors.text,
+                TokenKind::Keyword => syntax.keyword,
+                TokenKind::Type => syntax.type_name,
+                TokenKind::Constant => syntax.constant,
+                TokenKind::String => syntax.string,
+                TokenKind::Number => syntax.number,
+                TokenKind::Comment => syntax.comment,
+                TokenKind::Punctuation => syntax.punctuation,
+                TokenKind::Heading => syntax.heading,
+                TokenKind::Key => syntax.key,
+                TokenKind::Variable => syntax.variable,
+                TokenKind::Code => syntax.code,
+                TokenKind::Emphasis => syntax.emphasis,
+            }
+        }
+
+        /// Sets the color of a `[colors]` or `[syntax]` entry.
+        pub fn apply(&mut self, entry: &Entry) -> Result<(), ConfigError> {
+            let colors = &mut self.colors;
+            let syntax = &mut self.syntax;
+            let color = match (entry.section.as_str(), entry.key.as_str()) {
+                ("colors", "background") => &mut colors.background,
+                ("colors", "text") => &mut colors.text,
+                ("colors", "cursor") => &mut colors.cursor,
+                ("colors", "cursor_extender") => &mut colors.cursor_extender,
+                ("colors", "cursor_selection") => &mut colors.cursor_selection,
+                ("colors", "gutter") => &mut colors.gutter,
+                ("colors", "status_bar") => &mut colors.status_bar,
+                ("colors", "status_bar_text") => &mut colors.status_bar_text,
+                ("syntax", "keyword") => &mut syntax.keyword,
+                ("syntax", "type") => &mut syntax.type_name,
+                ("syntax", "constant") => &mut syntax.constant,
+                ("syntax", "string") => &mut syntax.string,
+                ("syntax", "number") => &mut syntax.number,
+                ("syntax", "comment") => &mut syntax.comment,
+                ("syntax", "punctuation") => &mut syntax.punctuation,
+                ("syntax", "heading") => &mut syntax.heading,
+                ("syntax", "key") => &mut syntax.key,
+                ("syntax", "variable") => &mut syntax.variable,
+                ("syntax", "code") => &mut syntax.code,
+                ("syntax", "emphasis") => &mut syntax.emphasis,
+                _ => return Err(ConfigError::for_entry(entry, "unknown key")),
+            };
+            *color = get_color(entry)?;
+            Ok(())
+        }
+
+        /// Parses a theme file: an optional `base` theme to start from,
+        /// followed by `[colors]` and `[syntax]` like in the configuration.
+        /// Without a base, a theme named like one of `themes` changes that
+        /// one, any other starts from the dark theme.
+        pub fn from_str(name: &str, content: &str, themes: &[Theme]) -> Result<Theme, ConfigError> {
+            let entries = parse(content)?;
+            let mut theme = themes
+                .iter()
+                .find(|theme| theme.name == name)
+                .cloned()
+                .unwrap_or_else(Theme::dark);
+            for entry in entries.iter().filter(|entry| entry.section.is_empty()) {
+                match entry.key.as_str() {
+                    "base" => theme = find_theme(themes, entry)?.clone(),
+                    _ => return Err(ConfigError::for_entry(entry, "unknown key")),
+                }
+            }
+            for entry in entries.iter().filter(|entry| !entry.section.is_empty()) {
+                theme.apply(entry)?;
+            }
+            theme.name = name.to_string();
+            Ok(theme)
+        }
+
+        /// The presets followed by the user's themes, which replace presets
+        /// of the same name.
+        pub fn load_all() -> Result<Vec<Theme>, String> {
+            match themes_directory() {
+                Some(directory) => Theme::load_all_from(&directory),
+                None => Ok(Theme::presets()),
+            }
+        }
+
+        /// The presets followed by the themes in `directory`, if there is
+        /// one.
+        pub fn load_all_from(directory: &Path) -> Result<Vec<Theme>, String> {
+            let mut themes = Theme::presets();
+            if !directory.is_dir() {
+                return Ok(themes);
+            }
+            let mut paths = fs::read_dir(directory)
+                .map_err(|error| format!("could not read {}: {}", directory.display(), error))?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|extension| extension == "toml")
+                })
+                .collect::<Vec<PathBuf>>();
+            paths.sort();
+            for path in paths {
+                let name = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let theme = fs::read_to_string(&path)
+                    .map_err(|error| error.to_string())
+                    .and_then(|content| {
+                        Theme::from_str(&name, &content, &themes).map_err(|error| error.to_string())
+                    })
+                    .map_err(|error| format!("in {}: {}", path.display(), error))?;
+                match themes.iter().position(|existing| existing.name == name) {
+                    Some(index) => themes[index] = theme,
+                    None => themes.push(theme),
+                }
+            }
+            Ok(themes)
+        }
+    }
+
+    /// Looks up the theme named by the string value of `entry`.
+    pub fn find_theme<'a>(themes: &'a [Theme], entry: &Entry) -> Result<&'a Theme, ConfigError> {
+        let name = get_string(entry)?;
+        themes
+            .iter()
+            .find(|theme| theme.name == name)
+            .ok_or_else(|| {
+                let names = themes
+                    .iter()
+                    .map(|theme| format!("\"{}\"", theme.name))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                ConfigError::for_entry(
+                    entry,
+                    &format!("unknown theme \"{}\", expected one of {}", name, names),
+                )
+            })
+    }
+
+    /// `themes` next to the configuration file, one `<name>.toml` per theme.
+    pub fn themes_directory() -> Option<PathBuf> {
+        config_directory().map(|directory| directory.join("themes"))
+    }
+}
@@ -0,0 +1,126 @@
+#[path = "theme.rs"]
+mod theme;
+
+#[path = "config.rs"]
+mod config;
+
+#[path = "commands.rs"]
+mod commands;
+
+#[path = "cursor.rs"]
+mod cursor;
+
+#[path = "kill_ring.rs"]
+mod kill_ring;
+
+#[path = "keymap.rs"]
+mod keymap;
+
+#[path = "font.rs"]
+mod font;
+
+#[path = "highlighting.rs"]
+mod highlighting;
+
+#[cfg(test)]
+mod tests {
+    use super::theme::theme::Theme;
+    use sdl2::pixels::Color;
+    use std::env;
+    use std::fs;
+    #[test]
+    fn presets_have_distinct_names_and_colors() {
+        let presets = Theme::presets();
+        for (index, theme) in presets.iter().enumerate() {
+            for other in presets[index + 1..].iter() {
+                assert_ne!(theme.name, other.name);
+                assert_ne!(theme.colors, other.colors);
+            }
+        }
+    }
+    #[test]
+    fn presets_text_contrasts_with_background() {
+        for theme in Theme::presets() {
+            let background = theme.colors.background;
+            let text = theme.colors.text;
+            let difference = (background.r as i32 - text.r as i32).abs()
+                + (background.g as i32 - text.g as i32).abs()
+                + (background.b as i32 - text.b as i32).abs();
+            assert!(difference >= 3 * 80, "{}", theme.name);
+        }
+    }
+    #[test]
+    fn from_str_starts_from_dark() {
+        let theme =
+            Theme::from_str("mine", "[colors]\ntext = \"#102030\"", &Theme::presets()).unwrap();
+        assert_eq!(theme.name, "mine");
+        assert_eq!(theme.colors.text, Color::RGB(0x10, 0x20, 0x30));
+        assert_eq!(theme.colors.background, Theme::dark().colors.background);
+        assert_eq!(theme.syntax, Theme::dark().syntax);
+    }
+    #[test]
+    fn from_str_with_base() {
+        let theme = Theme::from_str(
+            "paper",
+            "base = \"light\"\n\n[syntax]\nkeyword = [1, 2, 3]",
+            &Theme::presets(),
+        )
+        .unwrap();
+        assert_eq!(theme.colors, Theme::light().colors);
+        assert_eq!(theme.syntax.keyword, Color::RGB(1, 2, 3));
+        assert_eq!(theme.syntax.string, Theme::light().syntax.string);
+    }
+    #[test]
+    fn from_str_errors() {
+        let presets = Theme::presets();
+        let error = Theme::from_str("x", "base = \"sepia\"", &presets).unwrap_err();
+        assert_eq!(error.key, "base");
+        let error =
+            Theme::from_str("x", "[syntax]\n\nstrings = \"#000000\"", &presets).unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.key, "syntax.strings");
+        let error = Theme::from_str("x", "name = \"y\"", &presets).unwrap_err();
+        assert_eq!(error.message, "unknown key");
+    }
+    #[test]
+    fn load_all_from_themes_directory() {
+        let themes_directory =
+            env::temp_dir().join(format!("editor-themes-{}", std::process::id()));
+        assert_eq!(
+            Theme::load_all_from(&themes_directory).unwrap(),
+            Theme::presets()
+        );
+        fs::create_dir_all(&themes_directory).unwrap();
+        fs::write(
+            themes_directory.join("light.toml"),
+            "[colors]\ntext = \"#000000\"",
+        )
+        .unwrap();
+        fs::write(themes_directory.join("night.toml"), "base = \"light\"").unwrap();
+        fs::write(themes_directory.join("notes.txt"), "ignored").unwrap();
+        let themes = Theme::load_all_from(&themes_directory).unwrap();
+        let names = themes
+            .iter()
+            .map(|theme| theme.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            names,
+            vec!["dark", "light", "high-contrast", "classic", "night"]
+        );
+        assert_eq!(themes[1].colors.text, Color::RGB(0, 0, 0));
+        assert_eq!(
+            themes[1].colors.background,
+            Theme::light().colors.background
+        );
+        assert_eq!(
+            themes[4].colors.background,
+            Theme::light().colors.background
+        );
+
+        fs::write(themes_directory.join("broken.toml"), "[colors]\ntext = 3").unwrap();
+        let error = Theme::load_all_from(&themes_directory).unwrap_err();
+        assert!(error.contains("broken.toml"));
+        assert!(error.contains("line 2"));
+        fs::remove_dir_all(&themes_directory).unwrap();
+    }
+}
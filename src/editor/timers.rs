@@ -0,0 +1,156 @@
+pub mod timers {
+    use std::collections::VecDeque;
+    use std::time::{Duration, Instant};
+
+    /// Fires once `interval` after it was started, for things like autosave.
+    pub struct Timer {
+        interval: Duration,
+        deadline: Option<Instant>,
+    }
+
+    impl Timer {
+        pub fn new(interval: Duration) -> Timer {
+            Timer {
+                interval,
+                deadline: None,
+            }
+        }
+        pub fn is_running(&self) -> bool {
+            self.deadline.is_some()
+        }
+        /// Starts the interval over from `now`, also if it is running.
+        pub fn start(&mut self, now: Instant) {
+            self.deadline = Some(now + self.interval);
+        }
+        pub fn stop(&mut self) {
+            self.deadline = None;
+        }
+        pub fn deadline(&self) -> Option<Instant> {
+            self.deadline
+        }
+        /// Whether the deadline passed, stopping the timer if so.
+        pub fn fire(&mut self, now: Instant) -> bool {
+            match self.deadline {
+                Some(deadline) if deadline <= now => {
+                    self.deadline = None;
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    /// Turns the caret on and off every interval, and on again after every
+    /// keystroke.
+    pub struct Blink {
+        /// `None` if the caret does not blink.
+        timer: Option<Timer>,
+        pub is_on: bool,
+    }
+
+    impl Blink {
+        pub fn new(interval: Option<Duration>, now: Instant) -> Blink {
+            let mut blink = Blink {
+                timer: interval.map(Timer::new),
+                is_on: true,
+            };
+            blink.reset(now);
+            blink
+        }
+        /// Turns the caret on for a whole interval.
+        pub fn reset(&mut self, now: Instant) {
+            self.is_on = true;
+            if let Some(timer) = self.timer.as_mut() {
+                timer.start(now);
+            }
+        }
+        /// Keeps the caret on without deadlines until the next `reset`.
+        pub fn pause(&mut self) {
+            self.is_on = true;
+            if let Some(timer) = self.timer.as_mut() {
+                timer.stop();
+            }
+        }
+        pub fn deadline(&self) -> Option<Instant> {
+            self.timer.as_ref().and_then(Timer::deadline)
+        }
+        /// Whether the caret was turned on or off.
+        pub fn update(&mut self, now: Instant) -> bool {
+            let timer = match self.timer.as_mut() {
+                Some(timer) => timer,
+                None => return false,
+            };
+            if !timer.fire(now) {
+                return false;
+            }
+            self.is_on = !self.is_on;
+            timer.start(now);
+            true
+        }
+    }
+
+    /// Keeps redraws at most `frames_per_second` apart.
+    pub struct FrameLimiter {
+        frame_time: Duration,
+        last_frame: Option<Instant>,
+    }
+
+    impl FrameLimiter {
+        pub fn new(frames_per_second: u32) -> FrameLimiter {
+            FrameLimiter {
+                frame_time: Duration::from_secs(1) / frames_per_second.max(1),
+                last_frame: None,
+            }
+        }
+        /// When the next frame may be drawn.
+        pub fn next_frame(&self) -> Option<Instant> {
+            self.last_frame
+                .map(|last_frame| last_frame + self.frame_time)
+        }
+        pub fn is_ready(&self, now: Instant) -> bool {
+            self.next_frame().is_none_or(|next_frame| next_frame <= now)
+        }
+        pub fn record_frame(&mut self, now: Instant) {
+            self.last_frame = Some(now);
+        }
+    }
+
+    /// How long the last few frames took to draw.
+    pub struct FrameTimes {
+        times: VecDeque<Duration>,
+        capacity: usize,
+    }
+
+    impl FrameTimes {
+        pub fn new(capacity: usize) -> FrameTimes {
+            FrameTimes {
+                times: VecDeque::new(),
+                capacity: capacity.max(1),
+            }
+        }
+        /// Forgets the oldest frame once there are `capacity` of them.
+        pub fn record(&mut self, time: Duration) {
+            if self.times.len() == self.capacity {
+                self.times.pop_front();
+            }
+            self.times.push_back(time);
+        }
+        /// `None` before the first frame.
+        pub fn average(&self) -> Option<Duration> {
+            if self.times.is_empty() {
+                return None;
+            }
+            Some(self.times.iter().sum::<Duration>() / self.times.len() as u32)
+        }
+    }
+
+    /// How long to wait for events before the first of `deadlines` is due,
+    /// `None` to wait until an event arrives.
+    pub fn get_timeout(now: Instant, deadlines: &[Option<Instant>]) -> Option<Duration> {
+        deadlines
+            .iter()
+            .filter_map(|deadline| *deadline)
+            .min()
+            .map(|deadline| deadline.saturating_duration_since(now))
+    }
+}
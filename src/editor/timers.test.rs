@@ -0,0 +1,107 @@
+#[path = "timers.rs"]
+mod timers;
+
+#[cfg(test)]
+mod tests {
+    use super::timers::timers::{get_timeout, Blink, FrameLimiter, FrameTimes, Timer};
+    use std::time::{Duration, Instant};
+    fn milliseconds(milliseconds: u64) -> Duration {
+        Duration::from_millis(milliseconds)
+    }
+    #[test]
+    fn timer_fires_once_after_its_interval() {
+        let now = Instant::now();
+        let mut timer = Timer::new(milliseconds(100));
+        assert!(!timer.is_running());
+        assert!(!timer.fire(now + milliseconds(500)));
+        timer.start(now);
+        assert_eq!(timer.deadline(), Some(now + milliseconds(100)));
+        assert!(!timer.fire(now + milliseconds(99)));
+        assert!(timer.fire(now + milliseconds(100)));
+        assert!(!timer.is_running());
+        assert!(!timer.fire(now + milliseconds(200)));
+    }
+    #[test]
+    fn starting_again_moves_the_deadline() {
+        let now = Instant::now();
+        let mut timer = Timer::new(milliseconds(100));
+        timer.start(now);
+        timer.start(now + milliseconds(50));
+        assert!(!timer.fire(now + milliseconds(120)));
+        assert!(timer.fire(now + milliseconds(150)));
+        assert_eq!(timer.deadline(), None);
+        timer.start(now);
+        timer.stop();
+        assert!(!timer.is_running());
+    }
+    #[test]
+    fn caret_blinks_and_turns_on_after_keystrokes() {
+        let now = Instant::now();
+        let mut blink = Blink::new(Some(milliseconds(500)), now);
+        assert!(blink.is_on);
+        assert!(!blink.update(now + milliseconds(499)));
+        assert!(blink.update(now + milliseconds(500)));
+        assert!(!blink.is_on);
+        blink.reset(now + milliseconds(600));
+        assert!(blink.is_on);
+        assert_eq!(blink.deadline(), Some(now + milliseconds(1100)));
+        assert!(blink.update(now + milliseconds(1100)));
+        assert!(!blink.is_on);
+        blink.pause();
+        assert!(blink.is_on);
+        assert_eq!(blink.deadline(), None);
+        assert!(!blink.update(now + milliseconds(5000)));
+    }
+    #[test]
+    fn caret_without_interval_stays_on() {
+        let now = Instant::now();
+        let mut blink = Blink::new(None, now);
+        assert_eq!(blink.deadline(), None);
+        assert!(!blink.update(now + milliseconds(5000)));
+        assert!(blink.is_on);
+    }
+    #[test]
+    fn frames_are_kept_apart() {
+        let now = Instant::now();
+        let mut limiter = FrameLimiter::new(10);
+        assert!(limiter.is_ready(now));
+        assert_eq!(limiter.next_frame(), None);
+        limiter.record_frame(now);
+        assert!(!limiter.is_ready(now + milliseconds(99)));
+        assert!(limiter.is_ready(now + milliseconds(100)));
+        assert_eq!(limiter.next_frame(), Some(now + milliseconds(100)));
+    }
+    #[test]
+    fn frame_times_average_the_last_frames() {
+        let mut times = FrameTimes::new(3);
+        assert_eq!(times.average(), None);
+        times.record(milliseconds(10));
+        assert_eq!(times.average(), Some(milliseconds(10)));
+        times.record(milliseconds(20));
+        times.record(milliseconds(30));
+        assert_eq!(times.average(), Some(milliseconds(20)));
+        times.record(milliseconds(40));
+        assert_eq!(times.average(), Some(milliseconds(30)));
+    }
+    #[test]
+    fn timeout_until_the_first_deadline() {
+        let now = Instant::now();
+        assert_eq!(get_timeout(now, &[]), None);
+        assert_eq!(get_timeout(now, &[None, None]), None);
+        assert_eq!(
+            get_timeout(
+                now,
+                &[
+                    Some(now + milliseconds(30)),
+                    None,
+                    Some(now + milliseconds(20))
+                ]
+            ),
+            Some(milliseconds(20))
+        );
+        assert_eq!(
+            get_timeout(now + milliseconds(50), &[Some(now)]),
+            Some(Duration::from_secs(0))
+        );
+    }
+}
@@ -0,0 +1,521 @@
+pub mod tty {
+    use super::super::commands::commands::{Editor, Outcome};
+    use super::super::config::config::{CaretStyle, Config, Keybindings};
+    use super::super::highlighting::highlighting::{get_grammar_for_path, Highlighter, TokenKind};
+    use super::super::keymap::keymap::{Chord, KeyResult, Modifiers};
+    use super::super::rendering::rendering::{
+        get_camera_line_following_cursor, get_caret_look, get_selection_segments, StatusLine, View,
+    };
+    use super::super::svg::svg::to_svg;
+    use super::super::theme::theme::Theme;
+    use super::super::vim::vim;
+    use super::super::vim::vim::{Input, Vim};
+    use sdl2::pixels::Color;
+    use std::fs;
+    use std::io;
+    use std::mem;
+    use std::os::unix::io::RawFd;
+
+    /// Input decoded from the bytes a terminal sends.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum TerminalEvent {
+        /// A key with the character it types, if it types one.
+        Key { chord: Chord, text: Option<char> },
+        /// Mouse wheel lines, negative towards the top.
+        Scroll(i32),
+    }
+
+    fn key(name: &str, modifiers: Modifiers) -> TerminalEvent {
+        TerminalEvent::Key {
+            chord: Chord::new(name, modifiers),
+            text: None,
+        }
+    }
+
+    /// Modifiers from the xterm parameter, which is one more than the sum of
+    /// shift 1, alt 2 and ctrl 4.
+    fn get_modifiers(parameter: u32) -> Modifiers {
+        let bits = parameter.saturating_sub(1);
+        Modifiers {
+            shift: bits & 1 != 0,
+            alt: bits & 2 != 0,
+            ctrl: bits & 4 != 0,
+        }
+    }
+
+    /// Decodes a control sequence after `ESC [` or `ESC O`, returning the
+    /// event and how many bytes it took.
+    fn parse_sequence(input: &[u8]) -> Option<(Option<TerminalEvent>, usize)> {
+        let end = input.iter().position(|byte| (0x40..=0x7e).contains(byte))?;
+        let parameters = String::from_utf8_lossy(&input[..end]);
+        let final_byte = input[end] as char;
+        if parameters.starts_with('<') && (final_byte == 'M' || final_byte == 'm') {
+            let button = parameters[1..]
+                .split(';')
+                .next()
+                .and_then(|button| button.parse::<u32>().ok());
+            let event = match button {
+                Some(64) => Some(TerminalEvent::Scroll(-3)),
+                Some(65) => Some(TerminalEvent::Scroll(3)),
+                _ => None,
+            };
+            return Some((event, end + 1));
+        }
+        let numbers = parameters
+            .split(';')
+            .map(|number| number.parse::<u32>().unwrap_or(1))
+            .collect::<Vec<u32>>();
+        let modifiers = get_modifiers(numbers.get(1).cloned().unwrap_or(1));
+        let name = match final_byte {
+            'A' => "up",
+            'B' => "down",
+            'C' => "right",
+            'D' => "left",
+            'H' => "home",
+            'F' => "end",
+            'Z' => return Some((Some(key("tab", get_modifiers(2))), end + 1)),
+            '~' => match numbers[0] {
+                1 | 7 => "home",
+                2 => "insert",
+                3 => "delete",
+                4 | 8 => "end",
+                5 => "page_up",
+                6 => "page_down",
+                _ => return Some((None, end + 1)),
+            },
+            _ => return Some((None, end + 1)),
+        };
+        Some((Some(key(name, modifiers)), end + 1))
+    }
+
+    /// A key typed without escape sequences.
+    fn parse_character(character: char) -> TerminalEvent {
+        let control = |name: &str| {
+            key(
+                name,
+                Modifiers {
+                    ctrl: true,
+                    ..Modifiers::default()
+                },
+            )
+        };
+        match character {
+            '\r' | '\n' => key("return", Modifiers::default()),
+            '\t' => TerminalEvent::Key {
+                chord: Chord::new("tab", Modifiers::default()),
+                text: Some('\t'),
+            },
+            '\u{7f}' | '\u{8}' => key("backspace", Modifiers::default()),
+            '\u{0}' => control("space"),
+            '\u{1}'..='\u{1a}' => control(&((character as u8 - 1 + b'a') as char).to_string()),
+            '\u{1c}'..='\u{1f}' => control(&((character as u8 + 0x40) as char).to_string()),
+            ' ' => TerminalEvent::Key {
+                chord: Chord::new("space", Modifiers::default()),
+                text: Some(' '),
+            },
+            _ => TerminalEvent::Key {
+                chord: Chord::new(
+                    &character.to_lowercase().to_string(),
+                    Modifiers {
+                        shift: character.is_uppercase(),
+                        ..Modifiers::default()
+                    },
+                ),
+                text: Some(character),
+            },
+        }
+    }
+
+    /// Splits what was read from the terminal into events. An escape at the
+    /// end of the input is the escape key, one before another key holds alt.
+    pub fn parse_events(input: &[u8]) -> Vec<TerminalEvent> {
+        let mut events = vec![];
+        let mut index = 0;
+        while index < input.len() {
+            if input[index] == 0x1b {
+                match input.get(index + 1) {
+                    None => {
+                        events.push(key("escape", Modifiers::default()));
+                        index += 1;
+                    }
+                    Some(b'[') | Some(b'O') => match parse_sequence(&input[index + 2..]) {
+                        Some((event, length)) => {
+                            events.extend(event);
+                            index += 2 + length;
+                        }
+                        None => index = input.len(),
+                    },
+                    Some(0x1b) => {
+                        events.push(key("escape", Modifiers::default()));
+                        index += 1;
+                    }
+                    Some(_) => {
+                        let mut rest = parse_events(&input[index + 1..]).into_iter();
+                        if let Some(TerminalEvent::Key { chord, .. }) = rest.next() {
+                            let mut modifiers = chord.modifiers;
+                            modifiers.alt = true;
+                            events.push(key(&chord.key, modifiers));
+                        }
+                        events.extend(rest);
+                        index = input.len();
+                    }
+                }
+                continue;
+            }
+            let length = match input[index] {
+                0x00..=0x7f => 1,
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                _ => 4,
+            };
+            let bytes = &input[index..(index + length).min(input.len())];
+            if let Some(character) = std::str::from_utf8(bytes)
+                .ok()
+                .and_then(|text| text.chars().next())
+            {
+                events.push(parse_character(character));
+            }
+            index += length;
+        }
+        events
+    }
+
+    fn set_colors(foreground: Color, background: Color) -> String {
+        format!(
+            "\x1b[38;2;{};{};{};48;2;{};{};{}m",
+            foreground.r, foreground.g, foreground.b, background.r, background.g, background.b
+        )
+    }
+
+    /// The escape sequences drawing the text, the selection and the status
+    /// line into `view.lines` rows and a row below them, with the terminal
+    /// cursor at the caret in its style.
+    pub fn draw_screen(view: &View, theme: &Theme) -> String {
+        let colors = &theme.colors;
+        let cursor = view.cursor;
+        let columns = view.columns.max(0) as usize;
+        let selection = get_selection_segments(cursor);
+        let mut screen = String::from("\x1b[?25l");
+        let mut current_colors = None;
+        let mut switch_colors = |screen: &mut String, foreground: Color, background: Color| {
+            if current_colors != Some((foreground, background)) {
+                screen.push_str(&set_colors(foreground, background));
+                current_colors = Some((foreground, background));
+            }
+        };
+        for row in 0..view.lines {
+            screen.push_str(&format!("\x1b[{};1H", row + 1));
+            let line = view.camera_line.max(0) as usize + row;
+            if let Some(text) = cursor.lines.get(line) {
+                let spans = view.highlighter.spans(line);
+                for (column, character) in text.chars().enumerate().take(columns) {
+                    let is_selected = selection.iter().any(|(selected, from, to)| {
+                        *selected == line && *from <= column && column < *to
+                    });
+                    let (foreground, background) = if is_selected {
+                        (colors.background, colors.cursor_selection)
+                    } else {
+                        let kind = spans
+                            .iter()
+                            .find(|span| span.start <= column && column < span.end)
+                            .map_or(TokenKind::Plain, |span| span.kind);
+                        (theme.get_token_color(kind), colors.background)
+                    };
+                    switch_colors(&mut screen, foreground, background);
+                    screen.push(if character.is_control() {
+                        ' '
+                    } else {
+                        character
+                    });
+                }
+            }
+            switch_colors(&mut screen, colors.text, colors.background);
+            screen.push_str("\x1b[K");
+        }
+
+        if let Some(status_line) = view.status_line.as_ref() {
+            screen.push_str(&format!("\x1b[{};1H", view.lines + 1));
+            switch_colors(&mut screen, colors.status_bar_text, colors.status_bar);
+            let right = status_line.right.chars().count();
+            let left = status_line
+                .left
+                .chars()
+                .take(columns.saturating_sub(right + 1))
+                .collect::<String>();
+            let gap = columns.saturating_sub(left.chars().count() + right);
+            screen.push_str(&left);
+            screen.push_str(&" ".repeat(gap));
+            if right < columns {
+                screen.push_str(&status_line.right);
+            }
+        }
+        screen.push_str("\x1b[0m");
+
+        let caret = &cursor.extender;
+        let row = caret.line as i32 - view.camera_line;
+        if let (Some(look), true) = (view.caret, row >= 0 && (row as usize) < view.lines) {
+            let shape = match look.style {
+                CaretStyle::Block => 2,
+                CaretStyle::Underline => 4,
+                CaretStyle::Bar => 6,
+            };
+            screen.push_str(&format!(
+                "\x1b[{};{}H\x1b[{} q\x1b[?25h",
+                row + 1,
+                caret.column.min(columns) + 1,
+                shape
+            ));
+        }
+        screen
+    }
+
+    /// Raw mode on the alternate screen with mouse wheel reporting, set
+    /// back when dropped.
+    pub struct Terminal {
+        input: RawFd,
+        output: RawFd,
+        original: libc::termios,
+    }
+
+    impl Terminal {
+        pub fn open(input: RawFd, output: RawFd) -> io::Result<Terminal> {
+            let original = unsafe {
+                let mut original: libc::termios = mem::zeroed();
+                if libc::tcgetattr(input, &mut original) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                let mut raw = original;
+                libc::cfmakeraw(&mut raw);
+                if libc::tcsetattr(input, libc::TCSANOW, &raw) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                original
+            };
+            let terminal = Terminal {
+                input,
+                output,
+                original,
+            };
+            terminal.write("\x1b[?1049h\x1b[?1000h\x1b[?1006h\x1b[2J")?;
+            Ok(terminal)
+        }
+
+        pub fn write(&self, text: &str) -> io::Result<()> {
+            let mut bytes = text.as_bytes();
+            while !bytes.is_empty() {
+                let written = unsafe {
+                    libc::write(
+                        self.output,
+                        bytes.as_ptr() as *const libc::c_void,
+                        bytes.len(),
+                    )
+                };
+                if written < 0 {
+                    let error = io::Error::last_os_error();
+                    if error.kind() != io::ErrorKind::Interrupted {
+                        return Err(error);
+                    }
+                    continue;
+                }
+                bytes = &bytes[written as usize..];
+            }
+            Ok(())
+        }
+
+        /// Rows and columns, or 24 by 80 if the terminal does not say.
+        pub fn size(&self) -> (usize, usize) {
+            let mut size: libc::winsize = unsafe { mem::zeroed() };
+            let result = unsafe { libc::ioctl(self.output, libc::TIOCGWINSZ, &mut size) };
+            if result == 0 && size.ws_row > 0 && size.ws_col > 0 {
+                (size.ws_row as usize, size.ws_col as usize)
+            } else {
+                (24, 80)
+            }
+        }
+
+        /// Waits up to `timeout_milliseconds` for input and returns what
+        /// arrived, nothing on timeout.
+        pub fn read(&self, timeout_milliseconds: i32) -> io::Result<Vec<u8>> {
+            let mut poll = libc::pollfd {
+                fd: self.input,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            let ready = unsafe { libc::poll(&mut poll, 1, timeout_milliseconds) };
+            if ready < 0 {
+                let error = io::Error::last_os_error();
+                return match error.kind() {
+                    io::ErrorKind::Interrupted => Ok(vec![]),
+                    _ => Err(error),
+                };
+            }
+            if ready == 0 {
+                return Ok(vec![]);
+            }
+            let mut buffer = [0u8; 1024];
+            let read = unsafe {
+                libc::read(
+                    self.input,
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                )
+            };
+            match read {
+                read if read < 0 => Err(io::Error::last_os_error()),
+                0 => Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "the terminal was closed",
+                )),
+                read => Ok(buffer[..read as usize].to_vec()),
+            }
+        }
+    }
+
+    impl Drop for Terminal {
+        fn drop(&mut self) {
+            let _ = self.write("\x1b[0m\x1b[0 q\x1b[?1006l\x1b[?1000l\x1b[?1049l\x1b[?25h");
+            unsafe {
+                libc::tcsetattr(self.input, libc::TCSANOW, &self.original);
+            }
+        }
+    }
+
+    /// Edits `file_path` in the terminal reading keys from `input` and
+    /// drawing to `output`, until `app.quit`.
+    pub fn run(file_path: &str, config: &Config, input: RawFd, output: RawFd) -> io::Result<()> {
+        let terminal = Terminal::open(input, output)?;
+        let mut editor = Editor::from_file(file_path);
+        let mut highlighter = Highlighter::new(get_grammar_for_path(file_path));
+        let mut keymap = config.keymap.clone();
+        let mut theme = config.theme.clone();
+        let mut vim = match config.editing.keybindings {
+            Keybindings::Vim => Some(Vim::new()),
+            Keybindings::Default | Keybindings::Emacs => None,
+        };
+        let mut camera_line: i32 = 0;
+        let mut message: Option<String> = None;
+        let mut drawn_size = None;
+        let mut is_changed = true;
+
+        loop {
+            let (rows, columns) = terminal.size();
+            let text_rows = rows.saturating_sub(1).max(1);
+            if is_changed || drawn_size != Some((rows, columns)) {
+                highlighter.update(&editor.cursor.lines);
+                let cursor = &editor.cursor;
+                let status_line = StatusLine {
+                    left: message.clone().unwrap_or_else(|| match vim.as_ref() {
+                        Some(vim) => vim.mode.label().to_string(),
+                        None => file_path.to_string(),
+                    }),
+                    right: match vim.as_ref() {
+                        Some(vim) if !vim.pending_keys().is_empty() => vim.pending_keys(),
+                        _ => format!(
+                            "{}:{}",
+                            cursor.extender.line + 1,
+                            cursor.extender.column + 1
+                        ),
+                    },
+                };
+                let view = View {
+                    cursor,
+                    highlighter: &highlighter,
+                    camera_line,
+                    lines: text_rows,
+                    columns: columns as i32,
+                    width: 0,
+                    status_line: Some(status_line),
+                    caret: get_caret_look(&config.caret, editor.is_overwrite, true, true),
+                    preedit: None,
+                    stroke_width: 1,
+                    scrollbar: None,
+                    minimap: None,
+                };
+                terminal.write(&draw_screen(&view, &theme))?;
+                drawn_size = Some((rows, columns));
+                is_changed = false;
+            }
+
+            let input = terminal.read(100)?;
+            for event in parse_events(&input) {
+                is_changed = true;
+                message = None;
+                let (chord, text) = match event {
+                    TerminalEvent::Scroll(lines) => {
+                        let last_line = editor.cursor.lines.len() as i32 - 1;
+                        camera_line = (camera_line + lines).max(0).min(last_line.max(0));
+                        continue;
+                    }
+                    TerminalEvent::Key { chord, text } => (chord, text),
+                };
+                if let Some(vim) = vim.as_mut() {
+                    if !chord.modifiers.ctrl
+                        && !chord.modifiers.alt
+                        && vim::is_special_key(&chord.key)
+                    {
+                        vim.handle(&mut editor.cursor, Input::Key(chord.key));
+                        camera_line = get_camera_line_following_cursor(
+                            camera_line,
+                            editor.cursor.extender.line,
+                            text_rows as f32 - 1.0,
+                        );
+                        continue;
+                    }
+                }
+                match keymap.press(chord) {
+                    KeyResult::Command(command) => match editor.execute(command) {
+                        Outcome::Continue | Outcome::Zoom(_) | Outcome::ToggleFrameTime => {}
+                        Outcome::NextTheme => {
+                            theme = config.next_theme(&theme);
+                            message = Some(format!("Theme: {}", theme.name));
+                        }
+                        Outcome::EditGlyph(_) => {
+                            message = Some(String::from("The glyph editor needs a window"))
+                        }
+                        Outcome::ExportSvg => {
+                            highlighter.update(&editor.cursor.lines);
+                            let svg = to_svg(
+                                &editor.cursor,
+                                &highlighter,
+                                &config.layout,
+                                &config.font,
+                                &theme,
+                                &config.export,
+                            );
+                            let path = format!("{}.svg", editor.file_path);
+                            message = Some(match fs::write(&path, svg) {
+                                Ok(()) => format!("Exported to \"{}\"", path),
+                                Err(error) => {
+                                    format!("Exporting to \"{}\" failed: {}", path, error)
+                                }
+                            });
+                        }
+                        Outcome::Saved(result) => {
+                            message = Some(match result {
+                                Ok(()) => format!("Saved \"{}\"", file_path),
+                                Err(error) => format!("Saving \"{}\" failed: {}", file_path, error),
+                            });
+                        }
+                        Outcome::Quit => return Ok(()),
+                    },
+                    KeyResult::Pending => {}
+                    KeyResult::Unbound => {
+                        if let Some(character) = text {
+                            match vim.as_mut() {
+                                Some(vim) => {
+                                    vim.handle(&mut editor.cursor, Input::Character(character))
+                                }
+                                None => editor.add(character),
+                            }
+                        }
+                    }
+                }
+                camera_line = get_camera_line_following_cursor(
+                    camera_line,
+                    editor.cursor.extender.line,
+                    text_rows as f32 - 1.0,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,274 @@
+#[path = "tty.rs"]
+mod tty;
+
+#[path = "svg.rs"]
+mod svg;
+
+#[path = "rendering.rs"]
+mod rendering;
+
+#[path = "scrollbar.rs"]
+mod scrollbar;
+
+#[path = "config.rs"]
+mod config;
+
+#[path = "commands.rs"]
+mod commands;
+
+#[path = "cursor.rs"]
+mod cursor;
+
+#[path = "kill_ring.rs"]
+mod kill_ring;
+
+#[path = "keymap.rs"]
+mod keymap;
+
+#[path = "vim.rs"]
+mod vim;
+
+#[path = "font.rs"]
+mod font;
+
+#[path = "highlighting.rs"]
+mod highlighting;
+
+#[path = "theme.rs"]
+mod theme;
+
+#[path = "text_rendering.rs"]
+mod text_rendering;
+
+#[cfg(test)]
+mod tests {
+    use super::config::config::Config;
+    use super::cursor::cursor::Cursor;
+    use super::highlighting::highlighting::{get_grammar_for_path, Highlighter};
+    use super::keymap::keymap::{Chord, Modifiers};
+    use super::rendering::rendering::{CaretLook, StatusLine, View};
+    use super::theme::theme::Theme;
+    use super::tty::tty::{draw_screen, parse_events, run, TerminalEvent};
+    use std::env;
+    use std::fs;
+    use std::ptr;
+    use std::thread;
+    use std::time::Duration;
+    fn key(name: &str, ctrl: bool, alt: bool, shift: bool) -> TerminalEvent {
+        TerminalEvent::Key {
+            chord: Chord::new(name, Modifiers { ctrl, alt, shift }),
+            text: None,
+        }
+    }
+    fn typed(character: char, shift: bool) -> TerminalEvent {
+        TerminalEvent::Key {
+            chord: Chord::new(
+                &character.to_lowercase().to_string(),
+                Modifiers {
+                    shift,
+                    ..Modifiers::default()
+                },
+            ),
+            text: Some(character),
+        }
+    }
+    fn cursor(lines: Vec<&str>) -> Cursor {
+        Cursor::new(lines.into_iter().map(String::from).collect())
+    }
+    fn screen(cursor: &Cursor, highlighter: &Highlighter, camera_line: i32) -> String {
+        let view = View {
+            cursor,
+            highlighter,
+            camera_line,
+            lines: 2,
+            columns: 10,
+            width: 0,
+            status_line: Some(StatusLine {
+                left: String::from("NORMAL"),
+                right: String::from("1:1"),
+            }),
+            caret: Some(CaretLook::default()),
+            preedit: None,
+            stroke_width: 1,
+            scrollbar: None,
+            minimap: None,
+        };
+        draw_screen(&view, &Theme::dark())
+    }
+    #[test]
+    fn characters_and_control_keys() {
+        assert_eq!(
+            parse_events(b"aB \r\x7f\x13"),
+            vec![
+                typed('a', false),
+                typed('B', true),
+                TerminalEvent::Key {
+                    chord: Chord::new("space", Modifiers::default()),
+                    text: Some(' '),
+                },
+                key("return", false, false, false),
+                key("backspace", false, false, false),
+                key("s", true, false, false),
+            ]
+        );
+        assert_eq!(parse_events("é".as_bytes()), vec![typed('é', false)]);
+        assert_eq!(
+            parse_events(b"\t"),
+            vec![TerminalEvent::Key {
+                chord: Chord::new("tab", Modifiers::default()),
+                text: Some('\t'),
+            }]
+        );
+    }
+    #[test]
+    fn escape_sequences() {
+        assert_eq!(
+            parse_events(b"\x1b[A\x1b[1;2D\x1b[1;5C\x1b[3~\x1b[6;3~\x1bOH"),
+            vec![
+                key("up", false, false, false),
+                key("left", false, false, true),
+                key("right", true, false, false),
+                key("delete", false, false, false),
+                key("page_down", false, true, false),
+                key("home", false, false, false),
+            ]
+        );
+    }
+    #[test]
+    fn escape_alone_or_before_a_key() {
+        assert_eq!(
+            parse_events(b"\x1b"),
+            vec![key("escape", false, false, false)]
+        );
+        assert_eq!(parse_events(b"\x1bf"), vec![key("f", false, true, false)]);
+        assert_eq!(
+            parse_events(b"\x1b\x1b"),
+            vec![
+                key("escape", false, false, false),
+                key("escape", false, false, false)
+            ]
+        );
+    }
+    #[test]
+    fn mouse_wheel() {
+        assert_eq!(
+            parse_events(b"\x1b[<64;3;4M\x1b[<65;3;4M\x1b[<0;3;4M"),
+            vec![TerminalEvent::Scroll(-3), TerminalEvent::Scroll(3)]
+        );
+    }
+    #[test]
+    fn screen_shows_the_lines_from_the_camera() {
+        let cursor = cursor(vec!["ab", "cd", "ef"]);
+        let screen = screen(&cursor, &Highlighter::new(None), 1);
+        let text = screen
+            .split("\x1b[")
+            .filter_map(|part| {
+                part.split_once(|c: char| c.is_ascii_alphabetic())
+                    .map(|(_, text)| text)
+            })
+            .collect::<String>();
+        assert_eq!(text, "cdefNORMAL 1:1");
+        assert!(screen.contains("\x1b[1;1H"));
+        assert!(screen.contains("\x1b[3;1H"));
+        assert!(!screen.contains("\x1b[4;1H"));
+        assert!(!screen.ends_with("\x1b[?25h"));
+    }
+    #[test]
+    fn screen_colors_the_selection_and_places_the_caret() {
+        let mut cursor = cursor(vec!["abc"]);
+        cursor.move_to(0, 1, false);
+        cursor.move_to(0, 2, true);
+        let colors = Theme::dark().colors;
+        let selected = format!(
+            "\x1b[38;2;{};{};{};48;2;{};{};{}mb",
+            colors.background.r,
+            colors.background.g,
+            colors.background.b,
+            colors.cursor_selection.r,
+            colors.cursor_selection.g,
+            colors.cursor_selection.b
+        );
+        let screen = screen(&cursor, &Highlighter::new(None), 0);
+        assert!(screen.contains(&selected));
+        assert!(screen.ends_with("\x1b[1;3H\x1b[6 q\x1b[?25h"));
+    }
+    #[test]
+    fn screen_uses_syntax_colors() {
+        let cursor = cursor(vec!["fn"]);
+        let mut highlighter = Highlighter::new(get_grammar_for_path("a.rs"));
+        highlighter.update(&cursor.lines);
+        let keyword = Theme::dark().syntax.keyword;
+        let screen = screen(&cursor, &highlighter, 0);
+        assert!(screen.contains(&format!(
+            "\x1b[38;2;{};{};{};",
+            keyword.r, keyword.g, keyword.b
+        )));
+    }
+    #[test]
+    fn edits_through_a_pseudo_terminal() {
+        let path = env::temp_dir().join("editor_tty_test.txt");
+        fs::write(&path, "hello").unwrap();
+        let (mut master, mut slave) = (0, 0);
+        let mut size = libc::winsize {
+            ws_row: 10,
+            ws_col: 40,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        let result = unsafe {
+            libc::openpty(
+                &mut master,
+                &mut slave,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                &mut size,
+            )
+        };
+        assert_eq!(result, 0);
+
+        let reader = thread::spawn(move || {
+            let mut output = vec![];
+            let mut buffer = [0u8; 4096];
+            loop {
+                let read = unsafe {
+                    libc::read(
+                        master,
+                        buffer.as_mut_ptr() as *mut libc::c_void,
+                        buffer.len(),
+                    )
+                };
+                if read <= 0 {
+                    break;
+                }
+                output.extend_from_slice(&buffer[..read as usize]);
+            }
+            output
+        });
+        let file_path = path.to_str().unwrap().to_string();
+        let editor = thread::spawn(move || run(&file_path, &Config::default(), slave, slave));
+
+        for input in &[&b"X"[..], b"\t", b"\x13", b"\x1b"] {
+            thread::sleep(Duration::from_millis(150));
+            let written =
+                unsafe { libc::write(master, input.as_ptr() as *const libc::c_void, input.len()) };
+            assert_eq!(written, input.len() as isize);
+        }
+        editor.join().unwrap().unwrap();
+        unsafe {
+            libc::close(slave);
+        }
+        let output = String::from_utf8_lossy(&reader.join().unwrap()).to_string();
+        unsafe {
+            libc::close(master);
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "X\thello");
+        fs::remove_file(&path).unwrap();
+        assert!(output.starts_with("\x1b[?1049h"));
+        assert!(output.contains("\x1b[10;1H"));
+        assert!(
+            output.contains("Saved \""),
+            "the status line reports the save"
+        );
+        assert!(output.contains("\x1b[?1049l"));
+    }
+}
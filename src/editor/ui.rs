@@ -4,366 +4,1016 @@ mod cursor;
 #[path = "text_rendering.rs"]
 mod text_rendering;
 
+#[path = "config.rs"]
+mod config;
+
+#[path = "commands.rs"]
+mod commands;
+
+#[path = "keymap.rs"]
+mod keymap;
+
+#[path = "kill_ring.rs"]
+mod kill_ring;
+
+#[path = "vim.rs"]
+mod vim;
+
+#[path = "highlighting.rs"]
+mod highlighting;
+
+#[path = "theme.rs"]
+mod theme;
+
+#[path = "font.rs"]
+mod font;
+
+#[path = "glyph_editor.rs"]
+mod glyph_editor;
+
+#[path = "rendering.rs"]
+mod rendering;
+
+#[path = "scrollbar.rs"]
+mod scrollbar;
+
+#[path = "rasterizer.rs"]
+mod rasterizer;
+
+#[path = "image_rendering.rs"]
+mod image_rendering;
+
+#[path = "glyph_cache.rs"]
+mod glyph_cache;
+
+#[path = "svg.rs"]
+mod svg;
+
+#[path = "tty.rs"]
+mod tty;
+
+#[path = "timers.rs"]
+mod timers;
+
 pub mod editor {
-    use sdl2::event::Event;
-    use sdl2::keyboard::Keycode;
-    use sdl2::pixels::Color;
-    use sdl2::rect::Point;
-    use sdl2::render::WindowCanvas;
-    use std::collections::HashSet;
-    use std::time::Duration;
-
-    use super::cursor::cursor::Cursor;
-    use super::text_rendering::text_rendering::get_character_coords;
-
-    static CHARACTER_WIDTH: i32 = 10;
-    static CHARACTER_HEIGHT: i32 = 16;
-    static LINE_GAP: i32 = CHARACTER_HEIGHT / 3;
-    static CHARACTER_GAP: i32 = CHARACTER_WIDTH / 3;
-    static CHARACTER_X_OFFSET: i32 = 10;
-    static CHARACTER_Y_OFFSET: i32 = 5;
-
-    static BACKGROUND_COLOR: Color = Color {
-        r: 0,
-        g: 0,
-        b: 0,
-        a: 0xff,
-    };
-    static TEXT_COLOR: Color = Color {
-        r: 80,
-        g: 80,
-        b: 80,
-        a: 0xff,
+    use sdl2::event::{Event, WindowEvent};
+    use sdl2::keyboard::{Keycode, Mod};
+    use sdl2::mouse::MouseButton;
+    use sdl2::pixels::PixelFormatEnum;
+    use sdl2::rect::{Point, Rect};
+    use sdl2::render::{Texture, TextureCreator, WindowCanvas};
+    use sdl2::video::{Window, WindowContext};
+    use sdl2::EventPump;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, Instant};
+
+    use super::commands::commands::{Editor, Outcome, Zoom};
+    use super::config::config::{
+        config_directory, get_character_y, get_columns_in_width, get_display_scale,
+        get_scaled_zoom, get_stroke_width, zoom_in, zoom_out, Config, Keybindings, Layout,
+        StrokeStyle,
     };
-    static CURSOR_COLOR: Color = Color {
-        r: 200,
-        g: 200,
-        b: 200,
-        a: 0xff,
+    use super::font::font::{format_character, Font};
+    use super::glyph_cache::glyph_cache::{CachedCanvas, TextureCache};
+    use super::glyph_editor::glyph_editor::{
+        next_missing, GlyphEditor, GridView, GRID, GRID_MAXIMUM, GRID_MINIMUM,
     };
-    static CURSOR_EXTENDER_COLOR: Color = Color {
-        r: 135,
-        g: 200,
-        b: 200,
-        a: 0xff,
+    use super::highlighting::highlighting::{get_grammar_for_path, Highlighter};
+    use super::image_rendering::image_rendering;
+    use super::image_rendering::image_rendering::ImageRenderer;
+    use super::keymap::keymap::{Chord, KeyResult, Modifiers};
+    use super::rendering::rendering::{
+        draw_frame, draw_text, format_frame_time, get_camera_line_following_cursor, get_caret_cell,
+        get_caret_look, layout_frame, layout_overlay, Frame, Preedit, StatusLine, View,
     };
-    static CURSOR_SELECTION_COLOR: Color = Color {
-        r: 250,
-        g: 150,
-        b: 100,
-        a: 0xff,
+    use super::scrollbar::scrollbar::{
+        get_minimap_layout, get_minimap_width, get_scrollbar_width, Minimap, Scrollbar,
     };
+    use super::svg::svg::to_svg;
+    use super::theme::theme::Colors;
+    use super::timers::timers::{get_timeout, Blink, FrameLimiter, FrameTimes, Timer};
+    use super::tty::tty;
+    use super::vim::vim;
+    use super::vim::vim::{Input, Vim};
+
+    /// How many lines fit below `y_offset`, without the status line if there is one.
+    fn get_text_height_in_lines(layout: &Layout, window_height: u32, has_status_line: bool) -> f32 {
+        let scroll_height_in_lines = (window_height as f32 - layout.y_offset as f32)
+            / (layout.line_gap as f32 + layout.character_height as f32);
+        if has_status_line {
+            scroll_height_in_lines - 1.0
+        } else {
+            scroll_height_in_lines
+        }
+    }
+
+    /// Nothing for modifier keys pressed on their own, which would cut a
+    /// pending key sequence short.
+    fn get_chord(keycode: Keycode, keymod: Mod) -> Option<Chord> {
+        match keycode {
+            Keycode::LCtrl
+            | Keycode::RCtrl
+            | Keycode::LShift
+            | Keycode::RShift
+            | Keycode::LAlt
+            | Keycode::RAlt
+            | Keycode::LGui
+            | Keycode::RGui
+            | Keycode::Mode
+            | Keycode::CapsLock => None,
+            _ => Some(Chord::new(
+                &keycode.name(),
+                Modifiers {
+                    ctrl: keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD),
+                    alt: keymod.intersects(Mod::LALTMOD | Mod::RALTMOD),
+                    shift: keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+                },
+            )),
+        }
+    }
+
+    /// What the glyph editor asks of the main loop after an event.
+    enum GlyphEditorAction {
+        Continue,
+        Close,
+        Save,
+        Open(char),
+    }
+
+    /// Left click places or drags points, right click deletes them. Mouse
+    /// positions are in window pixels, `scale` drawable pixels each.
+    fn handle_glyph_editor_event(
+        glyph_editor: &mut GlyphEditor,
+        font: &Font,
+        view: &GridView,
+        event: &Event,
+        scale: f32,
+    ) -> GlyphEditorAction {
+        let character = glyph_editor.character;
+        let get_grid_point = |x: i32, y: i32| {
+            view.get_grid_point((x as f32 * scale) as i32, (y as f32 * scale) as i32)
+        };
+        match event {
+            Event::MouseButtonDown {
+                mouse_btn, x, y, ..
+            } => {
+                if let Some((x, y)) = get_grid_point(*x, *y) {
+                    match mouse_btn {
+                        MouseButton::Left => glyph_editor.press(x, y),
+                        MouseButton::Right => glyph_editor.delete(x, y),
+                        _ => {}
+                    }
+                }
+            }
+            Event::MouseMotion { x, y, .. } => {
+                if let Some((x, y)) = get_grid_point(*x, *y) {
+                    glyph_editor.drag(x, y);
+                }
+            }
+            Event::MouseButtonUp { .. } => glyph_editor.release(),
+            Event::KeyDown {
+                keycode: Some(keycode),
+                keymod,
+                ..
+            } => {
+                let ctrl = keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD);
+                match keycode {
+                    Keycode::Escape => return GlyphEditorAction::Close,
+                    Keycode::Return => glyph_editor.lift_pen(),
+                    Keycode::Backspace | Keycode::Delete => glyph_editor.delete_selected(),
+                    Keycode::C if !ctrl => glyph_editor.toggle_curve(),
+                    Keycode::S if ctrl => return GlyphEditorAction::Save,
+                    Keycode::Tab => {
+                        return GlyphEditorAction::Open(next_missing(font, character));
+                    }
+                    Keycode::PageDown | Keycode::PageUp => {
+                        let code = if *keycode == Keycode::PageDown {
+                            character as u32 + 1
+                        } else {
+                            (character as u32).saturating_sub(1)
+                        };
+                        if let Some(character) = std::char::from_u32(code) {
+                            return GlyphEditorAction::Open(character);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        GlyphEditorAction::Continue
+    }
+
+    /// Saves to the configured font file, or else to `user.font` in the
+    /// configuration directory, which is then configured.
+    fn save_glyph(
+        glyph_editor: &GlyphEditor,
+        font_path: &mut Option<PathBuf>,
+    ) -> Result<PathBuf, String> {
+        let path = match font_path {
+            Some(path) => path.clone(),
+            None => config_directory()
+                .ok_or("no configuration directory")?
+                .join("user.font"),
+        };
+        glyph_editor.save(&path)?;
+        if font_path.is_none() {
+            Config::store("font", "path", "\"user.font\"")?;
+            *font_path = Some(path.clone());
+        }
+        Ok(path)
+    }
+
+    /// The grid between the title in the first line and the preview in the
+    /// last one.
+    fn get_glyph_view(layout: &Layout, window_width: u32, window_height: u32) -> GridView {
+        let line_height = layout.character_height + layout.line_gap;
+        GridView::fit(
+            0,
+            get_character_y(layout, 1),
+            window_width as i32,
+            window_height as i32 - get_character_y(layout, 1) - 2 * line_height,
+        )
+    }
+
+    /// The glyph being edited and where it is drawn in the window.
+    struct GlyphEditorScreen<'a> {
+        glyph_editor: &'a GlyphEditor,
+        view: GridView,
+        window_height: u32,
+    }
+
+    fn draw_glyph_editor(
+        canvas: &mut WindowCanvas,
+        layout: &Layout,
+        font: &Font,
+        colors: &Colors,
+        screen: &GlyphEditorScreen,
+        stroke_width: u32,
+    ) {
+        let GlyphEditorScreen {
+            glyph_editor,
+            view,
+            window_height,
+        } = screen;
+        let character = glyph_editor.character;
+        let preview = glyph_editor.preview_font(font);
+        let title = format!("{} U+{:04X}", format_character(character), character as u32);
+        canvas.set_draw_color(colors.text);
+        draw_text(canvas, layout, font, &title, 0, 0, stroke_width);
+
+        canvas.set_draw_color(colors.gutter);
+        for x in GRID_MINIMUM.0..=GRID_MAXIMUM.0 {
+            for y in GRID_MINIMUM.1..=GRID_MAXIMUM.1 {
+                let (screen_x, screen_y) = view.get_screen_position(x, y);
+                canvas.draw_point(Point::new(screen_x, screen_y)).unwrap();
+            }
+        }
+        let (left, top) = view.get_screen_position(0, 0);
+        let size = (GRID * view.spacing) as u32;
+        canvas.draw_rect(Rect::new(left, top, size, size)).unwrap();
+        canvas.set_draw_color(colors.cursor_extender);
+        for y in &[GRID / 2, GRID] {
+            let (_, screen_y) = view.get_screen_position(0, *y);
+            canvas
+                .draw_line((left, screen_y), (left + size as i32, screen_y))
+                .unwrap();
+        }
+
+        canvas.set_draw_color(colors.text);
+        let glyph_size = GRID * view.spacing;
+        for stroke in preview.get_strokes(&character, glyph_size, glyph_size) {
+            let points = stroke
+                .iter()
+                .map(|(x, y)| Point::new(left + x, top + y))
+                .collect::<Vec<Point>>();
+            canvas.draw_lines(points.as_slice()).unwrap();
+        }
+        for (stroke_index, stroke) in glyph_editor.strokes.iter().enumerate() {
+            for (point_index, point) in stroke.iter().enumerate() {
+                let is_selected = glyph_editor.selected == Some((stroke_index, point_index));
+                canvas.set_draw_color(if is_selected {
+                    colors.cursor_selection
+                } else {
+                    colors.cursor
+                });
+                let (x, y) = view.get_screen_position(point.x, point.y);
+                let handle = Rect::new(x - 2, y - 2, 5, 5);
+                if point.is_control {
+                    canvas.draw_rect(handle).unwrap();
+                } else {
+                    canvas.fill_rect(handle).unwrap();
+                }
+            }
+        }
+
+        let sample = format!("{0}{0}{0} H{0}n x{0}x 0{0}9", character);
+        let line_height = layout.character_height + layout.line_gap;
+        let last_line = (*window_height as i32 - layout.y_offset) / line_height - 1;
+        canvas.set_draw_color(colors.text);
+        draw_text(
+            canvas,
+            layout,
+            &preview,
+            &sample,
+            0,
+            last_line,
+            stroke_width,
+        );
+    }
+
+    fn load_config() -> Config {
+        match Config::load() {
+            Ok(config) => config,
+            Err(error) => {
+                eprintln!(
+                    "Invalid configuration in \"{}\": {}",
+                    Config::path()
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_default(),
+                    error
+                );
+                std::process::exit(1);
+            }
+        }
+    }
+
+    /// The size the window was left at, or else the configured columns and
+    /// lines next to the scrollbar and the minimap.
+    fn get_window_size(layout: &Layout, config: &Config) -> (u32, u32) {
+        let width = (layout.character_width + layout.character_gap) * config.window.columns as i32
+            + get_sidebar_width(config, layout, 1.0) as i32;
+        let height = (layout.character_height + layout.line_gap) * config.window.lines as i32;
+        (
+            config.window.width.unwrap_or(width as u32),
+            config.window.height.unwrap_or(height as u32),
+        )
+    }
+
+    /// Pixels the scrollbar and the minimap take at the right edge, if they
+    /// are turned on.
+    fn get_sidebar_width(config: &Config, layout: &Layout, scale: f32) -> u32 {
+        let mut width = 0;
+        if config.window.scrollbar {
+            width += get_scrollbar_width(layout);
+        }
+        if config.window.minimap {
+            width += get_minimap_width(&get_minimap_layout(get_scaled_zoom(100, scale)));
+        }
+        width
+    }
+
+    /// How many columns of text fit left of the scrollbar and the minimap.
+    fn get_text_columns(config: &Config, layout: &Layout, scale: f32, window_width: u32) -> u32 {
+        let sidebar_width = get_sidebar_width(config, layout, scale);
+        get_columns_in_width(layout, window_width.saturating_sub(sidebar_width))
+    }
+
+    /// The scrollbar and the minimap of a window of `size` that shows
+    /// `visible_lines` from `camera_line` on, if they are turned on.
+    fn get_sidebar(
+        config: &Config,
+        layout: &Layout,
+        scale: f32,
+        size: (u32, u32),
+        camera_line: i32,
+        visible_lines: f32,
+        line_count: usize,
+    ) -> (Option<Scrollbar>, Option<Minimap>) {
+        let (width, height) = size;
+        let scrollbar_width = if config.window.scrollbar {
+            get_scrollbar_width(layout)
+        } else {
+            0
+        };
+        let scrollbar = if config.window.scrollbar {
+            Some(Scrollbar::new(
+                width as i32 - scrollbar_width as i32,
+                scrollbar_width,
+                height,
+                camera_line,
+                visible_lines,
+                line_count,
+            ))
+        } else {
+            None
+        };
+        let minimap = if config.window.minimap {
+            let minimap_layout = get_minimap_layout(get_scaled_zoom(100, scale));
+            let left =
+                width as i32 - scrollbar_width as i32 - get_minimap_width(&minimap_layout) as i32;
+            Some(Minimap::new(
+                left,
+                height,
+                &minimap_layout,
+                camera_line,
+                line_count,
+            ))
+        } else {
+            None
+        };
+        (scrollbar, minimap)
+    }
+
+    /// Remembers the size and position of `window` for the next start.
+    /// Stored once as the window closes rather than while it is resized.
+    fn store_window_geometry(window: &Window) {
+        let (width, height) = window.size();
+        let (x, y) = window.position();
+        let (width, height, x, y) = (
+            width.to_string(),
+            height.to_string(),
+            x.to_string(),
+            y.to_string(),
+        );
+        let values = [
+            ("window", "width", width.as_str()),
+            ("window", "height", height.as_str()),
+            ("window", "x", x.as_str()),
+            ("window", "y", y.as_str()),
+        ];
+        if let Err(error) = Config::store_all(&values) {
+            eprintln!("Could not save the window size and position: {}", error);
+        }
+    }
+
+    /// Writes what the window shows right after opening `file_path` to the
+    /// PNG `output_path`, without opening a window, as a display with
+    /// `scale` drawable pixels per window pixel would show it.
+    pub fn screenshot(file_path: &str, output_path: &str, scale: f32) {
+        let config = load_config();
+        let (width, height) = get_window_size(&config.layout.zoomed(config.window.zoom), &config);
+        let layout = config
+            .layout
+            .zoomed(get_scaled_zoom(config.window.zoom, scale));
+        let editor = Editor::from_file(file_path);
+        let mut highlighter = Highlighter::new(get_grammar_for_path(file_path));
+        highlighter.update(&editor.cursor.lines);
+        let mut image = image::RgbaImage::new(
+            (width as f32 * scale).round() as u32,
+            (height as f32 * scale).round() as u32,
+        );
+        let mut view =
+            image_rendering::get_image_view(&image, &editor.cursor, &highlighter, 0, &layout);
+        view.stroke_width = get_stroke_width(
+            config.strokes.width,
+            get_scaled_zoom(config.window.zoom, scale),
+        );
+        view.columns = get_text_columns(&config, &layout, scale, image.width()) as i32;
+        let (scrollbar, minimap) = get_sidebar(
+            &config,
+            &layout,
+            scale,
+            image.dimensions(),
+            0,
+            get_text_height_in_lines(&layout, image.height(), false),
+            editor.cursor.lines.len(),
+        );
+        view.scrollbar = scrollbar;
+        view.minimap = minimap;
+        image_rendering::render_view(
+            &mut image,
+            &view,
+            &layout,
+            &config.font,
+            &config.theme,
+            config.strokes.style,
+        );
+        if let Err(error) = image_rendering::save_png(&image, Path::new(output_path)) {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    }
+
+    /// A frame drawn in memory with smooth strokes, shown through a
+    /// streaming texture of the same size.
+    struct SmoothTarget<'a> {
+        image: image::RgbaImage,
+        texture: Option<Texture<'a>>,
+    }
+
+    fn draw_smooth_frame<'a>(
+        canvas: &mut WindowCanvas,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        target: &mut SmoothTarget<'a>,
+        frame: &Frame,
+        layout: &Layout,
+        font: &Font,
+    ) {
+        let (width, height) = canvas.output_size().unwrap();
+        if target.image.dimensions() != (width, height) {
+            target.image = image::RgbaImage::new(width, height);
+            target.texture = None;
+        }
+        let mut renderer = ImageRenderer::with_stroke_style(&mut target.image, StrokeStyle::Smooth);
+        draw_frame(&mut renderer, frame, layout, font);
+        if target.texture.is_none() {
+            // The bytes of ABGR8888 are in RGBA order on little-endian machines.
+            let texture = texture_creator
+                .create_texture_streaming(PixelFormatEnum::ABGR8888, width, height)
+                .unwrap();
+            target.texture = Some(texture);
+        }
+        let texture = target.texture.as_mut().unwrap();
+        texture
+            .update(None, &target.image, width as usize * 4)
+            .unwrap();
+        canvas.copy(texture, None, None).unwrap();
+    }
 
-    fn get_character_x(column_index: i32) -> i32 {
-        column_index * (CHARACTER_GAP + CHARACTER_WIDTH) + CHARACTER_X_OFFSET
+    /// The events that arrived, waiting at most `timeout` for the first one
+    /// or, without a timeout, until it arrives.
+    fn wait_for_events(event_pump: &mut EventPump, timeout: Option<Duration>) -> Vec<Event> {
+        let first = match timeout {
+            Some(timeout) => {
+                let milliseconds = timeout.as_micros().div_ceil(1000);
+                event_pump.wait_event_timeout(milliseconds as u32)
+            }
+            None => Some(event_pump.wait_event()),
+        };
+        first.into_iter().chain(event_pump.poll_iter()).collect()
     }
 
-    fn get_character_y(line_index: i32) -> i32 {
-        line_index * (LINE_GAP + CHARACTER_HEIGHT) + CHARACTER_Y_OFFSET
+    /// Whether handling `event` in the editor can change what it shows.
+    fn is_changing_the_view(event: &Event) -> bool {
+        !matches!(event, Event::MouseMotion { .. } | Event::KeyUp { .. })
     }
 
-    fn get_lines_to_render(
-        lines: &Vec<String>,
-        start_line: usize,
-        number_of_lines: usize,
-    ) -> std::slice::Iter<String> {
-        lines[start_line..(start_line + number_of_lines).min(lines.len())].iter()
+    /// Edits `file_path` in the terminal the editor was started from.
+    pub fn run_terminal(file_path: &str) {
+        let config = load_config();
+        if let Err(error) = tty::run(file_path, &config, libc::STDIN_FILENO, libc::STDOUT_FILENO) {
+            eprintln!("The terminal frontend failed: {}", error);
+            std::process::exit(1);
+        }
     }
 
     pub fn run(file_path: &str) {
+        let config = load_config();
+        let mut zoom = config.window.zoom;
+        let mut layout = config.layout.zoomed(zoom);
+        let mut theme = config.theme.clone();
+        let mut font = config.font.clone();
+        let mut font_path = config.font_path.clone();
+        let mut glyph_editor: Option<GlyphEditor> = None;
+
         let sdl_context = sdl2::init().unwrap();
         let video_subsystem = sdl_context.video().unwrap();
-        let window_width = ((CHARACTER_WIDTH + CHARACTER_GAP) * 45) as u32;
-        let window_height = ((CHARACTER_HEIGHT + LINE_GAP) * 20) as u32;
+        let (width, height) = get_window_size(&layout, &config);
 
-        let scroll_height_in_lines = (window_height as f32 - CHARACTER_Y_OFFSET as f32)
-            / (LINE_GAP as f32 + CHARACTER_HEIGHT as f32);
-
-        let window = video_subsystem
-            .window("Editor", window_width, window_height)
-            .position_centered()
-            .build()
-            .unwrap();
+        let mut window_builder = video_subsystem.window("Editor", width, height);
+        window_builder.resizable().allow_highdpi();
+        match (config.window.x, config.window.y) {
+            (Some(x), Some(y)) => window_builder.position(x, y),
+            _ => window_builder.position_centered(),
+        };
+        let window = window_builder.build().unwrap();
         let mut canvas: WindowCanvas = window.into_canvas().build().unwrap();
         canvas.clear();
         canvas.present();
+        let (mut window_width, mut window_height) = canvas.output_size().unwrap();
+        let mut scale = get_display_scale(window_width, canvas.window().size().0);
+        layout = config.layout.zoomed(get_scaled_zoom(zoom, scale));
+        let texture_creator = canvas.texture_creator();
+        let mut smooth_target = SmoothTarget {
+            image: image::RgbaImage::new(0, 0),
+            texture: None,
+        };
+        let is_caching = config.cache.glyphs || config.cache.lines;
+        let mut texture_cache = TextureCache::new(
+            &texture_creator,
+            config.strokes.style,
+            config.cache.glyphs,
+            config.cache.lines,
+        );
+        let mut frame_times = FrameTimes::new(30);
+        let mut show_frame_time = config.window.show_frame_time;
 
         let mut event_pump = sdl_context.event_pump().unwrap();
 
-        let mut cursor = Cursor::from_file(file_path);
+        let mut editor = Editor::from_file(file_path);
+        let mut highlighter = Highlighter::new(get_grammar_for_path(file_path));
+        let mut keymap = config.keymap.clone();
+        let mut is_text_input_consumed = false;
+        let mut vim = match config.editing.keybindings {
+            Keybindings::Vim => Some(Vim::new()),
+            Keybindings::Default | Keybindings::Emacs => None,
+        };
+        let has_status_line = vim.is_some();
+        let mut text_height_in_lines =
+            get_text_height_in_lines(&layout, window_height, has_status_line);
+        let mut columns = get_text_columns(&config, &layout, scale, window_width);
 
         let mut camera_line: i32 = 0;
 
+        let mut frame_limiter = FrameLimiter::new(config.window.frames_per_second);
+        let mut autosave = Timer::new(Duration::from_secs(config.editing.autosave_seconds as u64));
+        let mut saved_lines = editor.cursor.lines.clone();
+        let mut is_redraw_needed = true;
+        let blink_interval = match config.caret.blink_milliseconds {
+            0 => None,
+            milliseconds => Some(Duration::from_millis(milliseconds as u64)),
+        };
+        let mut blink = Blink::new(blink_interval, Instant::now());
+        let mut is_focused = true;
+        let mut preedit: Option<Preedit> = None;
+        // Where on the thumb the scrollbar was grabbed while it is dragged.
+        let mut scrollbar_grab: Option<i32> = None;
+
         video_subsystem.text_input().start();
 
         'running: loop {
-            canvas.set_draw_color(BACKGROUND_COLOR);
-            canvas.clear();
-
-            let mut pressed_keys = HashSet::new();
-            pressed_keys = event_pump
-                .keyboard_state()
-                .pressed_scancodes()
-                .filter_map(Keycode::from_scancode)
-                .collect();
-
-            let is_selecting_text =
-                pressed_keys.contains(&Keycode::LShift) || pressed_keys.contains(&Keycode::RShift);
-
-            let is_holding_ctrl =
-                pressed_keys.contains(&Keycode::LCtrl) || pressed_keys.contains(&Keycode::RCtrl);
+            if is_redraw_needed && frame_limiter.is_ready(Instant::now()) {
+                let frame_start = Instant::now();
+                if let Some(glyph_editor) = glyph_editor.as_ref() {
+                    let screen = GlyphEditorScreen {
+                        glyph_editor,
+                        view: get_glyph_view(&layout, window_width, window_height),
+                        window_height,
+                    };
+                    canvas.set_draw_color(theme.colors.background);
+                    canvas.clear();
+                    draw_glyph_editor(
+                        &mut canvas,
+                        &layout,
+                        &font,
+                        &theme.colors,
+                        &screen,
+                        get_stroke_width(config.strokes.width, get_scaled_zoom(zoom, scale)),
+                    );
+                } else {
+                    highlighter.update(&editor.cursor.lines);
+                    let (scrollbar, minimap) = get_sidebar(
+                        &config,
+                        &layout,
+                        scale,
+                        (window_width, window_height),
+                        camera_line,
+                        text_height_in_lines,
+                        editor.cursor.lines.len(),
+                    );
+                    let view = View {
+                        cursor: &editor.cursor,
+                        highlighter: &highlighter,
+                        camera_line,
+                        lines: text_height_in_lines.ceil() as usize,
+                        columns: columns as i32,
+                        width: window_width,
+                        status_line: vim.as_ref().map(|vim| StatusLine {
+                            left: vim.mode.label().to_string(),
+                            right: vim.pending_keys(),
+                        }),
+                        caret: get_caret_look(
+                            &config.caret,
+                            editor.is_overwrite,
+                            is_focused,
+                            blink.is_on,
+                        ),
+                        preedit: preedit.clone(),
+                        stroke_width: get_stroke_width(
+                            config.strokes.width,
+                            get_scaled_zoom(zoom, scale),
+                        ),
+                        scrollbar,
+                        minimap,
+                    };
+                    let (left, top, width, height) = get_caret_cell(&view, &layout);
+                    let to_window = |size: i32| (size as f32 / scale) as i32;
+                    video_subsystem.text_input().set_rect(Rect::new(
+                        to_window(left),
+                        to_window(top),
+                        to_window(width as i32).max(1) as u32,
+                        to_window(height as i32).max(1) as u32,
+                    ));
+                    let mut frame = layout_frame(&view, &layout, &theme);
+                    if let (true, Some(time)) = (show_frame_time, frame_times.average()) {
+                        layout_overlay(
+                            &mut frame,
+                            &view,
+                            &layout,
+                            &theme,
+                            &format_frame_time(time),
+                        );
+                    }
+                    if is_caching {
+                        let mut renderer = CachedCanvas::new(&mut canvas, &mut texture_cache);
+                        draw_frame(&mut renderer, &frame, &layout, &font);
+                        texture_cache.end_frame();
+                    } else if config.strokes.style == StrokeStyle::Smooth {
+                        draw_smooth_frame(
+                            &mut canvas,
+                            &texture_creator,
+                            &mut smooth_target,
+                            &frame,
+                            &layout,
+                            &font,
+                        );
+                    } else {
+                        draw_frame(&mut canvas, &frame, &layout, &font);
+                    }
+                }
+                canvas.present();
+                frame_times.record(frame_start.elapsed());
+                frame_limiter.record_frame(Instant::now());
+                is_redraw_needed = false;
+            }
 
-            for event in event_pump.poll_iter() {
-                match event {
-                    Event::Quit { .. }
-                    | Event::KeyDown {
-                        keycode: Some(Keycode::Escape),
+            let next_frame = if is_redraw_needed {
+                frame_limiter.next_frame()
+            } else {
+                None
+            };
+            let timeout = get_timeout(
+                Instant::now(),
+                &[next_frame, autosave.deadline(), blink.deadline()],
+            );
+            for event in wait_for_events(&mut event_pump, timeout) {
+                is_redraw_needed |= glyph_editor.is_some() || is_changing_the_view(&event);
+                match &event {
+                    Event::KeyDown { .. } | Event::TextInput { .. } | Event::TextEditing { .. } => {
+                        blink.reset(Instant::now())
+                    }
+                    Event::Window {
+                        win_event: WindowEvent::FocusLost,
+                        ..
+                    } => {
+                        is_focused = false;
+                        blink.pause();
+                    }
+                    Event::Window {
+                        win_event: WindowEvent::FocusGained,
                         ..
                     } => {
+                        is_focused = true;
+                        blink.reset(Instant::now());
+                    }
+                    Event::Window {
+                        win_event: WindowEvent::SizeChanged(..),
+                        ..
+                    } => {
+                        let (width, height) = canvas.output_size().unwrap();
+                        window_width = width.max(1);
+                        window_height = height.max(1);
+                        scale = get_display_scale(window_width, canvas.window().size().0);
+                        layout = config.layout.zoomed(get_scaled_zoom(zoom, scale));
+                        text_height_in_lines =
+                            get_text_height_in_lines(&layout, window_height, has_status_line);
+                        columns = get_text_columns(&config, &layout, scale, window_width);
+                        camera_line = get_camera_line_following_cursor(
+                            camera_line,
+                            editor.cursor.extender.line,
+                            text_height_in_lines,
+                        );
+                    }
+                    _ => {}
+                }
+                if let Some(editing) = glyph_editor.as_mut() {
+                    let view = get_glyph_view(&layout, window_width, window_height);
+                    match handle_glyph_editor_event(editing, &font, &view, &event, scale) {
+                        GlyphEditorAction::Continue => {}
+                        GlyphEditorAction::Close => glyph_editor = None,
+                        GlyphEditorAction::Save => match save_glyph(editing, &mut font_path) {
+                            Ok(path) => {
+                                font = editing.preview_font(&font);
+                                texture_cache.clear();
+                                println!(
+                                    "Saved the glyph {} to \"{}\"",
+                                    format_character(editing.character),
+                                    path.display()
+                                );
+                            }
+                            Err(error) => eprintln!("Could not save the glyph: {}", error),
+                        },
+                        GlyphEditorAction::Open(character) => {
+                            glyph_editor = Some(GlyphEditor::new(&font, character))
+                        }
+                    }
+                    if let Event::Quit { .. } = event {
+                        break 'running;
+                    }
+                    continue;
+                }
+                match event {
+                    Event::Quit { .. } => {
                         break 'running;
                     }
                     Event::KeyDown {
-                        keycode, repeat, ..
+                        keycode: Some(keycode),
+                        keymod,
+                        repeat,
+                        ..
                     } => {
-                        match keycode {
-                            Some(Keycode::Right) => cursor.right(is_selecting_text),
-                            Some(Keycode::Left) => cursor.left(is_selecting_text),
-                            Some(Keycode::Down) => cursor.down(is_selecting_text),
-                            Some(Keycode::Up) => cursor.up(is_selecting_text),
-                            Some(Keycode::Home) => cursor.home(is_selecting_text),
-                            Some(Keycode::End) => cursor.end(is_selecting_text),
-                            Some(Keycode::Delete) => cursor.delete(),
-                            Some(Keycode::Backspace) => cursor.backspace(),
-                            Some(Keycode::Return) => cursor.new_line(),
-                            Some(Keycode::S) => {
-                                if !repeat && is_holding_ctrl {
-                                    if let Ok(_result) = cursor.to_file(file_path) {
-                                        println!(
-                                            "Saving current content to \"{}\" succeeded",
-                                            file_path
+                        is_text_input_consumed = false;
+                        let chord = match get_chord(keycode, keymod) {
+                            Some(chord) => chord,
+                            None => continue,
+                        };
+                        if let Some(vim) = vim.as_mut() {
+                            if !chord.modifiers.ctrl
+                                && !chord.modifiers.alt
+                                && vim::is_special_key(&chord.key)
+                            {
+                                vim.handle(&mut editor.cursor, Input::Key(chord.key));
+                                camera_line = get_camera_line_following_cursor(
+                                    camera_line,
+                                    editor.cursor.extender.line,
+                                    text_height_in_lines,
+                                );
+                                continue;
+                            }
+                        }
+                        match keymap.press(chord) {
+                            KeyResult::Command(command) => {
+                                is_text_input_consumed = true;
+                                if repeat && !command.is_repeatable() {
+                                    continue;
+                                }
+                                match editor.execute(command) {
+                                    Outcome::Continue => {}
+                                    Outcome::ToggleFrameTime => show_frame_time = !show_frame_time,
+                                    Outcome::NextTheme => {
+                                        theme = config.next_theme(&theme);
+                                        println!("Theme: {}", theme.name);
+                                    }
+                                    Outcome::Zoom(change) => {
+                                        zoom = match change {
+                                            Zoom::In => zoom_in(zoom),
+                                            Zoom::Out => zoom_out(zoom),
+                                            Zoom::Reset => 100,
+                                        };
+                                        layout = config.layout.zoomed(get_scaled_zoom(zoom, scale));
+                                        text_height_in_lines = get_text_height_in_lines(
+                                            &layout,
+                                            window_height,
+                                            has_status_line,
                                         );
-                                    } else {
-                                        println!(
-                                            "Saving current content to \"{}\" failed",
-                                            file_path
+                                        columns =
+                                            get_text_columns(&config, &layout, scale, window_width);
+                                        if let Err(error) =
+                                            Config::store("window", "zoom", &zoom.to_string())
+                                        {
+                                            eprintln!("Could not save the zoom level: {}", error);
+                                        }
+                                    }
+                                    Outcome::EditGlyph(character) => {
+                                        glyph_editor = Some(GlyphEditor::new(&font, character))
+                                    }
+                                    Outcome::ExportSvg => {
+                                        highlighter.update(&editor.cursor.lines);
+                                        let svg = to_svg(
+                                            &editor.cursor,
+                                            &highlighter,
+                                            &config.layout,
+                                            &font,
+                                            &theme,
+                                            &config.export,
                                         );
+                                        let path = format!("{}.svg", editor.file_path);
+                                        match fs::write(&path, svg) {
+                                            Ok(()) => println!("Exported to \"{}\"", path),
+                                            Err(error) => eprintln!(
+                                                "Exporting to \"{}\" failed: {}",
+                                                path, error
+                                            ),
+                                        }
                                     }
+                                    Outcome::Saved(result) => {
+                                        if result.is_ok() {
+                                            saved_lines = editor.cursor.lines.clone();
+                                        }
+                                        report_save(&editor, result);
+                                    }
+                                    Outcome::Quit => break 'running,
                                 }
                             }
-                            Some(Keycode::A) => {
-                                if !repeat && is_holding_ctrl {
-                                    cursor.current.line = 0;
-                                    cursor.current.column = 0;
-                                    cursor.extender.line = cursor.lines.len() - 1;
-                                    cursor.extender.column =
-                                        cursor.lines[cursor.lines.len() - 1].chars().count();
-                                }
-                            }
-                            Some(_) | None => {}
-                        }
-                        if cursor.extender.line
-                            > (camera_line as usize + scroll_height_in_lines as usize)
-                        {
-                            camera_line =
-                                cursor.extender.line as i32 - scroll_height_in_lines as i32;
-                        } else if cursor.extender.line < camera_line as usize {
-                            camera_line = cursor.extender.line as i32;
+                            KeyResult::Pending => is_text_input_consumed = true,
+                            KeyResult::Unbound => {}
                         }
+                        camera_line = get_camera_line_following_cursor(
+                            camera_line,
+                            editor.cursor.extender.line,
+                            text_height_in_lines,
+                        );
                     }
                     Event::TextInput { text, .. } => {
-                        let mut characters = text.chars();
-                        if let Some(character) = characters.next() {
-                            cursor.add(character);
+                        if is_text_input_consumed {
+                            continue;
+                        }
+                        preedit = None;
+                        if let Some(vim) = vim.as_mut() {
+                            for character in text.chars() {
+                                vim.handle(&mut editor.cursor, Input::Character(character));
+                            }
+                        } else {
+                            editor.add_text(&text);
                         }
+                        camera_line = get_camera_line_following_cursor(
+                            camera_line,
+                            editor.cursor.extender.line,
+                            text_height_in_lines,
+                        );
+                    }
+                    Event::TextEditing { text, start, .. } => {
+                        preedit = if text.is_empty() {
+                            None
+                        } else {
+                            let length = text.chars().count();
+                            Some(Preedit {
+                                text,
+                                cursor: (start.max(0) as usize).min(length),
+                            })
+                        };
                     }
                     Event::MouseWheel { y, .. } => {
                         let new_camera_line = camera_line - y;
-                        if new_camera_line >= 0 && new_camera_line < cursor.lines.len() as i32 {
+                        if new_camera_line >= 0
+                            && new_camera_line < editor.cursor.lines.len() as i32
+                        {
                             camera_line = new_camera_line;
                         }
                     }
-                    _ => {}
-                }
-            }
-
-            canvas.set_draw_color(TEXT_COLOR);
-
-            for (line_index, line) in get_lines_to_render(
-                &cursor.lines,
-                camera_line as usize,
-                scroll_height_in_lines.ceil() as usize,
-            )
-            .enumerate()
-            {
-                let line_y_offset = get_character_y(line_index as i32);
-                for (column_index, one_character_string) in line
-                    .split("")
-                    .filter(|string| string.chars().count() > 0)
-                    .enumerate()
-                {
-                    let mut characters = one_character_string.chars();
-                    if let Some(character) = characters.next() {
-                        let character_x_offset = get_character_x(column_index as i32);
-                        let coords =
-                            get_character_coords(&character, CHARACTER_WIDTH, CHARACTER_HEIGHT);
-
-                        for (index, (x1, y1)) in coords.iter().enumerate() {
-                            if index < (coords.len() - 1) {
-                                let (x2, y2) = coords[index + 1];
-
-                                canvas
-                                    .draw_line(
-                                        Point::new(*x1 + character_x_offset, *y1 + line_y_offset),
-                                        Point::new(x2 + character_x_offset, y2 + line_y_offset),
-                                    )
-                                    .unwrap();
+                    Event::MouseButtonDown {
+                        mouse_btn: MouseButton::Left,
+                        x,
+                        y,
+                        ..
+                    } => {
+                        let (x, y) = ((x as f32 * scale) as i32, (y as f32 * scale) as i32);
+                        let (scrollbar, minimap) = get_sidebar(
+                            &config,
+                            &layout,
+                            scale,
+                            (window_width, window_height),
+                            camera_line,
+                            text_height_in_lines,
+                            editor.cursor.lines.len(),
+                        );
+                        if let Some(scrollbar) = scrollbar.filter(|s| s.contains(x, y)) {
+                            let grab = if scrollbar.is_on_thumb(y) {
+                                y - scrollbar.thumb_top
+                            } else {
+                                scrollbar.thumb_height as i32 / 2
+                            };
+                            camera_line = scrollbar.get_camera_line(y - grab);
+                            scrollbar_grab = Some(grab);
+                        } else if let Some(minimap) = minimap.filter(|m| m.contains(x, y)) {
+                            let line =
+                                minimap.get_line_at(y) as i32 - text_height_in_lines as i32 / 2;
+                            camera_line = line.max(0).min(editor.cursor.lines.len() as i32 - 1);
+                        }
+                    }
+                    Event::MouseMotion { y, .. } => {
+                        if let Some(grab) = scrollbar_grab {
+                            let (scrollbar, _) = get_sidebar(
+                                &config,
+                                &layout,
+                                scale,
+                                (window_width, window_height),
+                                camera_line,
+                                text_height_in_lines,
+                                editor.cursor.lines.len(),
+                            );
+                            if let Some(scrollbar) = scrollbar {
+                                let y = (y as f32 * scale) as i32;
+                                camera_line = scrollbar.get_camera_line(y - grab);
+                                is_redraw_needed = true;
                             }
                         }
                     }
+                    Event::MouseButtonUp {
+                        mouse_btn: MouseButton::Left,
+                        ..
+                    } => scrollbar_grab = None,
+                    _ => {}
                 }
             }
 
-            if cursor.current.line >= camera_line as usize {
-                canvas.set_draw_color(CURSOR_COLOR);
-                canvas
-                    .draw_line(
-                        (
-                            get_character_x(cursor.current.column as i32),
-                            get_character_y(cursor.current.line as i32 - camera_line)
-                                - CHARACTER_HEIGHT * 1 / 5,
-                        ),
-                        (
-                            get_character_x(cursor.current.column as i32),
-                            get_character_y(cursor.current.line as i32 - camera_line)
-                                + CHARACTER_HEIGHT * 6 / 5,
-                        ),
-                    )
-                    .unwrap();
+            let now = Instant::now();
+            if config.editing.autosave_seconds > 0
+                && !autosave.is_running()
+                && editor.cursor.lines != saved_lines
+            {
+                autosave.start(now);
             }
-            canvas.set_draw_color(CURSOR_SELECTION_COLOR);
-            if cursor.current != cursor.extender {
-                if cursor.current.line != cursor.extender.line {
-                    for current_line in if cursor.current.line > cursor.extender.line {
-                        (cursor.extender.line + 1)..cursor.current.line
-                    } else {
-                        (cursor.current.line + 1)..cursor.extender.line
-                    } {
-                        canvas
-                            .draw_line(
-                                (
-                                    get_character_x(0),
-                                    get_character_y(current_line as i32 - camera_line)
-                                        + CHARACTER_HEIGHT / 2,
-                                ),
-                                (
-                                    get_character_x(
-                                        cursor.lines[current_line].chars().count() as i32
-                                    ),
-                                    get_character_y(current_line as i32 - camera_line)
-                                        + CHARACTER_HEIGHT / 2,
-                                ),
-                            )
-                            .unwrap();
-                    }
-
-                    let (selection_to_end_of_line, selection_from_beginning_of_line) =
-                        if cursor.current > cursor.extender {
-                            (&cursor.extender, &cursor.current)
-                        } else {
-                            (&cursor.current, &cursor.extender)
-                        };
-                    canvas
-                        .draw_line(
-                            (
-                                get_character_x(selection_to_end_of_line.column as i32),
-                                get_character_y(selection_to_end_of_line.line as i32 - camera_line)
-                                    + CHARACTER_HEIGHT / 2,
-                            ),
-                            (
-                                get_character_x(
-                                    cursor.lines[selection_to_end_of_line.line].chars().count()
-                                        as i32,
-                                ),
-                                get_character_y(selection_to_end_of_line.line as i32 - camera_line)
-                                    + CHARACTER_HEIGHT / 2,
-                            ),
-                        )
-                        .unwrap();
-                    canvas
-                        .draw_line(
-                            (
-                                get_character_x(0),
-                                get_character_y(
-                                    selection_from_beginning_of_line.line as i32 - camera_line,
-                                ) + CHARACTER_HEIGHT / 2,
-                            ),
-                            (
-                                get_character_x(selection_from_beginning_of_line.column as i32),
-                                get_character_y(
-                                    selection_from_beginning_of_line.line as i32 - camera_line,
-                                ) + CHARACTER_HEIGHT / 2,
-                            ),
-                        )
-                        .unwrap();
-                } else {
-                    canvas
-                        .draw_line(
-                            (
-                                get_character_x(cursor.current.column as i32),
-                                get_character_y(cursor.current.line as i32 - camera_line)
-                                    + CHARACTER_HEIGHT / 2,
-                            ),
-                            (
-                                get_character_x(cursor.extender.column as i32),
-                                get_character_y(cursor.current.line as i32 - camera_line)
-                                    + CHARACTER_HEIGHT / 2,
-                            ),
-                        )
-                        .unwrap();
-                }
-
-                if cursor.extender.line >= camera_line as usize {
-                    canvas.set_draw_color(CURSOR_EXTENDER_COLOR);
-                    canvas
-                        .draw_line(
-                            (
-                                get_character_x(cursor.extender.column as i32),
-                                get_character_y(cursor.extender.line as i32 - camera_line)
-                                    - CHARACTER_HEIGHT * 1 / 5,
-                            ),
-                            (
-                                get_character_x(cursor.extender.column as i32),
-                                get_character_y(cursor.extender.line as i32 - camera_line)
-                                    + CHARACTER_HEIGHT * 6 / 5,
-                            ),
-                        )
-                        .unwrap();
+            if blink.update(now) {
+                is_redraw_needed = true;
+            }
+            if autosave.fire(now) && editor.cursor.lines != saved_lines {
+                let result = editor.save();
+                if result.is_ok() {
+                    saved_lines = editor.cursor.lines.clone();
                 }
+                report_save(&editor, result);
             }
-
-            canvas.present();
-            ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 16));
         }
+        store_window_geometry(canvas.window());
     }
 
-    fn get_line_coords(column_from: usize, column_to: usize) -> Vec<(i32, i32)> {
-        let from = column_from as i32 * (CHARACTER_GAP + CHARACTER_WIDTH);
-        let to = column_to as i32 * (CHARACTER_GAP + CHARACTER_WIDTH);
-        vec![
-            (from, 0),
-            (to, 0),
-            (to, CHARACTER_HEIGHT),
-            (from, CHARACTER_HEIGHT),
-        ]
-    }
-
-    fn get_line_coords_line_draw(column_from: usize, column_to: usize) -> Vec<(i32, i32)> {
-        let mut coords = get_line_coords(column_from, column_to);
-        if coords.len() > 0 {
-            coords.push(coords[0]);
-            coords
-        } else {
-            coords
+    fn report_save(editor: &Editor, result: Result<(), String>) {
+        match result {
+            Ok(()) => println!("Saved \"{}\"", editor.file_path),
+            Err(error) => eprintln!("Saving \"{}\" failed: {}", editor.file_path, error),
         }
     }
 }
@@ -0,0 +1,838 @@
+pub mod vim {
+    use super::super::cursor::cursor::Cursor;
+    use std::collections::HashMap;
+    use std::mem;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Default)]
+    pub enum Mode {
+        #[default]
+        Normal,
+        Insert,
+        Visual,
+        VisualLine,
+    }
+
+    impl Mode {
+        pub fn label(self) -> &'static str {
+            match self {
+                Mode::Normal => "-- NORMAL --",
+                Mode::Insert => "-- INSERT --",
+                Mode::Visual => "-- VISUAL --",
+                Mode::VisualLine => "-- VISUAL LINE --",
+            }
+        }
+    }
+
+    /// Typed characters arrive as `Character`, keys without a character
+    /// (`escape`, `return`, `backspace`, `delete` and the arrows) as `Key`.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Input {
+        Character(char),
+        Key(String),
+    }
+
+    static SPECIAL_KEYS: [&str; 8] = [
+        "escape",
+        "return",
+        "backspace",
+        "delete",
+        "left",
+        "right",
+        "up",
+        "down",
+    ];
+
+    pub fn is_special_key(key: &str) -> bool {
+        SPECIAL_KEYS.contains(&key)
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Operator {
+        Delete,
+        Change,
+        Yank,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Motion {
+        Left,
+        Right,
+        Up,
+        Down,
+        WordForward,
+        WordBackward,
+        WordEnd,
+        LineStart,
+        LineEnd,
+        FirstLine,
+        LastLine,
+    }
+
+    impl Motion {
+        fn from_character(character: char) -> Option<Motion> {
+            match character {
+                'h' => Some(Motion::Left),
+                'l' | ' ' => Some(Motion::Right),
+                'k' => Some(Motion::Up),
+                'j' => Some(Motion::Down),
+                'w' => Some(Motion::WordForward),
+                'b' => Some(Motion::WordBackward),
+                'e' => Some(Motion::WordEnd),
+                '0' => Some(Motion::LineStart),
+                '$' => Some(Motion::LineEnd),
+                'G' => Some(Motion::LastLine),
+                _ => None,
+            }
+        }
+        fn is_linewise(self) -> bool {
+            matches!(
+                self,
+                Motion::Up | Motion::Down | Motion::FirstLine | Motion::LastLine
+            )
+        }
+        fn is_inclusive(self) -> bool {
+            self == Motion::WordEnd || self == Motion::LineEnd
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Register {
+        pub text: String,
+        pub linewise: bool,
+    }
+
+    type Position = (usize, usize);
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum CharacterClass {
+        Whitespace,
+        Word,
+        Punctuation,
+    }
+
+    fn line_length(lines: &[String], line: usize) -> usize {
+        lines[line].chars().count()
+    }
+
+    /// The end of each line counts as a position of its own, like a newline.
+    fn character_class(lines: &[String], (line, column): Position) -> CharacterClass {
+        match lines[line].chars().nth(column) {
+            None => CharacterClass::Whitespace,
+            Some(character) if character.is_whitespace() => CharacterClass::Whitespace,
+            Some(character) if character.is_alphanumeric() || character == '_' => {
+                CharacterClass::Word
+            }
+            Some(_) => CharacterClass::Punctuation,
+        }
+    }
+
+    fn next_position(lines: &[String], (line, column): Position) -> Option<Position> {
+        if column < line_length(lines, line) {
+            Some((line, column + 1))
+        } else if line + 1 < lines.len() {
+            Some((line + 1, 0))
+        } else {
+            None
+        }
+    }
+
+    fn previous_position(lines: &[String], (line, column): Position) -> Option<Position> {
+        if column > 0 {
+            Some((line, column - 1))
+        } else if line > 0 {
+            Some((line - 1, line_length(lines, line - 1)))
+        } else {
+            None
+        }
+    }
+
+    fn is_empty_line(lines: &[String], (line, _): Position) -> bool {
+        lines[line].is_empty()
+    }
+
+    fn word_forward(lines: &[String], start: Position) -> Position {
+        let class = character_class(lines, start);
+        let mut position = start;
+        if class != CharacterClass::Whitespace {
+            loop {
+                match next_position(lines, position) {
+                    Some(next) if character_class(lines, next) == class => position = next,
+                    Some(next) => {
+                        position = next;
+                        break;
+                    }
+                    None => return (position.0, line_length(lines, position.0)),
+                }
+            }
+        }
+        while character_class(lines, position) == CharacterClass::Whitespace {
+            if is_empty_line(lines, position) && position.0 != start.0 {
+                break;
+            }
+            match next_position(lines, position) {
+                Some(next) => position = next,
+                None => break,
+            }
+        }
+        position
+    }
+
+    fn word_end(lines: &[String], start: Position) -> Position {
+        let mut position = match next_position(lines, start) {
+            Some(next) => next,
+            None => return start,
+        };
+        while character_class(lines, position) == CharacterClass::Whitespace {
+            match next_position(lines, position) {
+                Some(next) => position = next,
+                None => return start,
+            }
+        }
+        let class = character_class(lines, position);
+        while let Some(next) = next_position(lines, position) {
+            if character_class(lines, next) != class {
+                break;
+            }
+            position = next;
+        }
+        position
+    }
+
+    fn word_backward(lines: &[String], start: Position) -> Position {
+        let mut position = match previous_position(lines, start) {
+            Some(previous) => previous,
+            None => return start,
+        };
+        while character_class(lines, position) == CharacterClass::Whitespace
+            && !is_empty_line(lines, position)
+        {
+            match previous_position(lines, position) {
+                Some(previous) => position = previous,
+                None => return position,
+            }
+        }
+        let class = character_class(lines, position);
+        while let Some(previous) = previous_position(lines, position) {
+            if previous.0 != position.0 || character_class(lines, previous) != class {
+                break;
+            }
+            position = previous;
+        }
+        position
+    }
+
+    fn first_non_blank(lines: &[String], line: usize) -> usize {
+        lines[line]
+            .chars()
+            .position(|character| !character.is_whitespace())
+            .unwrap_or(0)
+    }
+
+    fn clamp_to_normal(lines: &[String], (line, column): Position) -> Position {
+        (line, column.min(line_length(lines, line).saturating_sub(1)))
+    }
+
+    fn get_position(cursor: &Cursor) -> Position {
+        (cursor.extender.line, cursor.extender.column)
+    }
+
+    fn ordered(first: Position, second: Position) -> (Position, Position) {
+        if first <= second {
+            (first, second)
+        } else {
+            (second, first)
+        }
+    }
+
+    /// Vim-style modal editing on top of `Cursor`.
+    #[derive(Debug, Default)]
+    pub struct Vim {
+        pub mode: Mode,
+        count: Option<usize>,
+        operator: Option<(Operator, Option<usize>)>,
+        prefix: Option<char>,
+        register: Option<char>,
+        registers: HashMap<char, Register>,
+        desired_column: usize,
+        visual_anchor: Position,
+        visual_head: Position,
+        recording: Vec<Input>,
+        last_change: Vec<Input>,
+        is_insert_repeatable: bool,
+        is_replaying: bool,
+    }
+
+    impl Vim {
+        pub fn new() -> Vim {
+            Vim::default()
+        }
+
+        /// Pending count, register and operator, like Vim's `showcmd`.
+        pub fn pending_keys(&self) -> String {
+            let mut keys = String::new();
+            if let Some(register) = self.register {
+                keys.push('"');
+                keys.push(register);
+            }
+            if let Some((operator, count)) = self.operator {
+                if let Some(count) = count {
+                    keys.push_str(&count.to_string());
+                }
+                keys.push(match operator {
+                    Operator::Delete => 'd',
+                    Operator::Change => 'c',
+                    Operator::Yank => 'y',
+                });
+            }
+            if let Some(count) = self.count {
+                keys.push_str(&count.to_string());
+            }
+            if let Some(prefix) = self.prefix {
+                keys.push(prefix);
+            }
+            keys
+        }
+
+        pub fn handle(&mut self, cursor: &mut Cursor, input: Input) {
+            if !self.is_replaying && self.mode != Mode::Visual && self.mode != Mode::VisualLine {
+                self.recording.push(input.clone());
+            }
+            match self.mode {
+                Mode::Insert => self.handle_insert(cursor, input),
+                Mode::Normal => self.handle_normal(cursor, input),
+                Mode::Visual | Mode::VisualLine => self.handle_visual(cursor, input),
+            }
+        }
+
+        fn handle_insert(&mut self, cursor: &mut Cursor, input: Input) {
+            match input {
+                Input::Character(character) => cursor.add(character),
+                Input::Key(key) => match key.as_str() {
+                    "escape" => {
+                        self.mode = Mode::Normal;
+                        let (line, column) = get_position(cursor);
+                        self.move_cursor(cursor, (line, column.saturating_sub(1)));
+                        if !self.is_replaying {
+                            if self.is_insert_repeatable {
+                                self.last_change = mem::take(&mut self.recording);
+                            } else {
+                                self.recording.clear();
+                            }
+                        }
+                    }
+                    "return" => cursor.new_line(),
+                    "backspace" => cursor.backspace(),
+                    "delete" => cursor.delete(),
+                    "left" => cursor.left(false),
+                    "right" => cursor.right(false),
+                    "up" => cursor.up(false),
+                    "down" => cursor.down(false),
+                    _ => {}
+                },
+            }
+        }
+
+        fn key_to_character(key: &str) -> Option<char> {
+            match key {
+                "left" | "backspace" => Some('h'),
+                "right" => Some('l'),
+                "up" => Some('k'),
+                "down" | "return" => Some('j'),
+                "delete" => Some('x'),
+                _ => None,
+            }
+        }
+
+        /// Handles the `"` and `g` prefixes and count digits shared by the
+        /// normal and visual modes. Returns the character if it still needs
+        /// handling.
+        fn handle_prefix(&mut self, character: char) -> Option<char> {
+            match self.prefix.take() {
+                Some('"') => {
+                    self.register = Some(character);
+                    return None;
+                }
+                Some('g') if character == 'g' => return Some('g'),
+                Some(_) => {
+                    self.reset();
+                    return None;
+                }
+                None => {}
+            }
+            match character {
+                '0'..='9' if character != '0' || self.count.is_some() => {
+                    let digit = character.to_digit(10).unwrap() as usize;
+                    self.count = Some(self.count.unwrap_or(0) * 10 + digit);
+                    None
+                }
+                '"' | 'g' => {
+                    self.prefix = Some(character);
+                    None
+                }
+                _ => Some(character),
+            }
+        }
+
+        fn handle_normal(&mut self, cursor: &mut Cursor, input: Input) {
+            let character = match input {
+                Input::Character(character) => character,
+                Input::Key(key) => {
+                    if key == "escape" {
+                        self.finish(cursor, false);
+                        return;
+                    }
+                    match Vim::key_to_character(&key) {
+                        Some(character) => character,
+                        None => return,
+                    }
+                }
+            };
+            let character = match self.handle_prefix(character) {
+                Some(character) => character,
+                None => return,
+            };
+            if let Some(motion) = Motion::from_character(character) {
+                return self.motion(cursor, motion);
+            }
+            match character {
+                'g' => self.motion(cursor, Motion::FirstLine),
+                'd' => self.operator(cursor, Operator::Delete),
+                'c' => self.operator(cursor, Operator::Change),
+                'y' => self.operator(cursor, Operator::Yank),
+                'x' => {
+                    let count = self.count.take().unwrap_or(1);
+                    let (line, column) = get_position(cursor);
+                    let to = (column + count).min(line_length(&cursor.lines, line));
+                    let is_change = to > column;
+                    if is_change {
+                        self.operate_range(cursor, Operator::Delete, (line, column), (line, to));
+                    }
+                    self.finish(cursor, is_change);
+                }
+                'i' | 'a' | 'I' | 'A' | 'o' | 'O' => {
+                    let (line, column) = get_position(cursor);
+                    let length = line_length(&cursor.lines, line);
+                    match character {
+                        'a' => cursor.move_to(line, (column + 1).min(length), false),
+                        'I' => cursor.move_to(line, first_non_blank(&cursor.lines, line), false),
+                        'A' => cursor.move_to(line, length, false),
+                        'o' => {
+                            cursor.move_to(line, length, false);
+                            cursor.new_line();
+                        }
+                        'O' => {
+                            cursor.move_to(line, 0, false);
+                            cursor.new_line();
+                            cursor.up(false);
+                        }
+                        _ => {}
+                    }
+                    self.start_insert(true);
+                }
+                'v' => self.start_visual(cursor, Mode::Visual),
+                'V' => self.start_visual(cursor, Mode::VisualLine),
+                'p' => self.paste(cursor, true),
+                'P' => self.paste(cursor, false),
+                '.' => self.repeat(cursor),
+                _ => self.finish(cursor, false),
+            }
+        }
+
+        fn handle_visual(&mut self, cursor: &mut Cursor, input: Input) {
+            let character = match input {
+                Input::Character(character) => character,
+                Input::Key(key) => {
+                    if key == "escape" {
+                        self.stop_visual(cursor);
+                        return;
+                    }
+                    match Vim::key_to_character(&key) {
+                        Some(character) => character,
+                        None => return,
+                    }
+                }
+            };
+            let character = match self.handle_prefix(character) {
+                Some(character) => character,
+                None => return,
+            };
+            let motion = match character {
+                'g' => Some(Motion::FirstLine),
+                _ => Motion::from_character(character),
+            };
+            if let Some(motion) = motion {
+                let count = self.count.take();
+                self.visual_head = self.get_target(&cursor.lines, self.visual_head, motion, count);
+                self.show_visual(cursor);
+                return;
+            }
+            let operator = match character {
+                'd' | 'x' => Operator::Delete,
+                'c' => Operator::Change,
+                'y' => Operator::Yank,
+                'o' => {
+                    mem::swap(&mut self.visual_anchor, &mut self.visual_head);
+                    self.show_visual(cursor);
+                    return;
+                }
+                'v' | 'V' => {
+                    let mode = if character == 'v' {
+                        Mode::Visual
+                    } else {
+                        Mode::VisualLine
+                    };
+                    if self.mode == mode {
+                        self.stop_visual(cursor);
+                    } else {
+                        self.mode = mode;
+                        self.show_visual(cursor);
+                    }
+                    return;
+                }
+                _ => return,
+            };
+            let (from, to) = ordered(self.visual_anchor, self.visual_head);
+            let is_linewise = self.mode == Mode::VisualLine;
+            self.mode = Mode::Normal;
+            if is_linewise {
+                self.operate_lines(cursor, operator, from.0, to.0);
+            } else {
+                let end = (to.0, (to.1 + 1).min(line_length(&cursor.lines, to.0)));
+                self.operate_range(cursor, operator, from, end);
+            }
+            if operator == Operator::Change {
+                self.start_insert(false);
+            } else {
+                self.finish(cursor, false);
+            }
+        }
+
+        fn start_visual(&mut self, cursor: &mut Cursor, mode: Mode) {
+            self.count = None;
+            self.recording.clear();
+            self.mode = mode;
+            self.visual_anchor = get_position(cursor);
+            self.visual_head = self.visual_anchor;
+            self.show_visual(cursor);
+        }
+
+        fn stop_visual(&mut self, cursor: &mut Cursor) {
+            self.mode = Mode::Normal;
+            let head = self.visual_head;
+            self.move_cursor(cursor, head);
+            self.finish(cursor, false);
+        }
+
+        /// Mirrors the visual selection into the cursor so that it is drawn
+        /// like any other selection. Vim selections include the character
+        /// under the cursor, `Cursor` selections do not.
+        fn show_visual(&self, cursor: &mut Cursor) {
+            let (from, to) = ordered(self.visual_anchor, self.visual_head);
+            if self.mode == Mode::VisualLine {
+                cursor.move_to(from.0, 0, false);
+                cursor.move_to(to.0, line_length(&cursor.lines, to.0), true);
+            } else {
+                cursor.move_to(from.0, from.1, false);
+                cursor.move_to(to.0, (to.1 + 1).min(line_length(&cursor.lines, to.0)), true);
+            }
+        }
+
+        fn start_insert(&mut self, is_repeatable: bool) {
+            self.mode = Mode::Insert;
+            self.is_insert_repeatable = is_repeatable;
+            self.count = None;
+            self.operator = None;
+            self.prefix = None;
+            self.register = None;
+        }
+
+        fn reset(&mut self) {
+            self.count = None;
+            self.operator = None;
+            self.prefix = None;
+            self.register = None;
+        }
+
+        fn finish(&mut self, cursor: &mut Cursor, is_change: bool) {
+            self.reset();
+            if !self.is_replaying {
+                if is_change {
+                    self.last_change = mem::take(&mut self.recording);
+                } else {
+                    self.recording.clear();
+                }
+            }
+            let position = clamp_to_normal(&cursor.lines, get_position(cursor));
+            cursor.move_to(position.0, position.1, false);
+        }
+
+        fn move_cursor(&mut self, cursor: &mut Cursor, position: Position) {
+            let (line, column) = clamp_to_normal(&cursor.lines, position);
+            cursor.move_to(line, column, false);
+        }
+
+        fn get_target(
+            &mut self,
+            lines: &[String],
+            start: Position,
+            motion: Motion,
+            count: Option<usize>,
+        ) -> Position {
+            let (line, column) = start;
+            let times = count.unwrap_or(1);
+            let last_line = lines.len() - 1;
+            let target = match motion {
+                Motion::Left => (line, column.saturating_sub(times)),
+                Motion::Right => (line, (column + times).min(line_length(lines, line))),
+                Motion::Up | Motion::Down => {
+                    let line = if motion == Motion::Up {
+                        line.saturating_sub(times)
+                    } else {
+                        (line + times).min(last_line)
+                    };
+                    return (line, self.desired_column.min(line_length(lines, line)));
+                }
+                Motion::WordForward => {
+                    (0..times).fold(start, |position, _| word_forward(lines, position))
+                }
+                Motion::WordBackward => {
+                    (0..times).fold(start, |position, _| word_backward(lines, position))
+                }
+                Motion::WordEnd => (0..times).fold(start, |position, _| word_end(lines, position)),
+                Motion::LineStart => (line, 0),
+                Motion::LineEnd => {
+                    let line = (line + times - 1).min(last_line);
+                    self.desired_column = usize::MAX;
+                    return (line, line_length(lines, line).saturating_sub(1));
+                }
+                Motion::FirstLine | Motion::LastLine => {
+                    let line = match count {
+                        Some(count) => (count.max(1) - 1).min(last_line),
+                        None if motion == Motion::FirstLine => 0,
+                        None => last_line,
+                    };
+                    (line, first_non_blank(lines, line))
+                }
+            };
+            self.desired_column = target.1;
+            target
+        }
+
+        fn motion(&mut self, cursor: &mut Cursor, motion: Motion) {
+            let count = self.count.take();
+            let start = get_position(cursor);
+            match self.operator.take() {
+                Some((operator, operator_count)) => {
+                    let count = match (operator_count, count) {
+                        (None, None) => None,
+                        (first, second) => Some(first.unwrap_or(1) * second.unwrap_or(1)),
+                    };
+                    self.operator_motion(cursor, operator, motion, count);
+                }
+                None => {
+                    let target = self.get_target(&cursor.lines, start, motion, count);
+                    self.move_cursor(cursor, target);
+                    self.finish(cursor, false);
+                }
+            }
+        }
+
+        fn operator(&mut self, cursor: &mut Cursor, operator: Operator) {
+            let count = self.count.take();
+            match self.operator.take() {
+                Some((pending, operator_count)) if pending == operator => {
+                    let count = operator_count.unwrap_or(1) * count.unwrap_or(1);
+                    let line = cursor.extender.line;
+                    let last_line = (line + count - 1).min(cursor.lines.len() - 1);
+                    self.operate_lines(cursor, operator, line, last_line);
+                    if operator == Operator::Change {
+                        self.start_insert(true);
+                    } else {
+                        self.finish(cursor, operator != Operator::Yank);
+                    }
+                }
+                Some(_) => self.finish(cursor, false),
+                None => self.operator = Some((operator, count)),
+            }
+        }
+
+        fn operator_motion(
+            &mut self,
+            cursor: &mut Cursor,
+            operator: Operator,
+            motion: Motion,
+            count: Option<usize>,
+        ) {
+            let start = get_position(cursor);
+            let motion = if operator == Operator::Change
+                && motion == Motion::WordForward
+                && character_class(&cursor.lines, start) != CharacterClass::Whitespace
+            {
+                Motion::WordEnd
+            } else {
+                motion
+            };
+            let target = self.get_target(&cursor.lines, start, motion, count);
+            let (from, to) = ordered(start, target);
+            if motion.is_linewise() {
+                self.operate_lines(cursor, operator, from.0, to.0);
+            } else {
+                let to = if motion.is_inclusive() {
+                    (to.0, (to.1 + 1).min(line_length(&cursor.lines, to.0)))
+                } else if motion == Motion::WordForward && to.0 > from.0 {
+                    (to.0 - 1, line_length(&cursor.lines, to.0 - 1))
+                } else {
+                    to
+                };
+                self.operate_range(cursor, operator, from, to);
+            }
+            if operator == Operator::Change {
+                self.start_insert(true);
+            } else {
+                self.finish(cursor, operator != Operator::Yank);
+            }
+        }
+
+        fn operate_range(
+            &mut self,
+            cursor: &mut Cursor,
+            operator: Operator,
+            from: Position,
+            to: Position,
+        ) {
+            cursor.move_to(from.0, from.1, false);
+            cursor.move_to(to.0, to.1, true);
+            let text = cursor.selected_text();
+            self.store(text, false, operator == Operator::Yank);
+            if operator == Operator::Yank {
+                cursor.move_to(from.0, from.1, false);
+            } else {
+                cursor.delete();
+            }
+        }
+
+        fn operate_lines(
+            &mut self,
+            cursor: &mut Cursor,
+            operator: Operator,
+            from: usize,
+            to: usize,
+        ) {
+            let mut text = cursor.lines[from..=to].join("\n");
+            text.push('\n');
+            self.store(text, true, operator == Operator::Yank);
+            match operator {
+                Operator::Yank => {
+                    let column = cursor.extender.column;
+                    self.move_cursor(cursor, (from, column));
+                }
+                Operator::Change => {
+                    cursor.move_to(from, 0, false);
+                    cursor.move_to(to, line_length(&cursor.lines, to), true);
+                    cursor.delete();
+                }
+                Operator::Delete => {
+                    let last_line = cursor.lines.len() - 1;
+                    if to < last_line {
+                        cursor.move_to(from, 0, false);
+                        cursor.move_to(to + 1, 0, true);
+                    } else if from > 0 {
+                        cursor.move_to(from - 1, line_length(&cursor.lines, from - 1), false);
+                        cursor.move_to(to, line_length(&cursor.lines, to), true);
+                    } else {
+                        cursor.select_all();
+                    }
+                    cursor.delete();
+                    let line = from.min(cursor.lines.len() - 1);
+                    cursor.move_to(line, first_non_blank(&cursor.lines, line), false);
+                }
+            }
+        }
+
+        /// Stores deleted or yanked text in the unnamed register and the
+        /// selected one; uppercase register names append.
+        fn store(&mut self, text: String, linewise: bool, is_yank: bool) {
+            let register = Register { text, linewise };
+            let unnamed = match self.register.take() {
+                Some(name) if name.is_ascii_uppercase() => {
+                    let entry =
+                        self.registers
+                            .entry(name.to_ascii_lowercase())
+                            .or_insert(Register {
+                                text: String::new(),
+                                linewise,
+                            });
+                    // Mixing in whole lines makes the register linewise, and
+                    // the characters become lines of their own.
+                    if linewise && !entry.linewise && !entry.text.is_empty() {
+                        entry.text.push('\n');
+                    }
+                    entry.text.push_str(&register.text);
+                    if entry.linewise && !linewise {
+                        entry.text.push('\n');
+                    }
+                    entry.linewise |= linewise;
+                    entry.clone()
+                }
+                Some(name) if name != '"' => {
+                    self.registers.insert(name, register.clone());
+                    register
+                }
+                _ => {
+                    if is_yank {
+                        self.registers.insert('0', register.clone());
+                    }
+                    register
+                }
+            };
+            self.registers.insert('"', unnamed);
+        }
+
+        fn paste(&mut self, cursor: &mut Cursor, after: bool) {
+            let count = self.count.take().unwrap_or(1);
+            let name = self.register.take().unwrap_or('"').to_ascii_lowercase();
+            let register = match self.registers.get(&name) {
+                Some(register) => register.clone(),
+                None => return self.finish(cursor, false),
+            };
+            let (line, column) = get_position(cursor);
+            let text = register.text.repeat(count);
+            if register.linewise {
+                let target = if after {
+                    cursor.move_to(line, line_length(&cursor.lines, line), false);
+                    cursor.insert(&format!("\n{}", text.strip_suffix('\n').unwrap_or(&text)));
+                    line + 1
+                } else {
+                    cursor.move_to(line, 0, false);
+                    cursor.insert(&text);
+                    line
+                };
+                cursor.move_to(target, first_non_blank(&cursor.lines, target), false);
+            } else {
+                let column = if after && line_length(&cursor.lines, line) > 0 {
+                    column + 1
+                } else {
+                    column
+                };
+                cursor.move_to(line, column, false);
+                cursor.insert(&text);
+                cursor.left(false);
+            }
+            self.finish(cursor, true);
+        }
+
+        fn repeat(&mut self, cursor: &mut Cursor) {
+            let count = self.count.take().unwrap_or(1);
+            self.recording.clear();
+            let change = self.last_change.clone();
+            self.is_replaying = true;
+            for _ in 0..count {
+                for input in change.iter() {
+                    self.handle(cursor, input.clone());
+                }
+            }
+            self.is_replaying = false;
+            self.finish(cursor, false);
+        }
+    }
+}
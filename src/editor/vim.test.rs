@@ -0,0 +1,351 @@
+#[path = "cursor.rs"]
+mod cursor;
+
+#[path = "vim.rs"]
+mod vim;
+
+#[cfg(test)]
+mod tests {
+    use super::cursor::cursor::Cursor;
+    use super::vim::vim::{Input, Mode, Vim};
+
+    /// Feeds keys like Vim's `:normal`, with `<esc>`, `<cr>` and `<bs>` for
+    /// the special keys.
+    fn feed(vim: &mut Vim, cursor: &mut Cursor, keys: &str) {
+        let mut rest = keys;
+        while let Some(character) = rest.chars().next() {
+            let special = [
+                ("<esc>", "escape"),
+                ("<cr>", "return"),
+                ("<bs>", "backspace"),
+            ]
+            .iter()
+            .find(|(notation, _)| rest.starts_with(notation));
+            if let Some((notation, key)) = special {
+                vim.handle(cursor, Input::Key(key.to_string()));
+                rest = &rest[notation.len()..];
+            } else {
+                vim.handle(cursor, Input::Character(character));
+                rest = &rest[character.len_utf8()..];
+            }
+        }
+    }
+    fn run(lines: Vec<&str>, keys: &str) -> (Vim, Cursor) {
+        let mut vim = Vim::new();
+        let mut cursor = Cursor::new(lines.into_iter().map(String::from).collect());
+        feed(&mut vim, &mut cursor, keys);
+        (vim, cursor)
+    }
+    fn lines(cursor: &Cursor) -> Vec<&str> {
+        cursor.lines.iter().map(|line| line.as_str()).collect()
+    }
+    fn position(cursor: &Cursor) -> (usize, usize) {
+        (cursor.current.line, cursor.current.column)
+    }
+    #[test]
+    fn starts_in_normal_mode() {
+        assert_eq!(Vim::new().mode, Mode::Normal);
+    }
+    #[test]
+    fn hjkl() {
+        let (_, cursor) = run(vec!["abc", "def"], "lljh");
+        assert_eq!(position(&cursor), (1, 1));
+        let (_, cursor) = run(vec!["abc", "def"], "jk");
+        assert_eq!(position(&cursor), (0, 0));
+    }
+    #[test]
+    fn l_stops_on_last_character() {
+        let (_, cursor) = run(vec!["abc"], "lllll");
+        assert_eq!(position(&cursor), (0, 2));
+    }
+    #[test]
+    fn vertical_motion_remembers_column() {
+        let (_, cursor) = run(vec!["abcd", "a", "abcd"], "lllj");
+        assert_eq!(position(&cursor), (1, 0));
+        let (_, cursor) = run(vec!["abcd", "a", "abcd"], "llljj");
+        assert_eq!(position(&cursor), (2, 3));
+    }
+    #[test]
+    fn counts() {
+        let (_, cursor) = run(vec!["abcdef"], "3l");
+        assert_eq!(position(&cursor), (0, 3));
+        let (_, cursor) = run(vec!["a"; 20], "12j");
+        assert_eq!(position(&cursor), (12, 0));
+    }
+    #[test]
+    fn word_motions() {
+        let (_, cursor) = run(vec!["foo.bar baz"], "w");
+        assert_eq!(position(&cursor), (0, 3));
+        let (_, cursor) = run(vec!["foo.bar baz"], "ww");
+        assert_eq!(position(&cursor), (0, 4));
+        let (_, cursor) = run(vec!["foo.bar baz"], "3w");
+        assert_eq!(position(&cursor), (0, 8));
+        let (_, cursor) = run(vec!["foo.bar baz"], "e");
+        assert_eq!(position(&cursor), (0, 2));
+        let (_, cursor) = run(vec!["foo.bar baz"], "$b");
+        assert_eq!(position(&cursor), (0, 8));
+        let (_, cursor) = run(vec!["foo.bar baz"], "$bb");
+        assert_eq!(position(&cursor), (0, 4));
+    }
+    #[test]
+    fn word_motions_across_lines() {
+        let (_, cursor) = run(vec!["foo", "  bar"], "w");
+        assert_eq!(position(&cursor), (1, 2));
+        let (_, cursor) = run(vec!["foo", "", "bar"], "w");
+        assert_eq!(position(&cursor), (1, 0));
+        let (_, cursor) = run(vec!["foo", "bar"], "jb");
+        assert_eq!(position(&cursor), (0, 0));
+        let (_, cursor) = run(vec!["foo", "bar"], "ee");
+        assert_eq!(position(&cursor), (1, 2));
+    }
+    #[test]
+    fn line_start_and_end() {
+        let (_, cursor) = run(vec!["abc"], "$");
+        assert_eq!(position(&cursor), (0, 2));
+        let (_, cursor) = run(vec!["abc"], "$0");
+        assert_eq!(position(&cursor), (0, 0));
+    }
+    #[test]
+    fn first_and_last_line() {
+        let (_, cursor) = run(vec!["a", "b", "  c"], "G");
+        assert_eq!(position(&cursor), (2, 2));
+        let (_, cursor) = run(vec!["a", "b", "c"], "Ggg");
+        assert_eq!(position(&cursor), (0, 0));
+        let (_, cursor) = run(vec!["a", "b", "c"], "2G");
+        assert_eq!(position(&cursor), (1, 0));
+        let (_, cursor) = run(vec!["a", "b", "c"], "3gg");
+        assert_eq!(position(&cursor), (2, 0));
+    }
+    #[test]
+    fn delete_word() {
+        let (_, cursor) = run(vec!["foo bar baz"], "dw");
+        assert_eq!(lines(&cursor), vec!["bar baz"]);
+    }
+    #[test]
+    fn delete_word_at_end_of_line_keeps_next_line() {
+        let (_, cursor) = run(vec!["foo bar", "baz"], "wdw");
+        assert_eq!(lines(&cursor), vec!["foo ", "baz"]);
+    }
+    #[test]
+    fn delete_with_counts() {
+        let (_, cursor) = run(vec!["a b c d e"], "2dw");
+        assert_eq!(lines(&cursor), vec!["c d e"]);
+        let (_, cursor) = run(vec!["a b c d e"], "d3w");
+        assert_eq!(lines(&cursor), vec!["d e"]);
+        let (_, cursor) = run(vec!["a b c d e f g"], "2d2w");
+        assert_eq!(lines(&cursor), vec!["e f g"]);
+    }
+    #[test]
+    fn delete_to_end_of_line() {
+        let (_, cursor) = run(vec!["abcdef"], "lld$");
+        assert_eq!(lines(&cursor), vec!["ab"]);
+        assert_eq!(position(&cursor), (0, 1));
+    }
+    #[test]
+    fn delete_to_word_end() {
+        let (_, cursor) = run(vec!["foo bar"], "de");
+        assert_eq!(lines(&cursor), vec![" bar"]);
+    }
+    #[test]
+    fn delete_line() {
+        let (_, cursor) = run(vec!["a", "b", "c"], "jdd");
+        assert_eq!(lines(&cursor), vec!["a", "c"]);
+        assert_eq!(position(&cursor), (1, 0));
+    }
+    #[test]
+    fn delete_last_line() {
+        let (_, cursor) = run(vec!["a", "b"], "jdd");
+        assert_eq!(lines(&cursor), vec!["a"]);
+        assert_eq!(position(&cursor), (0, 0));
+    }
+    #[test]
+    fn delete_all_lines() {
+        let (_, cursor) = run(vec!["a", "b"], "5dd");
+        assert_eq!(lines(&cursor), vec![""]);
+    }
+    #[test]
+    fn delete_lines_with_motion() {
+        let (_, cursor) = run(vec!["a", "b", "c", "d"], "jdj");
+        assert_eq!(lines(&cursor), vec!["a", "d"]);
+        let (_, cursor) = run(vec!["a", "b", "c", "d"], "jdG");
+        assert_eq!(lines(&cursor), vec!["a"]);
+    }
+    #[test]
+    fn delete_characters() {
+        let (_, cursor) = run(vec!["abcdef"], "x");
+        assert_eq!(lines(&cursor), vec!["bcdef"]);
+        let (_, cursor) = run(vec!["abcdef"], "l3x");
+        assert_eq!(lines(&cursor), vec!["aef"]);
+        let (_, cursor) = run(vec!["ab"], "$5x");
+        assert_eq!(lines(&cursor), vec!["a"]);
+        assert_eq!(position(&cursor), (0, 0));
+    }
+    #[test]
+    fn change_word() {
+        let (vim, cursor) = run(vec!["foo bar"], "cwxy");
+        assert_eq!(vim.mode, Mode::Insert);
+        assert_eq!(lines(&cursor), vec!["xy bar"]);
+    }
+    #[test]
+    fn change_line() {
+        let (vim, cursor) = run(vec!["a", "bcd", "e"], "jccx<esc>");
+        assert_eq!(vim.mode, Mode::Normal);
+        assert_eq!(lines(&cursor), vec!["a", "x", "e"]);
+    }
+    #[test]
+    fn insert_and_escape() {
+        let (vim, cursor) = run(vec!["ac"], "lib<esc>");
+        assert_eq!(vim.mode, Mode::Normal);
+        assert_eq!(lines(&cursor), vec!["abc"]);
+        assert_eq!(position(&cursor), (0, 1));
+    }
+    #[test]
+    fn insert_keys() {
+        let (_, cursor) = run(vec!["ab"], "ix<cr>y<bs>z<esc>");
+        assert_eq!(lines(&cursor), vec!["x", "zab"]);
+    }
+    #[test]
+    fn append() {
+        let (_, cursor) = run(vec!["ac"], "ab<esc>");
+        assert_eq!(lines(&cursor), vec!["abc"]);
+        let (_, cursor) = run(vec!["ab"], "Ac<esc>");
+        assert_eq!(lines(&cursor), vec!["abc"]);
+        let (_, cursor) = run(vec!["  b"], "$Ia<esc>");
+        assert_eq!(lines(&cursor), vec!["  ab"]);
+    }
+    #[test]
+    fn open_line() {
+        let (_, cursor) = run(vec!["a", "c"], "ob<esc>");
+        assert_eq!(lines(&cursor), vec!["a", "b", "c"]);
+        let (_, cursor) = run(vec!["b", "c"], "Oa<esc>");
+        assert_eq!(lines(&cursor), vec!["a", "b", "c"]);
+    }
+    #[test]
+    fn yank_and_paste_line() {
+        let (_, cursor) = run(vec!["a", "b"], "yyjp");
+        assert_eq!(lines(&cursor), vec!["a", "b", "a"]);
+        assert_eq!(position(&cursor), (2, 0));
+        let (_, cursor) = run(vec!["a", "b"], "jyykP");
+        assert_eq!(lines(&cursor), vec!["b", "a", "b"]);
+        assert_eq!(position(&cursor), (0, 0));
+    }
+    #[test]
+    fn yank_and_paste_word() {
+        let (_, cursor) = run(vec!["foo bar"], "yw$p");
+        assert_eq!(lines(&cursor), vec!["foo barfoo "]);
+        let (_, cursor) = run(vec!["foo bar"], "ywP");
+        assert_eq!(lines(&cursor), vec!["foo foo bar"]);
+    }
+    #[test]
+    fn delete_and_paste() {
+        let (_, cursor) = run(vec!["ab"], "xp");
+        assert_eq!(lines(&cursor), vec!["ba"]);
+        let (_, cursor) = run(vec!["a", "b"], "ddp");
+        assert_eq!(lines(&cursor), vec!["b", "a"]);
+    }
+    #[test]
+    fn paste_with_count() {
+        let (_, cursor) = run(vec!["a"], "yy3p");
+        assert_eq!(lines(&cursor), vec!["a", "a", "a", "a"]);
+    }
+    #[test]
+    fn named_registers() {
+        let (_, cursor) = run(vec!["a", "b"], "\"ayyjdd\"ap");
+        assert_eq!(lines(&cursor), vec!["a", "a"]);
+        let (_, cursor) = run(vec!["a", "b"], "\"ayyjdd\"ap\"\"p");
+        assert_eq!(lines(&cursor), vec!["a", "a", "b"]);
+    }
+    #[test]
+    fn append_to_register() {
+        let (_, cursor) = run(vec!["a", "b", "c"], "\"ayyj\"Ayyj\"aP");
+        assert_eq!(lines(&cursor), vec!["a", "b", "a", "b", "c"]);
+    }
+    #[test]
+    fn appending_lines_and_characters_makes_a_linewise_register() {
+        let (_, cursor) = run(vec!["héé wörd", "ünï"], "\"ayw\"Ayyj\"ap");
+        assert_eq!(lines(&cursor), vec!["héé wörd", "ünï", "héé ", "héé wörd"]);
+        let (_, cursor) = run(vec!["héé wörd", "ünï"], "\"ayyj\"Ayw\"ap");
+        assert_eq!(lines(&cursor), vec!["héé wörd", "ünï", "héé wörd", "ünï"]);
+    }
+    #[test]
+    fn yank_register_survives_delete() {
+        let (_, cursor) = run(vec!["a", "b"], "yyjdd\"0p");
+        assert_eq!(lines(&cursor), vec!["a", "a"]);
+    }
+    #[test]
+    fn repeat_delete() {
+        let (_, cursor) = run(vec!["a b c d"], "dw..");
+        assert_eq!(lines(&cursor), vec!["d"]);
+        let (_, cursor) = run(vec!["abcdef"], "2x.");
+        assert_eq!(lines(&cursor), vec!["ef"]);
+    }
+    #[test]
+    fn repeat_change() {
+        let (_, cursor) = run(vec!["foo bar"], "cwbaz<esc>w.");
+        assert_eq!(lines(&cursor), vec!["baz baz"]);
+    }
+    #[test]
+    fn repeat_insert_with_count() {
+        let (_, cursor) = run(vec![""], "ia<esc>3.");
+        assert_eq!(lines(&cursor), vec!["aaaa"]);
+    }
+    #[test]
+    fn repeat_ignores_motions() {
+        let (_, cursor) = run(vec!["abcdef"], "xll.");
+        assert_eq!(lines(&cursor), vec!["bcef"]);
+    }
+    #[test]
+    fn visual_delete() {
+        let (vim, cursor) = run(vec!["abcdef"], "lvlld");
+        assert_eq!(vim.mode, Mode::Normal);
+        assert_eq!(lines(&cursor), vec!["aef"]);
+    }
+    #[test]
+    fn visual_selection_is_drawn_inclusive() {
+        let (vim, cursor) = run(vec!["abcdef"], "lvl");
+        assert_eq!(vim.mode, Mode::Visual);
+        assert_eq!(cursor.selected_text(), "bc");
+    }
+    #[test]
+    fn visual_backwards_and_swap() {
+        let (_, cursor) = run(vec!["abcdef"], "$vhhd");
+        assert_eq!(lines(&cursor), vec!["abc"]);
+        let (_, cursor) = run(vec!["abcdef"], "llvlohd");
+        assert_eq!(lines(&cursor), vec!["aef"]);
+    }
+    #[test]
+    fn visual_line_yank_and_paste() {
+        let (vim, cursor) = run(vec!["a", "b", "c"], "Vjy");
+        assert_eq!(vim.mode, Mode::Normal);
+        assert_eq!(position(&cursor), (0, 0));
+        let (_, cursor) = run(vec!["a", "b", "c"], "VjyGp");
+        assert_eq!(lines(&cursor), vec!["a", "b", "c", "a", "b"]);
+    }
+    #[test]
+    fn visual_change() {
+        let (vim, cursor) = run(vec!["foo bar"], "vecx<esc>");
+        assert_eq!(vim.mode, Mode::Normal);
+        assert_eq!(lines(&cursor), vec!["x bar"]);
+    }
+    #[test]
+    fn visual_escape() {
+        let (vim, cursor) = run(vec!["abc"], "vl<esc>");
+        assert_eq!(vim.mode, Mode::Normal);
+        assert_eq!(cursor.current, cursor.extender);
+        assert_eq!(position(&cursor), (0, 1));
+    }
+    #[test]
+    fn pending_keys() {
+        let (vim, _) = run(vec!["abc"], "\"a2d3");
+        assert_eq!(vim.pending_keys(), "\"a2d3");
+        let (vim, _) = run(vec!["abc"], "2d<esc>");
+        assert_eq!(vim.pending_keys(), "");
+    }
+    #[test]
+    fn mode_labels() {
+        let (vim, _) = run(vec!["abc"], "V");
+        assert_eq!(vim.mode.label(), "-- VISUAL LINE --");
+        let (vim, _) = run(vec!["abc"], "i");
+        assert_eq!(vim.mode.label(), "-- INSERT --");
+    }
+}
@@ -2,10 +2,114 @@
 #[path = "editor/cursor.test.rs"]
 mod tests;
 
+#[cfg(test)]
+#[path = "editor/config.test.rs"]
+mod config_tests;
+
+#[cfg(test)]
+#[path = "editor/commands.test.rs"]
+mod commands_tests;
+
+#[cfg(test)]
+#[path = "editor/keymap.test.rs"]
+mod keymap_tests;
+
+#[cfg(test)]
+#[path = "editor/kill_ring.test.rs"]
+mod kill_ring_tests;
+
+#[cfg(test)]
+#[path = "editor/vim.test.rs"]
+mod vim_tests;
+
+#[cfg(test)]
+#[path = "editor/highlighting.test.rs"]
+mod highlighting_tests;
+
+#[cfg(test)]
+#[path = "editor/theme.test.rs"]
+mod theme_tests;
+
+#[cfg(test)]
+#[path = "editor/text_rendering.test.rs"]
+mod text_rendering_tests;
+
+#[cfg(test)]
+#[path = "editor/font.test.rs"]
+mod font_tests;
+
+#[cfg(test)]
+#[path = "editor/glyph_editor.test.rs"]
+mod glyph_editor_tests;
+
+#[cfg(test)]
+#[path = "editor/rendering.test.rs"]
+mod rendering_tests;
+
+#[cfg(test)]
+#[path = "editor/image_rendering.test.rs"]
+mod image_rendering_tests;
+
+#[cfg(test)]
+#[path = "editor/snapshot.test.rs"]
+mod snapshot_tests;
+
+#[cfg(test)]
+#[path = "editor/svg.test.rs"]
+mod svg_tests;
+
+#[cfg(test)]
+#[path = "editor/tty.test.rs"]
+mod tty_tests;
+
+#[cfg(test)]
+#[path = "editor/timers.test.rs"]
+mod timers_tests;
+
+#[cfg(test)]
+#[path = "editor/rasterizer.test.rs"]
+mod rasterizer_tests;
+
+#[cfg(test)]
+#[path = "editor/glyph_cache.test.rs"]
+mod glyph_cache_tests;
+
+#[cfg(test)]
+#[path = "editor/scrollbar.test.rs"]
+mod scrollbar_tests;
+
 #[path = "editor/ui.rs"]
 mod ui;
 use crate::ui::editor;
+use std::env;
 
 fn main() {
-    editor::run("./editorTestFile");
+    let arguments = env::args().skip(1).collect::<Vec<String>>();
+    let get_value = |flag: &str| {
+        arguments
+            .iter()
+            .position(|argument| argument == flag)
+            .map(|index| arguments.get(index + 1))
+    };
+    let scale = match get_value("--scale") {
+        None => 1.0,
+        Some(value) => match value.and_then(|value| value.parse::<f32>().ok()) {
+            Some(scale) if scale > 0.0 => scale,
+            _ => {
+                eprintln!("--scale needs a number greater than 0");
+                std::process::exit(2);
+            }
+        },
+    };
+    match get_value("--screenshot") {
+        Some(Some(output_path)) => editor::screenshot("./editorTestFile", output_path, scale),
+        Some(None) => {
+            eprintln!("--screenshot needs the path of the PNG to write");
+            std::process::exit(2);
+        }
+        None if arguments.iter().any(|argument| argument == "--tty") => {
+            editor::run_terminal("./editorTestFile")
+        }
+        None => editor::run("./editorTestFile"),
+    }
 }